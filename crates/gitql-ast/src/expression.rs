@@ -54,6 +54,7 @@ pub enum ExprKind {
     Column,
     Row,
     MemberAccess,
+    Subquery,
 }
 
 dyn_clone::clone_trait_object!(Expr);
@@ -661,7 +662,7 @@ impl Expr for InExpr {
     }
 
     fn expr_type(&self) -> Box<dyn DataType> {
-        self.values_type.clone()
+        Box::new(BoolType)
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -785,3 +786,34 @@ impl Expr for MemberAccessExpr {
         self
     }
 }
+
+#[derive(PartialEq, Clone)]
+pub enum SubqueryKind {
+    /// Subquery used as a single value, for example `WHERE x > (SELECT ...)`
+    Scalar,
+    /// Subquery used as a list of values, for example `WHERE x IN (SELECT ...)`
+    List,
+}
+
+/// Reference to an uncorrelated subquery stored in the [`crate::query::SelectQuery`] by name,
+/// the engine evaluates the subquery once before executing the query that use it
+#[derive(Clone)]
+pub struct SubqueryExpr {
+    pub name: String,
+    pub kind: SubqueryKind,
+    pub result_type: Box<dyn DataType>,
+}
+
+impl Expr for SubqueryExpr {
+    fn kind(&self) -> ExprKind {
+        ExprKind::Subquery
+    }
+
+    fn expr_type(&self) -> Box<dyn DataType> {
+        self.result_type.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
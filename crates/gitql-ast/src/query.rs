@@ -1,22 +1,60 @@
 use std::collections::HashMap;
 
 use crate::expression::Expr;
+use crate::statement::LimitStatement;
+use crate::statement::OffsetStatement;
+use crate::statement::OrderByStatement;
 use crate::statement::Statement;
 
 pub enum Query {
     Select(SelectQuery),
+    SetOperation(SetOperationQuery),
     GlobalVariableDecl(GlobalVariableDeclQuery),
     Do(DoQuery),
     DescribeTable(DescribeQuery),
     ShowTables,
+    Explain(ExplainQuery),
+    CreateFunction(CreateFunctionQuery),
+    CreateView(CreateViewQuery),
+    DropView(DropViewQuery),
 }
 
+#[derive(Clone)]
 pub struct SelectQuery {
     pub statements: HashMap<&'static str, Statement>,
     pub alias_table: HashMap<String, String>,
     pub has_aggregation_function: bool,
     pub has_group_by_statement: bool,
     pub hidden_selections: HashMap<String, Vec<String>>,
+    pub subqueries: HashMap<String, SelectQuery>,
+    pub common_tables: Vec<CommonTableExpression>,
+}
+
+#[derive(Clone)]
+pub struct CommonTableExpression {
+    pub name: String,
+    pub columns_names: Vec<String>,
+    pub query: SelectQuery,
+    pub recursive_query: Option<SelectQuery>,
+    pub is_union_all: bool,
+}
+
+#[derive(PartialEq)]
+pub enum SetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+pub struct SetOperationQuery {
+    pub left: Box<Query>,
+    pub right: Box<Query>,
+    pub operator: SetOperator,
+    pub is_all: bool,
+    /// `ORDER BY`, `OFFSET` and `LIMIT` after the last query applied on the combined rows
+    pub order_by: Option<OrderByStatement>,
+    pub offset: Option<OffsetStatement>,
+    pub limit: Option<LimitStatement>,
 }
 
 pub struct DoQuery {
@@ -27,6 +65,25 @@ pub struct DescribeQuery {
     pub table_name: String,
 }
 
+pub struct ExplainQuery {
+    pub query: SelectQuery,
+    pub is_analyze: bool,
+}
+
+pub struct CreateFunctionQuery {
+    pub name: String,
+    pub parameters_names: Vec<String>,
+    pub body: Box<dyn Expr>,
+}
+
+pub struct CreateViewQuery {
+    pub name: String,
+}
+
+pub struct DropViewQuery {
+    pub name: String,
+}
+
 pub struct GlobalVariableDeclQuery {
     pub name: String,
     pub value: Box<dyn Expr>,
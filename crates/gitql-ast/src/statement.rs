@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
 use crate::expression::Expr;
+use crate::query::SelectQuery;
 
+#[derive(Clone)]
 pub enum Statement {
     Select(SelectStatement),
     Where(WhereStatement),
@@ -27,6 +29,32 @@ pub enum Distinct {
 pub struct TableSelection {
     pub table_name: String,
     pub columns_names: Vec<String>,
+    /// The query of the derived table `FROM (SELECT ...) AS name`, or None for schema tables
+    pub derived_query: Option<Box<SelectQuery>>,
+    /// The external file of the file table `FROM read_csv('path')`, or None for other tables
+    pub file_source: Option<FileSource>,
+    /// The call of the table function `FROM generate_series(1, 10)`, or None for other tables
+    pub table_function: Option<TableFunctionCall>,
+}
+
+/// Call of table function with the arguments expressions
+#[derive(Clone)]
+pub struct TableFunctionCall {
+    pub function_name: String,
+    pub arguments: Vec<Box<dyn Expr>>,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum FileFormat {
+    Csv,
+    Json,
+}
+
+/// External file that can be queried as a table
+#[derive(Clone)]
+pub struct FileSource {
+    pub path: String,
+    pub format: FileFormat,
 }
 
 #[derive(Clone, PartialEq)]
@@ -104,7 +132,25 @@ pub struct OrderByStatement {
 #[derive(Clone)]
 pub struct GroupByStatement {
     pub values: Vec<Box<dyn Expr>>,
-    pub has_with_roll_up: bool,
+    /// Each grouping set is the indices of the grouped values, `GROUP BY a, b` has one set with all values,
+    /// while `ROLLUP`, `CUBE` and `GROUPING SETS` can have many sets with some or none of the values
+    pub grouping_sets: Vec<Vec<usize>>,
+}
+
+impl GroupByStatement {
+    /// Create [`GroupByStatement`] with one grouping set that contains all the values
+    pub fn new(values: Vec<Box<dyn Expr>>) -> Self {
+        let grouping_sets = vec![(0..values.len()).collect()];
+        GroupByStatement {
+            values,
+            grouping_sets,
+        }
+    }
+
+    /// Return true if the rows are grouped by more than one grouping set
+    pub fn has_grouping_sets(&self) -> bool {
+        self.grouping_sets.len() > 1
+    }
 }
 
 #[derive(Clone)]
@@ -117,11 +163,41 @@ pub struct WindowOrderingClause {
     pub order_by: OrderByStatement,
 }
 
+#[derive(Clone, PartialEq)]
+pub enum WindowFrameUnit {
+    Rows,
+    Range,
+    Groups,
+}
+
+/// Start or end of the window frame, the offset is constant expression that evaluated
+/// once, it's number of rows for `ROWS`, number of peer groups for `GROUPS` and
+/// distance from the ordering value for `RANGE`
+#[derive(Clone)]
+pub enum WindowFrameBound {
+    UnboundedPreceding,
+    Preceding(Box<dyn Expr>),
+    CurrentRow,
+    Following(Box<dyn Expr>),
+    UnboundedFollowing,
+}
+
+/// Window frame `ROWS BETWEEN 6 PRECEDING AND CURRENT ROW` that select the rows used to
+/// calculate the value of each row in the partition
+#[derive(Clone)]
+pub struct WindowFrame {
+    pub unit: WindowFrameUnit,
+    pub start: WindowFrameBound,
+    pub end: WindowFrameBound,
+}
+
 #[derive(Clone)]
 pub struct WindowDefinition {
     pub name: Option<String>,
     pub partitioning_clause: Option<WindowPartitioningClause>,
     pub ordering_clause: Option<WindowOrderingClause>,
+    /// The frame of each row, or None to use the whole partition
+    pub frame: Option<Box<WindowFrame>>,
 }
 
 #[derive(Clone)]
@@ -154,10 +230,25 @@ pub struct QualifyStatement {
     pub condition: Box<dyn Expr>,
 }
 
+#[derive(Clone)]
+pub struct AggregateFunction {
+    pub function_name: String,
+    /// Function arguments, for ordered-set function the `WITHIN GROUP` ordering values are the last arguments
+    pub arguments: Vec<Box<dyn Expr>>,
+    /// Ordering of the group values of ordered-set function like `percentile_cont(0.5) WITHIN GROUP (ORDER BY x)`
+    pub within_group: Option<OrderByStatement>,
+    /// Pass only the distinct arguments values to the function like `COUNT(DISTINCT x)`
+    pub distinct: bool,
+    /// Pass only the rows that match the condition to the function like `COUNT() FILTER (WHERE x > 1)`
+    pub filter: Option<Box<dyn Expr>>,
+}
+
 #[derive(Clone)]
 pub enum AggregateValue {
     Expression(Box<dyn Expr>),
-    Function(String, Vec<Box<dyn Expr>>),
+    Function(AggregateFunction),
+    /// `GROUPING(a, b)` bit mask with bit for each argument that is set if it's not grouped in the row grouping set
+    Grouping(Vec<Box<dyn Expr>>),
 }
 
 #[derive(Clone)]
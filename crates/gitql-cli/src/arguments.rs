@@ -9,6 +9,18 @@ pub struct Arguments {
     pub page_size: usize,
     pub enable_line_editor: bool,
     pub output_format: OutputFormatKind,
+    pub diffs_cache: DiffsCacheMode,
+}
+
+/// How to use the persistent cache of the computed diffs
+#[derive(Debug, PartialEq)]
+pub enum DiffsCacheMode {
+    /// Load and store the diffs in the cache
+    Enabled,
+    /// Calculate the diffs without the cache
+    Disabled,
+    /// Remove the stored cache and calculate the diffs without it
+    Clear,
 }
 
 /// Create a new instance of Arguments with the default settings
@@ -21,6 +33,7 @@ impl Arguments {
             page_size: 10,
             enable_line_editor: false,
             output_format: OutputFormatKind::Table,
+            diffs_cache: DiffsCacheMode::Disabled,
         }
     }
 }
@@ -154,6 +167,26 @@ pub fn parse_arguments(args: &[String]) -> Command {
 
                 arg_index += 1;
             }
+            "--cache" | "-c" => {
+                arg_index += 1;
+                if arg_index >= args_len {
+                    let message = format!("Argument {arg} must be followed by cache mode");
+                    return Command::Error(message);
+                }
+
+                let cache_mode = &args[arg_index].to_lowercase();
+                if cache_mode == "enable" {
+                    arguments.diffs_cache = DiffsCacheMode::Enabled;
+                } else if cache_mode == "disable" {
+                    arguments.diffs_cache = DiffsCacheMode::Disabled;
+                } else if cache_mode == "clear" {
+                    arguments.diffs_cache = DiffsCacheMode::Clear;
+                } else {
+                    return Command::Error("Invalid cache mode".to_string());
+                }
+
+                arg_index += 1;
+            }
             _ => return Command::Error(format!("Unknown command {arg}")),
         }
     }
@@ -198,6 +231,7 @@ pub fn print_help_list() {
     println!("-ps, --pagesize             Set pagination page size [default: 10]");
     println!("-o,  --output               Set output format [render, json, csv]");
     println!("-a,  --analysis             Print Query analysis");
+    println!("-c,  --cache                Set diffs cache mode [enable, disable, clear]");
     println!("-e,  --editor               Enable GitQL Rich Line Editor");
     println!("-h,  --help                 Print GitQL help");
     println!("-v,  --version              Print GitQL Current Version");
@@ -287,6 +321,33 @@ mod tests {
         assert!(!matches!(command, Command::Error { .. }));
     }
 
+    #[test]
+    fn test_arguments_with_valid_cache_mode() {
+        let arguments = vec![
+            "gitql".to_string(),
+            "--cache".to_string(),
+            "enable".to_string(),
+        ];
+        let command = parse_arguments(&arguments);
+        match command {
+            Command::ReplMode(arguments) => {
+                assert_eq!(arguments.diffs_cache, DiffsCacheMode::Enabled)
+            }
+            _ => panic!("Expect repl mode command"),
+        }
+    }
+
+    #[test]
+    fn test_arguments_with_invalid_cache_mode() {
+        let arguments = vec![
+            "gitql".to_string(),
+            "--cache".to_string(),
+            "always".to_string(),
+        ];
+        let command = parse_arguments(&arguments);
+        assert!(matches!(command, Command::Error { .. }));
+    }
+
     #[test]
     fn test_arguments_with_invalid_output_format() {
         let arguments = vec![
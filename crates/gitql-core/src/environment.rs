@@ -1,15 +1,37 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
 
+use gitql_ast::query::SelectQuery;
 use gitql_ast::types::DataType;
 
+use crate::object::GitQLObject;
+use crate::object::Row;
 use crate::schema::Schema;
+use crate::signature::AggregationClosure;
 use crate::signature::AggregationFunction;
 use crate::signature::Signature;
+use crate::signature::StandardClosure;
 use crate::signature::StandardFunction;
+use crate::signature::TableClosure;
+use crate::signature::TableFunction;
+use crate::signature::TableSignature;
+use crate::signature::UserFunction;
+use crate::signature::WindowClosure;
 use crate::signature::WindowFunction;
 use crate::types_table::TypesTable;
 use crate::values::Value;
 
+/// Columns names and types of a derived or common table
+pub type TableColumns = Vec<(String, Box<dyn DataType>)>;
+
+/// Named select query created by `CREATE VIEW` query, it's used like a derived table
+#[derive(Clone)]
+pub struct View {
+    pub query: SelectQuery,
+    pub columns: TableColumns,
+}
+
 /// Environment that track schema, functions, scopes and types
 /// to be used in different places in the query engine
 pub struct Environment {
@@ -20,19 +42,34 @@ pub struct Environment {
     pub std_signatures: HashMap<&'static str, Signature>,
 
     /// Standard function references
-    pub std_functions: HashMap<&'static str, StandardFunction>,
+    pub std_functions: HashMap<&'static str, StandardClosure>,
 
     /// Aggregation function signatures
     pub aggregation_signatures: HashMap<&'static str, Signature>,
 
     /// Aggregation function references
-    pub aggregation_functions: HashMap<&'static str, AggregationFunction>,
+    pub aggregation_functions: HashMap<&'static str, AggregationClosure>,
+
+    /// Names of the aggregation functions that must be called with `WITHIN GROUP (ORDER BY ...)`
+    pub ordered_set_aggregations: HashSet<&'static str>,
 
     /// Window function signatures
     pub window_signatures: HashMap<&'static str, Signature>,
 
     /// Window function references
-    pub window_functions: HashMap<&'static str, WindowFunction>,
+    pub window_functions: HashMap<&'static str, WindowClosure>,
+
+    /// Table function signatures
+    pub table_signatures: HashMap<&'static str, TableSignature>,
+
+    /// Table function references
+    pub table_functions: HashMap<&'static str, TableClosure>,
+
+    /// User defined function signatures that can life for this program session
+    pub user_signatures: HashMap<String, Signature>,
+
+    /// User defined function references that can life for this program session
+    pub user_functions: HashMap<String, Rc<UserFunction>>,
 
     /// All Global Variables values that can life for this program session
     pub globals: HashMap<String, Box<dyn Value>>,
@@ -43,6 +80,18 @@ pub struct Environment {
     /// Local variables types in the current scope, later will be multi layer scopes
     pub scopes: HashMap<String, Box<dyn DataType>>,
 
+    /// Views that can life for this program session mapped by view name
+    pub views: HashMap<String, View>,
+
+    /// Columns names and types of the derived and common tables defined in the current query mapped by table name
+    pub derived_tables: HashMap<String, TableColumns>,
+
+    /// Materialized rows of the common tables evaluated in the current query mapped by table name
+    pub common_tables: HashMap<String, GitQLObject>,
+
+    /// Values of the subqueries evaluated in the current query mapped by their generated names
+    pub subqueries_values: HashMap<String, Vec<Box<dyn Value>>>,
+
     /// A Table of DataTypes mapped to their original names or aliases
     pub types_table: TypesTable,
 }
@@ -56,11 +105,20 @@ impl Environment {
             std_functions: HashMap::default(),
             aggregation_signatures: HashMap::default(),
             aggregation_functions: HashMap::default(),
+            ordered_set_aggregations: HashSet::default(),
             window_signatures: HashMap::default(),
             window_functions: HashMap::default(),
+            table_signatures: HashMap::default(),
+            table_functions: HashMap::default(),
+            user_signatures: HashMap::default(),
+            user_functions: HashMap::default(),
             globals: HashMap::default(),
             globals_types: HashMap::default(),
             scopes: HashMap::default(),
+            views: HashMap::default(),
+            derived_tables: HashMap::default(),
+            common_tables: HashMap::default(),
+            subqueries_values: HashMap::default(),
             types_table: TypesTable::new(),
         }
     }
@@ -72,7 +130,20 @@ impl Environment {
         functions: &HashMap<&'static str, StandardFunction>,
     ) {
         self.std_signatures.extend(signatures.to_owned());
-        self.std_functions.extend(functions.to_owned());
+        for (name, function) in functions {
+            self.std_functions.insert(name, Rc::new(*function));
+        }
+    }
+
+    /// Register standard function signature and closure that can capture state
+    pub fn with_standard_closure(
+        &mut self,
+        name: &'static str,
+        signature: Signature,
+        closure: impl Fn(&[Box<dyn Value>]) -> Box<dyn Value> + 'static,
+    ) {
+        self.std_signatures.insert(name, signature);
+        self.std_functions.insert(name, Rc::new(closure));
     }
 
     /// Register aggregation functions signatures and references
@@ -82,7 +153,31 @@ impl Environment {
         aggregation: &HashMap<&'static str, AggregationFunction>,
     ) {
         self.aggregation_signatures.extend(signatures.to_owned());
-        self.aggregation_functions.extend(aggregation.to_owned());
+        for (name, function) in aggregation {
+            self.aggregation_functions.insert(name, Rc::new(*function));
+        }
+    }
+
+    /// Register aggregation function signature and closure that can capture state
+    pub fn with_aggregation_closure(
+        &mut self,
+        name: &'static str,
+        signature: Signature,
+        closure: impl Fn(&[Vec<Box<dyn Value>>]) -> Box<dyn Value> + 'static,
+    ) {
+        self.aggregation_signatures.insert(name, signature);
+        self.aggregation_functions.insert(name, Rc::new(closure));
+    }
+
+    /// Register ordered-set aggregation functions signatures and references,
+    /// the values of `WITHIN GROUP (ORDER BY ...)` are passed to them as the last arguments in order
+    pub fn with_ordered_set_aggregation_functions(
+        &mut self,
+        signatures: &HashMap<&'static str, Signature>,
+        aggregation: &HashMap<&'static str, AggregationFunction>,
+    ) {
+        self.with_aggregation_functions(signatures, aggregation);
+        self.ordered_set_aggregations.extend(aggregation.keys());
     }
 
     /// Register Window functions signatures and references
@@ -92,7 +187,43 @@ impl Environment {
         window: &HashMap<&'static str, WindowFunction>,
     ) {
         self.window_signatures.extend(signatures.to_owned());
-        self.window_functions.extend(window.to_owned());
+        for (name, function) in window {
+            self.window_functions.insert(name, Rc::new(*function));
+        }
+    }
+
+    /// Register Window function signature and closure that can capture state
+    pub fn with_window_closure(
+        &mut self,
+        name: &'static str,
+        signature: Signature,
+        closure: impl Fn(&[Vec<Box<dyn Value>>], &[usize]) -> Vec<Box<dyn Value>> + 'static,
+    ) {
+        self.window_signatures.insert(name, signature);
+        self.window_functions.insert(name, Rc::new(closure));
+    }
+
+    /// Register table functions signatures and references
+    pub fn with_table_functions(
+        &mut self,
+        signatures: &HashMap<&'static str, TableSignature>,
+        functions: &HashMap<&'static str, TableFunction>,
+    ) {
+        self.table_signatures.extend(signatures.to_owned());
+        for (name, function) in functions {
+            self.table_functions.insert(name, Rc::new(*function));
+        }
+    }
+
+    /// Register table function signature and closure that can capture state
+    pub fn with_table_closure(
+        &mut self,
+        name: &'static str,
+        signature: TableSignature,
+        closure: impl Fn(&[Box<dyn Value>]) -> Result<Vec<Row>, String> + 'static,
+    ) {
+        self.table_signatures.insert(name, signature);
+        self.table_functions.insert(name, Rc::new(closure));
     }
 
     /// Register new Modified Types table
@@ -111,7 +242,7 @@ impl Environment {
     }
 
     /// Return Standard function reference by name
-    pub fn std_function(&self, str: &str) -> Option<&StandardFunction> {
+    pub fn std_function(&self, str: &str) -> Option<&StandardClosure> {
         self.std_functions.get(str)
     }
 
@@ -120,13 +251,18 @@ impl Environment {
         self.aggregation_signatures.contains_key(str)
     }
 
+    /// Return true if this name is an aggregation function that must be called with `WITHIN GROUP`
+    pub fn is_ordered_set_aggregation_function(&self, str: &str) -> bool {
+        self.ordered_set_aggregations.contains(str)
+    }
+
     /// Return Aggregation function signature by name
     pub fn aggregation_signature(&self, str: &str) -> Option<&Signature> {
         self.aggregation_signatures.get(str)
     }
 
     /// Return Aggregation function reference by name
-    pub fn aggregation_function(&self, str: &str) -> Option<&AggregationFunction> {
+    pub fn aggregation_function(&self, str: &str) -> Option<&AggregationClosure> {
         self.aggregation_functions.get(str)
     }
 
@@ -141,10 +277,54 @@ impl Environment {
     }
 
     /// Return Window function reference by name
-    pub fn window_function(&self, str: &str) -> Option<&WindowFunction> {
+    pub fn window_function(&self, str: &str) -> Option<&WindowClosure> {
         self.window_functions.get(str)
     }
 
+    /// Return true if this name is a valid table function
+    pub fn is_table_function(&self, str: &str) -> bool {
+        self.table_functions.contains_key(str)
+    }
+
+    /// Return table function signature by name
+    pub fn table_function_signature(&self, str: &str) -> Option<&TableSignature> {
+        self.table_signatures.get(str)
+    }
+
+    /// Return table function reference by name
+    pub fn table_function(&self, str: &str) -> Option<&TableClosure> {
+        self.table_functions.get(str)
+    }
+
+    /// Return true if this name is a valid user defined function
+    pub fn is_user_function(&self, str: &str) -> bool {
+        self.user_signatures.contains_key(str)
+    }
+
+    /// Return user defined function signature by name
+    pub fn user_function_signature(&self, str: &str) -> Option<&Signature> {
+        self.user_signatures.get(str)
+    }
+
+    /// Return user defined function reference by name
+    pub fn user_function(&self, str: &str) -> Option<Rc<UserFunction>> {
+        self.user_functions.get(str).cloned()
+    }
+
+    /// Return true if this name is used by standard, aggregation, window, table or user defined function
+    pub fn is_function(&self, str: &str) -> bool {
+        self.is_std_function(str)
+            || self.is_aggregation_function(str)
+            || self.is_window_function(str)
+            || self.is_table_function(str)
+            || self.is_user_function(str)
+    }
+
+    /// Define user defined function signature
+    pub fn define_user_function(&mut self, str: String, signature: Signature) {
+        self.user_signatures.insert(str, signature);
+    }
+
     /// Define in the current scope
     pub fn define(&mut self, str: String, data_type: Box<dyn DataType>) {
         self.scopes.insert(str, data_type);
@@ -160,6 +340,28 @@ impl Environment {
         self.scopes.contains_key(str) || self.globals_types.contains_key(str)
     }
 
+    /// Define view in the global scope
+    pub fn define_view(&mut self, str: String, view: View) {
+        self.views.insert(str, view);
+    }
+
+    /// Return true if this name is a view
+    pub fn is_view(&self, str: &str) -> bool {
+        self.views.contains_key(str)
+    }
+
+    /// Return the columns names of a table from the schema, the derived tables or the views
+    pub fn table_columns(&self, table_name: &str) -> Option<Vec<&str>> {
+        if let Some(table) = self.schema.table(table_name) {
+            return Some(table.columns_names());
+        }
+
+        self.derived_tables
+            .get(table_name)
+            .or_else(|| self.views.get(table_name).map(|view| &view.columns))
+            .map(|columns| columns.iter().map(|(name, _)| name.as_str()).collect())
+    }
+
     /// Resolve Global or Local type using symbol name
     #[allow(clippy::borrowed_box)]
     pub fn resolve_type(&self, str: &String) -> Option<&Box<dyn DataType>> {
@@ -171,6 +373,9 @@ impl Environment {
 
     /// Clear all locals scopes and only save globals
     pub fn clear_session(&mut self) {
-        self.scopes.clear()
+        self.scopes.clear();
+        self.derived_tables.clear();
+        self.common_tables.clear();
+        self.subqueries_values.clear();
     }
 }
@@ -0,0 +1,365 @@
+use gitql_ast::statement::FileFormat;
+use gitql_ast::statement::FileSource;
+use gitql_ast::types::boolean::BoolType;
+use gitql_ast::types::float::FloatType;
+use gitql_ast::types::integer::IntType;
+use gitql_ast::types::text::TextType;
+use gitql_ast::types::DataType;
+
+use crate::object::Row;
+use crate::schema::ColumnDefinition;
+use crate::schema::TableDefinition;
+use crate::values::boolean::BoolValue;
+use crate::values::float::FloatValue;
+use crate::values::integer::IntValue;
+use crate::values::null::NullValue;
+use crate::values::text::TextValue;
+use crate::values::Value;
+
+/// In memory representation of external file table with the inferred columns definitions
+pub struct FileTable {
+    pub definition: TableDefinition,
+    pub rows: Vec<Row>,
+}
+
+/// Read the CSV or JSON file and infer the type of each column from it's values
+///
+/// CSV files must have a header row, JSON files can be array of objects or
+/// one object per line (NDJSON), and their columns are ordered by name
+pub fn read_file_table(table_name: &str, source: &FileSource) -> Result<FileTable, String> {
+    let (columns_names, records) = match source.format {
+        FileFormat::Csv => read_csv_records(&source.path)?,
+        FileFormat::Json => read_json_records(&source.path)?,
+    };
+
+    // Infer the type of each column from all of it's values
+    let mut columns_kinds = vec![ValueKind::Unknown; columns_names.len()];
+    let mut columns_nullability = vec![false; columns_names.len()];
+    for record in records.iter() {
+        for (index, field) in record.iter().enumerate() {
+            if field.kind == ValueKind::Null {
+                columns_nullability[index] = true;
+            } else {
+                columns_kinds[index] = columns_kinds[index].merge(&field.kind);
+            }
+        }
+    }
+
+    let mut columns = Vec::with_capacity(columns_names.len());
+    for (index, column_name) in columns_names.iter().enumerate() {
+        let mut column = ColumnDefinition::new(column_name, columns_kinds[index].data_type());
+        if columns_nullability[index] {
+            column = column.nullable();
+        }
+        columns.push(column);
+    }
+
+    let mut rows = Vec::with_capacity(records.len());
+    for record in records {
+        let values = record
+            .into_iter()
+            .enumerate()
+            .map(|(index, field)| field.into_value(&columns_kinds[index]))
+            .collect();
+        rows.push(Row { values });
+    }
+
+    Ok(FileTable {
+        definition: TableDefinition::new(table_name, columns),
+        rows,
+    })
+}
+
+/// The kind of value in a file field, used to infer the column type
+#[derive(Clone, PartialEq)]
+enum ValueKind {
+    Unknown,
+    Null,
+    Bool,
+    Int,
+    Float,
+    Text,
+}
+
+impl ValueKind {
+    fn from_text(text: &str) -> Self {
+        if text.is_empty() {
+            ValueKind::Null
+        } else if text.parse::<i64>().is_ok() {
+            ValueKind::Int
+        } else if text.parse::<f64>().is_ok() {
+            ValueKind::Float
+        } else if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") {
+            ValueKind::Bool
+        } else {
+            ValueKind::Text
+        }
+    }
+
+    fn merge(&self, other: &ValueKind) -> ValueKind {
+        match (self, other) {
+            (ValueKind::Unknown, kind) => kind.clone(),
+            (kind, other) if kind == other => kind.clone(),
+            (ValueKind::Int, ValueKind::Float) | (ValueKind::Float, ValueKind::Int) => {
+                ValueKind::Float
+            }
+            _ => ValueKind::Text,
+        }
+    }
+
+    fn data_type(&self) -> Box<dyn DataType> {
+        match self {
+            ValueKind::Bool => Box::new(BoolType),
+            ValueKind::Int => Box::new(IntType),
+            ValueKind::Float => Box::new(FloatType),
+            _ => Box::new(TextType),
+        }
+    }
+}
+
+/// Field value as text with it's kind before converting it to the column type
+struct Field {
+    text: String,
+    kind: ValueKind,
+}
+
+impl Field {
+    fn into_value(self, column_kind: &ValueKind) -> Box<dyn Value> {
+        if self.kind == ValueKind::Null {
+            return Box::new(NullValue);
+        }
+
+        match column_kind {
+            ValueKind::Bool => Box::new(BoolValue::new(self.text.eq_ignore_ascii_case("true"))),
+            ValueKind::Int => Box::new(IntValue::new(self.text.parse().unwrap())),
+            ValueKind::Float => Box::new(FloatValue::new(self.text.parse().unwrap())),
+            _ => Box::new(TextValue::new(self.text)),
+        }
+    }
+}
+
+fn read_csv_records(path: &str) -> Result<(Vec<String>, Vec<Vec<Field>>), String> {
+    let mut reader = csv::Reader::from_path(path)
+        .map_err(|error| format!("Can't read CSV file `{path}`, {error}"))?;
+
+    let columns_names: Vec<String> = reader
+        .headers()
+        .map_err(|error| format!("Can't read CSV file `{path}` header, {error}"))?
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+
+    let mut records = vec![];
+    for record in reader.records() {
+        let record = record.map_err(|error| format!("Can't read CSV file `{path}`, {error}"))?;
+        let fields = record
+            .iter()
+            .map(|text| Field {
+                text: text.to_string(),
+                kind: ValueKind::from_text(text),
+            })
+            .collect();
+        records.push(fields);
+    }
+
+    Ok((columns_names, records))
+}
+
+fn read_json_records(path: &str) -> Result<(Vec<String>, Vec<Vec<Field>>), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|error| format!("Can't read JSON file `{path}`, {error}"))?;
+
+    // The file can be one array of objects or one object per line
+    let content = content.trim();
+    let elements: Vec<serde_json::Value> = if content.starts_with('[') {
+        serde_json::from_str(content)
+            .map_err(|error| format!("Can't parse JSON file `{path}`, {error}"))?
+    } else {
+        let mut elements = vec![];
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let element = serde_json::from_str(line)
+                .map_err(|error| format!("Can't parse JSON file `{path}`, {error}"))?;
+            elements.push(element);
+        }
+        elements
+    };
+
+    let mut objects = Vec::with_capacity(elements.len());
+    let mut columns_names: Vec<String> = vec![];
+    for element in elements {
+        let serde_json::Value::Object(object) = element else {
+            return Err(format!(
+                "Expect each row in JSON file `{path}` to be an object"
+            ));
+        };
+
+        for key in object.keys() {
+            if !columns_names.contains(key) {
+                columns_names.push(key.to_string());
+            }
+        }
+
+        objects.push(object);
+    }
+    columns_names.sort();
+
+    let mut records = Vec::with_capacity(objects.len());
+    for object in objects {
+        let fields = columns_names
+            .iter()
+            .map(|column_name| json_field(object.get(column_name)))
+            .collect();
+        records.push(fields);
+    }
+
+    Ok((columns_names, records))
+}
+
+fn json_field(value: Option<&serde_json::Value>) -> Field {
+    let (text, kind) = match value {
+        None | Some(serde_json::Value::Null) => (String::new(), ValueKind::Null),
+        Some(serde_json::Value::Bool(value)) => (value.to_string(), ValueKind::Bool),
+        Some(serde_json::Value::Number(number)) if number.is_i64() => {
+            (number.to_string(), ValueKind::Int)
+        }
+        Some(serde_json::Value::Number(number)) => (number.to_string(), ValueKind::Float),
+        Some(serde_json::Value::String(text)) => (text.to_string(), ValueKind::Text),
+        Some(value) => (value.to_string(), ValueKind::Text),
+    };
+    Field { text, kind }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write the content to temporary file and read it as file table
+    fn read_content_table(
+        name: &str,
+        format: FileFormat,
+        content: &str,
+    ) -> Result<FileTable, String> {
+        let path = std::env::temp_dir().join(format!("gitql-{}-{name}", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        let source = FileSource {
+            path: path.to_string_lossy().to_string(),
+            format,
+        };
+        let table = read_file_table(name, &source);
+        let _ = std::fs::remove_file(&path);
+        table
+    }
+
+    fn columns_types(table: &FileTable) -> Vec<(String, String, bool)> {
+        table
+            .definition
+            .columns
+            .iter()
+            .map(|column| {
+                let data_type = column.data_type.literal();
+                (column.name.to_string(), data_type, column.is_nullable)
+            })
+            .collect()
+    }
+
+    fn rows_literals(table: &FileTable) -> Vec<Vec<String>> {
+        table
+            .rows
+            .iter()
+            .map(|row| row.values.iter().map(|value| value.literal()).collect())
+            .collect()
+    }
+
+    fn column(name: &str, data_type: &str, is_nullable: bool) -> (String, String, bool) {
+        (name.to_string(), data_type.to_string(), is_nullable)
+    }
+
+    #[test]
+    fn csv_columns_types_are_inferred_from_all_values() {
+        let content = "id,ratio,active,name,code,note\n\
+                       1,1,true,alice,10,\n\
+                       2,2.5,FALSE,bob,A1,\n";
+        let table = read_content_table("types.csv", FileFormat::Csv, content).unwrap();
+
+        assert_eq!(
+            columns_types(&table),
+            vec![
+                column("id", "Int", false),
+                column("ratio", "Float", false),
+                column("active", "Boolean", false),
+                column("name", "Text", false),
+                column("code", "Text", false),
+                column("note", "Text", true),
+            ]
+        );
+
+        assert_eq!(
+            rows_literals(&table),
+            vec![
+                vec!["1", "1", "true", "alice", "10", "Null"],
+                vec!["2", "2.5", "false", "bob", "A1", "Null"],
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_empty_fields_are_nullable() {
+        let content = "id,size\n1,\n2,20\n";
+        let table = read_content_table("nullable.csv", FileFormat::Csv, content).unwrap();
+
+        assert_eq!(
+            columns_types(&table),
+            vec![column("id", "Int", false), column("size", "Int", true)]
+        );
+        assert_eq!(
+            rows_literals(&table),
+            vec![vec!["1", "Null"], vec!["2", "20"]]
+        );
+    }
+
+    #[test]
+    fn json_array_columns_are_ordered_by_name() {
+        let content = r#"[
+            {"name": "gitql", "size": 10, "stable": true, "tags": ["sql"]},
+            {"name": "gix", "size": 2.5, "extra": null}
+        ]"#;
+        let table = read_content_table("packages.json", FileFormat::Json, content).unwrap();
+
+        assert_eq!(
+            columns_types(&table),
+            vec![
+                column("extra", "Text", true),
+                column("name", "Text", false),
+                column("size", "Float", false),
+                column("stable", "Boolean", true),
+                column("tags", "Text", true),
+            ]
+        );
+
+        assert_eq!(
+            rows_literals(&table),
+            vec![
+                vec!["Null", "gitql", "10", "true", r#"["sql"]"#],
+                vec!["Null", "gix", "2.5", "Null", "Null"],
+            ]
+        );
+    }
+
+    #[test]
+    fn json_lines_are_read_as_rows() {
+        let content = "{\"id\": 1, \"name\": \"a\"}\n\n{\"id\": 2, \"name\": 3}\n";
+        let table = read_content_table("lines.json", FileFormat::Json, content).unwrap();
+
+        assert_eq!(
+            columns_types(&table),
+            vec![column("id", "Int", false), column("name", "Text", false)]
+        );
+        assert_eq!(rows_literals(&table), vec![vec!["1", "a"], vec!["2", "3"]]);
+    }
+
+    #[test]
+    fn json_rows_must_be_objects() {
+        let result = read_content_table("numbers.json", FileFormat::Json, "[1, 2]");
+        assert!(result.is_err_and(|error| error.contains("to be an object")));
+    }
+}
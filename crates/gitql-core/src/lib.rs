@@ -1,5 +1,6 @@
 pub mod combinations_generator;
 pub mod environment;
+pub mod file_table;
 pub mod object;
 pub mod schema;
 pub mod signature;
@@ -2,8 +2,121 @@ use std::collections::HashMap;
 
 use gitql_ast::types::DataType;
 
-/// A Representation of the Schema of the data including columns, tables and types
+/// A Representation of the Schema of the data including tables, columns and types
+#[derive(Clone, Default)]
 pub struct Schema {
-    pub tables_fields_names: HashMap<&'static str, Vec<&'static str>>,
-    pub tables_fields_types: HashMap<&'static str, Box<dyn DataType>>,
+    pub tables: HashMap<String, TableDefinition>,
+}
+
+impl Schema {
+    /// Create new empty [`Schema`]
+    pub fn new() -> Self {
+        Schema::default()
+    }
+
+    /// Create [`Schema`] from a list of table definitions
+    pub fn with_tables(tables: Vec<TableDefinition>) -> Self {
+        let mut schema = Schema::new();
+        for table in tables {
+            schema.add_table(table);
+        }
+        schema
+    }
+
+    /// Add or replace table definition in the schema
+    pub fn add_table(&mut self, table: TableDefinition) {
+        self.tables.insert(table.name.to_string(), table);
+    }
+
+    /// Remove table definition from the schema and return it if exists
+    pub fn remove_table(&mut self, table_name: &str) -> Option<TableDefinition> {
+        self.tables.remove(table_name)
+    }
+
+    /// Return the table definition if exists
+    pub fn table(&self, table_name: &str) -> Option<&TableDefinition> {
+        self.tables.get(table_name)
+    }
+
+    /// Return true if this schema has table with this name
+    pub fn contains_table(&self, table_name: &str) -> bool {
+        self.tables.contains_key(table_name)
+    }
+
+    /// Return the names of all tables in the schema
+    pub fn tables_names(&self) -> Vec<&str> {
+        self.tables.keys().map(|name| name.as_str()).collect()
+    }
+
+    /// Return the column definition of a column in a specific table if exists
+    pub fn column(&self, table_name: &str, column_name: &str) -> Option<&ColumnDefinition> {
+        self.tables
+            .get(table_name)
+            .and_then(|table| table.column(column_name))
+    }
+}
+
+/// A Definition of table with ordered list of columns
+#[derive(Clone)]
+pub struct TableDefinition {
+    pub name: String,
+    pub columns: Vec<ColumnDefinition>,
+}
+
+impl TableDefinition {
+    /// Create new [`TableDefinition`] with columns
+    pub fn new(name: &str, columns: Vec<ColumnDefinition>) -> Self {
+        TableDefinition {
+            name: name.to_string(),
+            columns,
+        }
+    }
+
+    /// Return the column definition if exists
+    pub fn column(&self, column_name: &str) -> Option<&ColumnDefinition> {
+        self.columns
+            .iter()
+            .find(|column| column.name == column_name)
+    }
+
+    /// Return the names of the columns in the same order of definition
+    pub fn columns_names(&self) -> Vec<&str> {
+        self.columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect()
+    }
+}
+
+/// A Definition of column in a table, including it's type, nullability and description
+#[derive(Clone)]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub data_type: Box<dyn DataType>,
+    pub is_nullable: bool,
+    pub description: Option<String>,
+}
+
+impl ColumnDefinition {
+    /// Create new non nullable [`ColumnDefinition`] without description
+    pub fn new(name: &str, data_type: Box<dyn DataType>) -> Self {
+        ColumnDefinition {
+            name: name.to_string(),
+            data_type,
+            is_nullable: false,
+            description: None,
+        }
+    }
+
+    /// Mark this column as nullable
+    pub fn nullable(mut self) -> Self {
+        self.is_nullable = true;
+        self
+    }
+
+    /// Set the description of this column
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
 }
@@ -1,5 +1,10 @@
+use std::rc::Rc;
+
+use super::object::Row;
+use super::schema::ColumnDefinition;
 use super::values::Value;
 
+use gitql_ast::expression::Expr;
 use gitql_ast::types::DataType;
 
 /// Standard function accept array of values and return single [`Value`]
@@ -29,7 +34,43 @@ pub type AggregationFunction = fn(&[Vec<Box<dyn Value>>]) -> Box<dyn Value>;
 /// This implementation allow Window` function to accept more than one parameter,
 /// and also accept any Expression not only field name
 ///
-pub type WindowFunction = fn(&[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>>;
+/// The second parameter is the peer group index of each row in the frame, rows are peers
+/// if they have equal values in the window `ORDER BY`, and all rows are peers without ordering,
+/// so functions such as `rank` and `cume_dist` can give peers the same value
+///
+/// For Example if the ordering values are [1, 1, 2, 3, 3] the peer groups are [0, 0, 1, 2, 2]
+///
+pub type WindowFunction = fn(&[Vec<Box<dyn Value>>], &[usize]) -> Vec<Box<dyn Value>>;
+
+/// Standard function closure, unlike [`StandardFunction`] it can capture state
+/// such as a repository handle or a config map
+///
+/// Closures that need to mutate their state can use interior mutability such as [`std::cell::RefCell`]
+pub type StandardClosure = Rc<dyn Fn(&[Box<dyn Value>]) -> Box<dyn Value>>;
+
+/// Aggregation function closure, unlike [`AggregationFunction`] it can capture state
+pub type AggregationClosure = Rc<dyn Fn(&[Vec<Box<dyn Value>>]) -> Box<dyn Value>>;
+
+/// Window function closure, unlike [`WindowFunction`] it can capture state
+pub type WindowClosure = Rc<dyn Fn(&[Vec<Box<dyn Value>>], &[usize]) -> Vec<Box<dyn Value>>>;
+
+/// Table function accept array of values and return the rows of a table that can be used in `FROM`
+///
+/// The values of each row must be in the same order of the columns in the [`TableSignature`]
+pub type TableFunction = fn(&[Box<dyn Value>]) -> Result<Vec<Row>, String>;
+
+/// Table function closure, unlike [`TableFunction`] it can capture state such as a repository handle
+pub type TableClosure = Rc<dyn Fn(&[Box<dyn Value>]) -> Result<Vec<Row>, String>>;
+
+/// User defined function created by `CREATE FUNCTION` query
+///
+/// The body is an expression that reference the parameters by their names,
+/// and it's evaluated with the arguments values bound to the parameters names
+#[derive(Clone)]
+pub struct UserFunction {
+    pub parameters_names: Vec<String>,
+    pub body: Box<dyn Expr>,
+}
 
 /// Signature struct is a representation of function type
 ///
@@ -81,3 +122,23 @@ impl Signature {
         self
     }
 }
+
+/// TableSignature is a representation of table function type
+///
+/// It track the parameters types with the same rules of [`Signature`] parameters,
+/// and the columns of the returned rows, columns types can be Dynamic depending on parameters
+#[derive(Clone)]
+pub struct TableSignature {
+    pub parameters: Vec<Box<dyn DataType>>,
+    pub columns: Vec<ColumnDefinition>,
+}
+
+impl TableSignature {
+    /// Create Instance of [`TableSignature`] with parameters and columns
+    pub fn new(parameters: Vec<Box<dyn DataType>>, columns: Vec<ColumnDefinition>) -> Self {
+        TableSignature {
+            parameters,
+            columns,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::any::Any;
 use std::cmp::Ordering;
 use std::fmt;
+use std::hash::Hasher;
 
 use dyn_clone::DynClone;
 use gitql_ast::operator::GroupComparisonOperator;
@@ -36,6 +37,12 @@ pub trait Value: DynClone {
     #[allow(clippy::borrowed_box)]
     fn compare(&self, other: &Box<dyn Value>) -> Option<Ordering>;
 
+    /// Feed the current [`Value`] into the given [`Hasher`],
+    /// values that are equal must feed the same data
+    fn hash(&self, state: &mut dyn Hasher) {
+        state.write(self.literal().as_bytes());
+    }
+
     /// Return the [`DataType`] for the current [`Value`]
     fn data_type(&self) -> Box<dyn DataType>;
 
@@ -1,5 +1,6 @@
 use std::any::Any;
 use std::cmp::Ordering;
+use std::hash::Hasher;
 
 use gitql_ast::operator::GroupComparisonOperator;
 use gitql_ast::types::boolean::BoolType;
@@ -46,6 +47,10 @@ impl Value for BoolValue {
         None
     }
 
+    fn hash(&self, state: &mut dyn Hasher) {
+        state.write_u8(self.value as u8);
+    }
+
     fn data_type(&self) -> Box<dyn DataType> {
         Box::new(BoolType)
     }
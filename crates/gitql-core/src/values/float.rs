@@ -1,5 +1,6 @@
 use std::any::Any;
 use std::cmp::Ordering;
+use std::hash::Hasher;
 
 use gitql_ast::operator::GroupComparisonOperator;
 use gitql_ast::types::float::FloatType;
@@ -39,6 +40,12 @@ impl Value for FloatValue {
         None
     }
 
+    fn hash(&self, state: &mut dyn Hasher) {
+        // Positive and negative zero are equal so they must have the same hash
+        let value = if self.value == 0.0 { 0.0 } else { self.value };
+        state.write_u64(value.to_bits());
+    }
+
     fn data_type(&self) -> Box<dyn DataType> {
         Box::new(FloatType)
     }
@@ -1,5 +1,6 @@
 use std::any::Any;
 use std::cmp::Ordering;
+use std::hash::Hasher;
 
 use gitql_ast::operator::GroupComparisonOperator;
 use gitql_ast::types::integer::IntType;
@@ -43,6 +44,10 @@ impl Value for IntValue {
         None
     }
 
+    fn hash(&self, state: &mut dyn Hasher) {
+        state.write_i64(self.value);
+    }
+
     fn data_type(&self) -> Box<dyn DataType> {
         Box::new(IntType)
     }
@@ -1,5 +1,6 @@
 use std::any::Any;
 use std::cmp::Ordering;
+use std::hash::Hasher;
 
 use gitql_ast::operator::GroupComparisonOperator;
 use regex::Regex;
@@ -51,6 +52,10 @@ impl Value for TextValue {
         None
     }
 
+    fn hash(&self, state: &mut dyn Hasher) {
+        state.write(self.value.as_bytes());
+    }
+
     fn data_type(&self) -> Box<dyn DataType> {
         Box::new(TextType)
     }
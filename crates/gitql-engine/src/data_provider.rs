@@ -1,8 +1,154 @@
+use gitql_ast::operator::ComparisonOperator;
+use gitql_ast::statement::FileSource;
+use gitql_core::file_table::read_file_table;
 use gitql_core::object::Row;
+use gitql_core::values::null::NullValue;
+use gitql_core::values::Value;
+
+/// Iterator over the provided rows, each row can be an error message instead
+pub type RowsIterator<'a> = Box<dyn Iterator<Item = Result<Row, String>> + 'a>;
 
 /// DataProvider is a component that used to provide and map the data to the GitQL Engine
 ///
 /// User should implement [`DataProvider`] trait for each data format for example files, logs, api
 pub trait DataProvider {
     fn provide(&self, table: &str, selected_columns: &[String]) -> Result<Vec<Row>, String>;
+
+    /// Provide the rows of the table with hints extracted from the query,
+    /// the provider can use the hints to skip rows or stop early but the engine
+    /// will still apply the full `WHERE` and `LIMIT` statements on the result
+    ///
+    /// The default implementation ignores the hints
+    fn provide_with_hints(
+        &self,
+        table: &str,
+        selected_columns: &[String],
+        hints: &ProviderHints,
+    ) -> Result<Vec<Row>, String> {
+        let _ = hints;
+        self.provide(table, selected_columns)
+    }
+
+    /// Provide the rows of the table lazily, so the engine can stop consuming them early
+    /// for example when the query has `LIMIT` without `ORDER BY` or `GROUP BY`
+    ///
+    /// The default implementation provide all the rows using [`DataProvider::provide_with_hints`]
+    fn provide_iter<'a>(
+        &'a self,
+        table: &str,
+        selected_columns: &'a [String],
+        hints: &'a ProviderHints,
+    ) -> Result<RowsIterator<'a>, String> {
+        let rows = self.provide_with_hints(table, selected_columns, hints)?;
+        Ok(Box::new(rows.into_iter().map(Ok)))
+    }
+}
+
+/// Hints that can be used by the [`DataProvider`] to provide less rows
+#[derive(Default)]
+pub struct ProviderHints {
+    /// Conditions that every row in the result must satisfy
+    pub conditions: Vec<ColumnCondition>,
+    /// Maximum number of rows satisfying the conditions that the query need
+    pub limit: Option<usize>,
+}
+
+impl ProviderHints {
+    /// Return true if the row values satisfy all the conditions on the selected columns
+    pub fn is_satisfied_by(&self, selected_columns: &[String], values: &[Box<dyn Value>]) -> bool {
+        self.is_satisfied_by_columns(selected_columns, values, |_| true)
+    }
+
+    /// Return true if the row values satisfy the conditions on the columns accepted by the filter,
+    /// useful to skip the row before calculating the values of the other columns
+    pub fn is_satisfied_by_columns(
+        &self,
+        selected_columns: &[String],
+        values: &[Box<dyn Value>],
+        filter: impl Fn(&str) -> bool,
+    ) -> bool {
+        self.conditions
+            .iter()
+            .filter(|condition| filter(&condition.column))
+            .all(|condition| {
+                match selected_columns
+                    .iter()
+                    .position(|column| *column == condition.column)
+                {
+                    Some(index) => condition.is_satisfied_by(&values[index]),
+                    None => true,
+                }
+            })
+    }
+
+    /// Return true if the number of rows reached the limit
+    pub fn is_limit_reached(&self, rows_count: usize) -> bool {
+        self.limit.is_some_and(|limit| rows_count >= limit)
+    }
+}
+
+/// Comparison between a table column and a constant value, for example `datetime > '2024-01-01'`
+pub struct ColumnCondition {
+    pub column: String,
+    pub operator: ComparisonOperator,
+    pub value: Box<dyn Value>,
+}
+
+impl ColumnCondition {
+    /// Return true if the column value satisfy the condition,
+    /// values that can't be compared are treated as satisfied and left for the engine
+    #[allow(clippy::borrowed_box)]
+    pub fn is_satisfied_by(&self, column_value: &Box<dyn Value>) -> bool {
+        let result = match self.operator {
+            ComparisonOperator::Greater => column_value.gt_op(&self.value),
+            ComparisonOperator::GreaterEqual => column_value.gte_op(&self.value),
+            ComparisonOperator::Less => column_value.lt_op(&self.value),
+            ComparisonOperator::LessEqual => column_value.lte_op(&self.value),
+            ComparisonOperator::Equal => column_value.eq_op(&self.value),
+            ComparisonOperator::NotEqual => column_value.bang_eq_op(&self.value),
+            ComparisonOperator::NullSafeEqual => column_value.null_safe_eq_op(&self.value),
+        };
+
+        match result {
+            Ok(value) => value.as_bool().unwrap_or(true),
+            Err(_) => true,
+        }
+    }
+}
+
+/// Provide the rows of external CSV or JSON file used as a table for example `read_csv('path')`
+pub struct FileDataProvider {
+    pub source: FileSource,
+}
+
+impl DataProvider for FileDataProvider {
+    fn provide(&self, table: &str, selected_columns: &[String]) -> Result<Vec<Row>, String> {
+        let file_table = read_file_table(table, &self.source)?;
+
+        // Map each selected column to it's index in the file, generated columns has no index
+        let columns_indices: Vec<Option<usize>> = selected_columns
+            .iter()
+            .map(|column_name| {
+                file_table
+                    .definition
+                    .columns
+                    .iter()
+                    .position(|column| column.name == *column_name)
+            })
+            .collect();
+
+        let mut rows: Vec<Row> = Vec::with_capacity(file_table.rows.len());
+        for row in file_table.rows {
+            let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(columns_indices.len());
+            for column_index in columns_indices.iter() {
+                match column_index {
+                    Some(index) => values.push(row.values[*index].clone()),
+                    None => values.push(Box::new(NullValue)),
+                }
+            }
+            rows.push(Row { values });
+        }
+
+        Ok(rows)
+    }
 }
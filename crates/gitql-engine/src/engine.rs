@@ -1,45 +1,62 @@
 use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Instant;
 use std::vec;
 
+use gitql_ast::query::CommonTableExpression;
+use gitql_ast::query::CreateFunctionQuery;
 use gitql_ast::query::DescribeQuery;
 use gitql_ast::query::DoQuery;
+use gitql_ast::query::ExplainQuery;
 use gitql_ast::query::GlobalVariableDeclQuery;
 use gitql_ast::query::Query;
 use gitql_ast::query::SelectQuery;
-use gitql_ast::statement::Distinct;
+use gitql_ast::query::SetOperationQuery;
 use gitql_ast::statement::Statement;
 use gitql_core::environment::Environment;
 use gitql_core::object::GitQLObject;
 use gitql_core::object::Group;
 use gitql_core::object::Row;
+use gitql_core::signature::UserFunction;
+use gitql_core::values::boolean::BoolValue;
+use gitql_core::values::null::NullValue;
 use gitql_core::values::text::TextValue;
 use gitql_core::values::Value;
 
 use crate::data_provider::DataProvider;
-use crate::engine_distinct::apply_distinct_operator;
 use crate::engine_evaluator::evaluate_expression;
+use crate::engine_executor::execute_limit;
+use crate::engine_executor::execute_logical_plan;
+use crate::engine_executor::execute_offset;
 use crate::engine_executor::execute_statement;
+use crate::engine_executor::PlanContext;
+use crate::engine_explain::explain_into_statement;
+use crate::engine_explain::explain_logical_plan;
+use crate::engine_explain::explain_nodes_object;
+use crate::engine_explain::explain_query_prelude;
+use crate::engine_explain::explain_select_query;
+use crate::engine_explain::object_rows_count;
+use crate::engine_explain::record_node_profile;
+use crate::engine_explain::ExplainNodeId;
+use crate::engine_explain::QueryProfile;
+use crate::engine_ordering::execute_order_by_statement;
+use crate::engine_planner::plan_select_query;
+use crate::engine_provider_hints::ScanHints;
+use crate::engine_set_operation::apply_set_operation;
+use crate::engine_set_operation::DistinctRows;
 
-/// Static Logical Plan, later must be replaced by a Plan from the Logical query Planner
-const FIXED_LOGICAL_PLAN_LEN: usize = 10;
-const FIXED_LOGICAL_PLAN: [&str; FIXED_LOGICAL_PLAN_LEN] = [
-    "select",
-    "where",
-    "group",
-    "aggregation",
-    "having",
-    "window_functions",
-    "qualify",
-    "order",
-    "offset",
-    "limit",
-];
+/// Maximum number of iterations of the recursive common table query,
+/// used to stop recursive queries that never return empty rows
+const MAX_RECURSION_DEPTH: usize = 1000;
 
 pub enum EvaluationResult {
     Do,
     SelectedGroups(GitQLObject),
     SelectedInfo,
     SetGlobalVariable,
+    CreateFunction,
+    CreateView,
+    DropView,
 }
 
 #[allow(clippy::borrowed_box)]
@@ -52,11 +69,29 @@ pub fn evaluate(
     for query in queries {
         let evaluation_result = match query {
             Query::Do(do_query) => evaluate_do_query(env, &do_query),
-            Query::Select(select_query) => evaluate_select_query(env, data_provider, select_query),
+            Query::Select(select_query) => {
+                evaluate_select_query(env, data_provider, select_query, None)
+            }
+            Query::SetOperation(set_operation) => {
+                evaluate_set_operation_query(env, data_provider, set_operation)
+                    .map(EvaluationResult::SelectedGroups)
+            }
             Query::GlobalVariableDecl(global) => evaluate_global_declaration_query(env, &global),
             Query::DescribeTable(describe_query) => evaluate_describe_query(env, describe_query),
             Query::ShowTables => evaluate_show_tables_query(env),
+            Query::Explain(explain_query) => {
+                evaluate_explain_query(env, data_provider, explain_query)
+            }
+            Query::CreateFunction(create_function_query) => {
+                evaluate_create_function_query(env, create_function_query)
+            }
+            // Views are registered and dropped while parsing so the next queries can use them
+            Query::CreateView(_) => Ok(EvaluationResult::CreateView),
+            Query::DropView(_) => Ok(EvaluationResult::DropView),
         }?;
+
+        // Common tables are visible only inside the query that define them
+        env.common_tables.clear();
         evaluations_results.push(evaluation_result);
     }
     Ok(evaluations_results)
@@ -77,7 +112,35 @@ fn evaluate_select_query(
     env: &mut Environment,
     data_provider: &Box<dyn DataProvider>,
     select_query: SelectQuery,
+    mut profile: Option<&mut QueryProfile>,
 ) -> Result<EvaluationResult, String> {
+    if let Some(profile) = profile.as_deref_mut() {
+        profile.nodes.extend(explain_query_prelude(&select_query));
+    }
+
+    // Common tables are materialized once and shared by all references in the query
+    for common_table in select_query.common_tables {
+        let start = Instant::now();
+        let id = ExplainNodeId::CommonTable(common_table.name.to_string());
+        let rows_count = evaluate_common_table(env, data_provider, common_table)?;
+        record_node_profile(&mut profile, id, None, rows_count, start);
+    }
+
+    // Subqueries are uncorrelated, so they are evaluated once before the query plan
+    for (name, subquery) in select_query.subqueries {
+        let start = Instant::now();
+        let subquery_object = evaluate_subquery(env, data_provider, subquery)?;
+        let mut values = vec![];
+        for group in subquery_object.groups {
+            for row in group.rows {
+                values.extend(row.values.into_iter().take(1));
+            }
+        }
+        let id = ExplainNodeId::Subquery(name.to_string());
+        record_node_profile(&mut profile, id, None, values.len(), start);
+        env.subqueries_values.insert(name, values);
+    }
+
     let mut gitql_object = GitQLObject::default();
     let mut alias_table: HashMap<String, String> = select_query.alias_table;
 
@@ -87,33 +150,38 @@ fn evaluate_select_query(
     let mut statements_map = select_query.statements;
     let has_group_by_statement = statements_map.contains_key("group");
 
-    let mut distinct: Option<Distinct> = None;
-    for logical_node_name in FIXED_LOGICAL_PLAN {
-        if let Some(statement) = statements_map.get_mut(logical_node_name) {
-            execute_statement(
-                env,
-                statement,
-                data_provider,
-                &mut gitql_object,
-                &mut alias_table,
-                &hidden_selections_map,
-                has_group_by_statement,
-            )?;
-
-            if let Statement::Select(select_statement) = statement {
-                // If the main group is empty, no need to perform other statements
-                if gitql_object.is_empty() || gitql_object.groups[0].is_empty() {
-                    return Ok(EvaluationResult::SelectedGroups(gitql_object));
-                }
+    // Into statement is not part of the plan because it's executed on the final rows
+    let mut into_statement = statements_map.remove("into");
+    let Some(plan) = plan_select_query(env, statements_map, &alias_table, &hidden_selections_map)
+    else {
+        return Ok(EvaluationResult::SelectedGroups(gitql_object));
+    };
 
-                distinct = Some(select_statement.distinct.to_owned());
-            }
+    if let Some(profile) = profile.as_deref_mut() {
+        profile
+            .nodes
+            .extend(explain_logical_plan(&plan, &hidden_selections_map));
+        if let Some(Statement::Into(into_statement)) = &into_statement {
+            profile.nodes.push(explain_into_statement(into_statement));
         }
     }
 
-    // Apply the distinct operation after executing statements
-    if let Some(distinct) = distinct {
-        apply_distinct_operator(&distinct, &mut gitql_object, &hidden_selections);
+    let mut context = PlanContext {
+        data_provider,
+        alias_table: &mut alias_table,
+        hidden_selections: &hidden_selections_map,
+        has_group_by_statement,
+        profile: profile.as_deref_mut(),
+    };
+
+    // If the main group is empty, no need to perform other nodes
+    if !execute_logical_plan(env, &mut context, &plan, 0, &mut gitql_object)? {
+        remove_hidden_selected_from_groups(
+            &mut gitql_object.titles,
+            &mut gitql_object.groups,
+            &hidden_selections,
+        );
+        return Ok(EvaluationResult::SelectedGroups(gitql_object));
     }
 
     // Remove Hidden Selection from the rows after executing the query plan
@@ -149,7 +217,9 @@ fn evaluate_select_query(
     }
 
     // Into statement must be executed last after flatted and remove hidden selections
-    if let Some(into_statement) = statements_map.get_mut("into") {
+    if let Some(into_statement) = into_statement.as_mut() {
+        let start = Instant::now();
+        let rows_count = object_rows_count(&gitql_object);
         execute_statement(
             env,
             into_statement,
@@ -157,15 +227,171 @@ fn evaluate_select_query(
             &mut gitql_object,
             &mut alias_table,
             &hidden_selections_map,
+            &ScanHints::default(),
             has_group_by_statement,
         )?;
 
+        let id = ExplainNodeId::Into;
+        record_node_profile(&mut profile, id, Some(rows_count), rows_count, start);
         return Ok(EvaluationResult::SelectedInfo);
     }
 
     Ok(EvaluationResult::SelectedGroups(gitql_object))
 }
 
+/// Evaluate the left and right queries of the set operation and combine their rows
+#[allow(clippy::borrowed_box)]
+fn evaluate_set_operation_query(
+    env: &mut Environment,
+    data_provider: &Box<dyn DataProvider>,
+    set_operation: SetOperationQuery,
+) -> Result<GitQLObject, String> {
+    let mut left = evaluate_set_operation_operand(env, data_provider, *set_operation.left)?;
+    let right = evaluate_set_operation_operand(env, data_provider, *set_operation.right)?;
+    apply_set_operation(
+        &set_operation.operator,
+        set_operation.is_all,
+        &mut left,
+        right,
+    );
+
+    if let Some(order_by) = &set_operation.order_by {
+        execute_order_by_statement(env, order_by, &mut left, 0, None)?;
+    }
+
+    if let Some(offset) = &set_operation.offset {
+        execute_offset(env, &offset.start, &mut left)?;
+    }
+
+    if let Some(limit) = &set_operation.limit {
+        execute_limit(limit.count, &mut left)?;
+    }
+
+    Ok(left)
+}
+
+#[allow(clippy::borrowed_box)]
+fn evaluate_set_operation_operand(
+    env: &mut Environment,
+    data_provider: &Box<dyn DataProvider>,
+    query: Query,
+) -> Result<GitQLObject, String> {
+    match query {
+        Query::Select(select_query) => evaluate_subquery(env, data_provider, select_query),
+        Query::SetOperation(set_operation) => {
+            evaluate_set_operation_query(env, data_provider, set_operation)
+        }
+        _ => Err("Set operation can be performed only between select queries".to_owned()),
+    }
+}
+
+/// Evaluate a select query used as subquery or derived table and return the selected rows
+#[allow(clippy::borrowed_box)]
+pub(crate) fn evaluate_subquery(
+    env: &mut Environment,
+    data_provider: &Box<dyn DataProvider>,
+    subquery: SelectQuery,
+) -> Result<GitQLObject, String> {
+    match evaluate_select_query(env, data_provider, subquery, None)? {
+        EvaluationResult::SelectedGroups(gitql_object) => Ok(gitql_object),
+        _ => Err("Subquery must be a select query without `INTO` statement".to_owned()),
+    }
+}
+
+/// Evaluate the common table query and store it's rows in the environment, and return the rows count
+/// the recursive query is evaluated with the rows of the previous iteration until it return no rows
+#[allow(clippy::borrowed_box)]
+fn evaluate_common_table(
+    env: &mut Environment,
+    data_provider: &Box<dyn DataProvider>,
+    common_table: CommonTableExpression,
+) -> Result<usize, String> {
+    let mut gitql_object = evaluate_subquery(env, data_provider, common_table.query)?;
+    gitql_object.flat();
+    gitql_object.titles = common_table.columns_names;
+
+    if let Some(recursive_query) = common_table.recursive_query {
+        let mut distinct_rows = DistinctRows::default();
+        if !common_table.is_union_all {
+            gitql_object.groups[0]
+                .rows
+                .retain(|row| distinct_rows.insert(&row.values));
+        }
+
+        let mut working_rows = gitql_object.groups[0].rows.clone();
+        let mut recursion_depth = 0;
+        while !working_rows.is_empty() {
+            recursion_depth += 1;
+            if recursion_depth > MAX_RECURSION_DEPTH {
+                return Err(format!(
+                    "Recursive common table `{}` exceeded the maximum recursion depth {MAX_RECURSION_DEPTH}, make sure the recursive query stop returning rows",
+                    common_table.name
+                ));
+            }
+
+            let working_object = GitQLObject {
+                titles: gitql_object.titles.clone(),
+                groups: vec![Group { rows: working_rows }],
+            };
+            env.common_tables
+                .insert(common_table.name.to_string(), working_object);
+
+            let mut recursive_object =
+                evaluate_subquery(env, data_provider, recursive_query.clone())?;
+            recursive_object.flat();
+
+            let mut new_rows = std::mem::take(&mut recursive_object.groups[0].rows);
+            if !common_table.is_union_all {
+                new_rows.retain(|row| distinct_rows.insert(&row.values));
+            }
+
+            gitql_object.groups[0].rows.extend(new_rows.iter().cloned());
+            working_rows = new_rows;
+        }
+    }
+
+    let rows_count = gitql_object.groups[0].len();
+    env.common_tables.insert(common_table.name, gitql_object);
+    Ok(rows_count)
+}
+
+/// Evaluate `EXPLAIN` query by describing the logical nodes of the select query,
+/// and for `EXPLAIN ANALYZE` execute the query and report the rows and time of each node
+#[allow(clippy::borrowed_box)]
+fn evaluate_explain_query(
+    env: &mut Environment,
+    data_provider: &Box<dyn DataProvider>,
+    explain_query: ExplainQuery,
+) -> Result<EvaluationResult, String> {
+    if !explain_query.is_analyze {
+        let nodes = explain_select_query(env, explain_query.query);
+        return Ok(EvaluationResult::SelectedGroups(explain_nodes_object(
+            nodes, None,
+        )));
+    }
+
+    let mut profile = QueryProfile::default();
+    evaluate_select_query(env, data_provider, explain_query.query, Some(&mut profile))?;
+    Ok(EvaluationResult::SelectedGroups(explain_nodes_object(
+        profile.nodes,
+        Some(profile.nodes_profiles),
+    )))
+}
+
+fn evaluate_create_function_query(
+    env: &mut Environment,
+    create_function_query: CreateFunctionQuery,
+) -> Result<EvaluationResult, String> {
+    let function = UserFunction {
+        parameters_names: create_function_query.parameters_names,
+        body: create_function_query.body,
+    };
+
+    env.user_functions
+        .insert(create_function_query.name, Rc::new(function));
+    Ok(EvaluationResult::CreateFunction)
+}
+
 fn evaluate_global_declaration_query(
     env: &mut Environment,
     global_decl_query: &GlobalVariableDeclQuery,
@@ -180,25 +406,41 @@ fn evaluate_describe_query(
     env: &mut Environment,
     describe_query: DescribeQuery,
 ) -> Result<EvaluationResult, String> {
-    let table_fields = env
-        .schema
-        .tables_fields_names
-        .get(&describe_query.table_name.as_str())
-        .unwrap();
-
     let mut gitql_object = GitQLObject::default();
     gitql_object.titles.push("field".to_owned());
     gitql_object.titles.push("type".to_owned());
+    gitql_object.titles.push("nullable".to_owned());
+    gitql_object.titles.push("description".to_owned());
+
+    let table_name = describe_query.table_name.as_str();
+    let mut rows: Vec<Row> = vec![];
+    if let Some(view) = env.views.get(table_name) {
+        for (field, data_type) in view.columns.iter() {
+            rows.push(Row {
+                values: vec![
+                    Box::new(TextValue::new(field.to_owned())),
+                    Box::new(TextValue::new(data_type.literal())),
+                    Box::new(BoolValue::new(true)),
+                    Box::new(NullValue),
+                ],
+            })
+        }
+    } else if let Some(table) = env.schema.table(table_name) {
+        for column in table.columns.iter() {
+            let description: Box<dyn Value> = match &column.description {
+                Some(description) => Box::new(TextValue::new(description.to_owned())),
+                None => Box::new(NullValue),
+            };
 
-    let mut rows: Vec<Row> = Vec::with_capacity(table_fields.len());
-    for field in table_fields {
-        let value = env.schema.tables_fields_types.get(field).unwrap();
-        rows.push(Row {
-            values: vec![
-                Box::new(TextValue::new(field.to_owned().to_owned())),
-                Box::new(TextValue::new(value.literal())),
-            ],
-        })
+            rows.push(Row {
+                values: vec![
+                    Box::new(TextValue::new(column.name.to_owned())),
+                    Box::new(TextValue::new(column.data_type.literal())),
+                    Box::new(BoolValue::new(column.is_nullable)),
+                    description,
+                ],
+            })
+        }
     }
 
     gitql_object.groups.push(Group { rows });
@@ -206,12 +448,16 @@ fn evaluate_describe_query(
 }
 
 fn evaluate_show_tables_query(env: &mut Environment) -> Result<EvaluationResult, String> {
-    let tables = env.schema.tables_fields_names.keys();
+    let tables = env.schema.tables_names();
+
+    let mut rows: Vec<Row> = Vec::with_capacity(tables.len() + env.views.len());
+    for table in tables {
+        let values: Vec<Box<dyn Value>> = vec![Box::new(TextValue::new(table.to_owned()))];
+        rows.push(Row { values });
+    }
 
-    let mut rows: Vec<Row> = Vec::with_capacity(tables.len());
-    for table in env.schema.tables_fields_names.keys() {
-        let values: Vec<Box<dyn Value>> =
-            vec![Box::new(TextValue::new(table.to_owned().to_owned()))];
+    for view in env.views.keys() {
+        let values: Vec<Box<dyn Value>> = vec![Box::new(TextValue::new(view.to_owned()))];
         rows.push(Row { values });
     }
 
@@ -244,3 +490,125 @@ fn remove_hidden_selected_from_groups(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::first_column;
+    use crate::test_utils::query_rows;
+
+    #[test]
+    fn recursive_common_table() {
+        let rows = query_rows(
+            "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 5) SELECT x FROM n",
+        )
+        .unwrap();
+        assert_eq!(first_column(&rows), ["1", "2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn recursive_common_table_max_depth() {
+        let result = query_rows(
+            "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x > 0) SELECT x FROM n LIMIT 3",
+        );
+        assert!(result.is_err_and(|error| error.contains("maximum recursion depth")));
+    }
+
+    #[test]
+    fn recursive_common_table_union_removes_duplicated_rows() {
+        let rows = query_rows(
+            "WITH RECURSIVE n(x) AS (SELECT 1 UNION SELECT x % 3 + 1 FROM n WHERE x > 0) SELECT x FROM n",
+        )
+        .unwrap();
+        assert_eq!(first_column(&rows), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn create_function_and_call_it() {
+        let rows = query_rows(
+            "CREATE FUNCTION twice(x INT) RETURNS INT AS x * 2; SELECT id, twice(id) FROM items WHERE id < 3",
+        )
+        .unwrap();
+        assert_eq!(rows, [["1", "2"], ["2", "4"]]);
+    }
+
+    #[test]
+    fn create_function_with_existing_name() {
+        let result = query_rows("CREATE FUNCTION lower(x TEXT) RETURNS TEXT AS x");
+        assert!(result.is_err_and(|error| error.contains("already exists")));
+    }
+
+    #[test]
+    fn create_function_with_duplicated_parameter() {
+        let result = query_rows("CREATE FUNCTION add(x INT, x INT) RETURNS INT AS x + x");
+        assert!(result.is_err_and(|error| error.contains("defined more than once")));
+    }
+
+    #[test]
+    fn create_function_with_unresolved_symbol() {
+        let result = query_rows("CREATE FUNCTION other(x INT) RETURNS INT AS y");
+        assert!(result.is_err_and(|error| error.contains("Unresolved parameter name `y`")));
+    }
+
+    #[test]
+    fn create_function_with_wrong_return_type() {
+        let result = query_rows("CREATE FUNCTION add(x INT) RETURNS TEXT AS x + 1");
+        assert!(result.is_err_and(|error| error.contains("don't match return type")));
+    }
+
+    #[test]
+    fn create_function_with_aggregation_or_subquery() {
+        let result = query_rows("CREATE FUNCTION total(x INT) RETURNS INT AS SUM(x)");
+        assert!(result.is_err_and(|error| error.contains("can't be used in function body")));
+
+        let result =
+            query_rows("CREATE FUNCTION first_id(x INT) RETURNS INT AS (SELECT id FROM items)");
+        assert!(result.is_err_and(|error| error.contains("can't be used in function body")));
+    }
+
+    #[test]
+    fn call_function_with_wrong_argument_type() {
+        let result = query_rows(
+            "CREATE FUNCTION twice(x INT) RETURNS INT AS x * 2; SELECT name, twice(name) FROM items",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn create_view_and_select_from_it() {
+        let rows = query_rows(
+            "CREATE VIEW src_items AS SELECT id, name FROM items WHERE parent_id = 'src'; SELECT name FROM src_items",
+        )
+        .unwrap();
+        assert_eq!(first_column(&rows), ["lib", "main"]);
+    }
+
+    #[test]
+    fn describe_view_columns() {
+        let rows =
+            query_rows("CREATE VIEW names AS SELECT id, name FROM items; DESCRIBE names").unwrap();
+        assert_eq!(
+            rows,
+            [
+                ["id", "Int", "true", "Null"],
+                ["name", "Text", "true", "Null"]
+            ]
+        );
+    }
+
+    #[test]
+    fn create_view_with_table_name() {
+        let result = query_rows("CREATE VIEW items AS SELECT id FROM items");
+        assert!(result.is_err_and(|error| error.contains("already a table with name `items`")));
+    }
+
+    #[test]
+    fn drop_view_remove_it() {
+        let result = query_rows(
+            "CREATE VIEW names AS SELECT name FROM items; DROP VIEW names; SELECT name FROM names",
+        );
+        assert!(result.is_err());
+
+        let result = query_rows("DROP VIEW names");
+        assert!(result.is_err_and(|error| error.contains("Cannot find view with name `names`")));
+    }
+}
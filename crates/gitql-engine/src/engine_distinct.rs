@@ -7,6 +7,9 @@ use gitql_ast::statement::Distinct;
 use gitql_core::object::GitQLObject;
 use gitql_core::object::Group;
 use gitql_core::object::Row;
+use gitql_core::values::Value;
+
+use crate::engine_set_operation::DistinctRows;
 
 /// Apply the distinct operator depending on the type of distinct
 pub(crate) fn apply_distinct_operator(
@@ -103,3 +106,36 @@ fn apply_distinct_on_operation(object: &mut GitQLObject, distinct_fields: &[Stri
         object.groups[0].rows.append(&mut new_objects.rows);
     }
 }
+
+/// Keep only the first row of each distinct values and remove the rows that all it's values are NULL,
+/// used for aggregation function with `DISTINCT` arguments
+pub(crate) fn remove_duplicated_values_rows(values_rows: &mut Vec<Vec<Box<dyn Value>>>) {
+    let mut distinct_rows = DistinctRows::default();
+    values_rows.retain(|row_values| {
+        let is_null_row = !row_values.is_empty() && row_values.iter().all(|value| value.is_null());
+        !is_null_row && distinct_rows.insert(row_values)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::query_rows;
+
+    #[test]
+    fn aggregation_distinct_ignore_null_values() {
+        let rows = query_rows("SELECT COUNT(DISTINCT parent_id) FROM items").unwrap();
+        assert_eq!(rows, [["2"]]);
+
+        let rows =
+            query_rows("SELECT COUNT(DISTINCT parent_id) FILTER (WHERE id > 3) AS c FROM items")
+                .unwrap();
+        assert_eq!(rows, [["1"]]);
+    }
+
+    #[test]
+    fn aggregation_of_filtered_empty_group_is_null() {
+        let query = "SELECT SUM(id) FILTER (WHERE id > 10) AS s, COUNT() FILTER (WHERE id > 10) AS c FROM items";
+        let rows = query_rows(query).unwrap();
+        assert_eq!(rows, [["Null", "0"]]);
+    }
+}
@@ -31,6 +31,8 @@ use gitql_ast::expression::RegexExpr;
 use gitql_ast::expression::RowExpr;
 use gitql_ast::expression::SliceExpr;
 use gitql_ast::expression::StringExpr;
+use gitql_ast::expression::SubqueryExpr;
+use gitql_ast::expression::SubqueryKind;
 use gitql_ast::expression::SymbolExpr;
 use gitql_ast::expression::UnaryExpr;
 use gitql_ast::operator::ArithmeticOperator;
@@ -209,6 +211,10 @@ pub fn evaluate_expression(
                 .unwrap();
             evaluate_member_access(env, expr, titles, object)
         }
+        Subquery => {
+            let expr = expression.as_any().downcast_ref::<SubqueryExpr>().unwrap();
+            evaluate_subquery(env, expr)
+        }
         Null => Ok(Box::new(NullValue)),
     }
 }
@@ -358,6 +364,12 @@ fn evaluate_comparison(
 ) -> Result<Box<dyn Value>, String> {
     let lhs = evaluate_expression(env, &expr.left, titles, object)?;
     let rhs = evaluate_expression(env, &expr.right, titles, object)?;
+
+    // NULL values of nullable columns don't satisfy any comparison except `<=>`
+    if expr.operator != ComparisonOperator::NullSafeEqual && (lhs.is_null() || rhs.is_null()) {
+        return Ok(Box::new(BoolValue::new(false)));
+    }
+
     match expr.operator {
         ComparisonOperator::Greater => lhs.gt_op(&rhs),
         ComparisonOperator::GreaterEqual => lhs.gte_op(&rhs),
@@ -486,8 +498,18 @@ fn evaluate_call(
     for arg in expr.arguments.iter() {
         arguments.push(evaluate_expression(env, arg, titles, object)?);
     }
-    let function = env.std_function(function_name).unwrap();
-    Ok(function(&arguments))
+
+    if let Some(function) = env.std_function(function_name) {
+        return Ok(function(&arguments));
+    }
+
+    // User defined function body is evaluated with the arguments bound to the parameters names
+    match env.user_function(function_name) {
+        Some(function) => {
+            evaluate_expression(env, &function.body, &function.parameters_names, &arguments)
+        }
+        None => Err(format!("Function `{function_name}` is not defined")),
+    }
 }
 
 fn evaluate_benchmark_call(
@@ -567,6 +589,16 @@ fn evaluate_in(
 ) -> Result<Box<dyn Value>, String> {
     let argument = evaluate_expression(env, &expr.argument, titles, object)?;
     for value_expr in &expr.values {
+        // Subquery values are already evaluated, so compare with them directly
+        if value_expr.kind() == Subquery {
+            let subquery = value_expr.as_any().downcast_ref::<SubqueryExpr>().unwrap();
+            let values = subquery_values(env, subquery)?;
+            if values.iter().any(|value| argument.equals(value)) {
+                return Ok(Box::new(BoolValue::new(!expr.has_not_keyword)));
+            }
+            continue;
+        }
+
         let value = evaluate_expression(env, value_expr, titles, object)?;
         if argument.equals(&value) {
             return Ok(Box::new(BoolValue::new(!expr.has_not_keyword)));
@@ -633,3 +665,63 @@ fn evaluate_member_access(
     }
     Err("Invalid value for Member access expression".to_owned())
 }
+
+fn evaluate_subquery(env: &mut Environment, expr: &SubqueryExpr) -> Result<Box<dyn Value>, String> {
+    let values = subquery_values(env, expr)?;
+    match expr.kind {
+        SubqueryKind::Scalar => match values.len() {
+            0 => Ok(Box::new(NullValue)),
+            1 => Ok(values[0].clone()),
+            _ => Err("Subquery used as expression returned more than one row".to_owned()),
+        },
+        SubqueryKind::List => Ok(Box::new(ArrayValue::new(
+            values.to_vec(),
+            expr.result_type.clone(),
+        ))),
+    }
+}
+
+fn subquery_values<'a>(
+    env: &'a Environment,
+    expr: &SubqueryExpr,
+) -> Result<&'a Vec<Box<dyn Value>>, String> {
+    env.subqueries_values
+        .get(&expr.name)
+        .ok_or_else(|| "The value of subquery may be not calculated yet".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::first_column;
+    use crate::test_utils::query_rows;
+
+    #[test]
+    fn equal_with_null_value_is_false() {
+        let rows = query_rows("SELECT id FROM items WHERE parent_id = 'root'").unwrap();
+        assert_eq!(first_column(&rows), ["2", "3"]);
+    }
+
+    #[test]
+    fn not_equal_with_null_value_is_false() {
+        let rows = query_rows("SELECT id FROM items WHERE parent_id != 'root'").unwrap();
+        assert_eq!(first_column(&rows), ["4", "5"]);
+    }
+
+    #[test]
+    fn greater_with_null_value_is_false() {
+        let rows = query_rows("SELECT id FROM items WHERE parent_id > 'a'").unwrap();
+        assert_eq!(first_column(&rows), ["2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn less_with_null_value_is_false() {
+        let rows = query_rows("SELECT id FROM items WHERE parent_id < 'z'").unwrap();
+        assert_eq!(first_column(&rows), ["2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn is_null_matches_null_value() {
+        let rows = query_rows("SELECT id FROM items WHERE parent_id IS NULL").unwrap();
+        assert_eq!(first_column(&rows), ["1"]);
+    }
+}
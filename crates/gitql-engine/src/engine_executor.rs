@@ -1,34 +1,54 @@
 use std::cmp;
 use std::collections::HashMap;
+use std::time::Instant;
 
 use gitql_ast::expression::Expr;
 use gitql_ast::expression::ExprKind;
 use gitql_ast::statement::AggregateValue;
 use gitql_ast::statement::AggregationsStatement;
-use gitql_ast::statement::HavingStatement;
-use gitql_ast::statement::LimitStatement;
-use gitql_ast::statement::OffsetStatement;
-use gitql_ast::statement::QualifyStatement;
+use gitql_ast::statement::GroupByStatement;
+use gitql_ast::statement::Join;
 use gitql_ast::statement::SelectStatement;
 use gitql_ast::statement::Statement;
-use gitql_ast::statement::WhereStatement;
+use gitql_ast::statement::TableFunctionCall;
+use gitql_ast::statement::TableSelection;
 use gitql_core::environment::Environment;
 use gitql_core::object::GitQLObject;
 use gitql_core::object::Group;
 use gitql_core::object::Row;
+use gitql_core::values::integer::IntValue;
 use gitql_core::values::null::NullValue;
 use gitql_core::values::Value;
 
 use crate::data_provider::DataProvider;
+use crate::data_provider::FileDataProvider;
+use crate::data_provider::RowsIterator;
+use crate::engine::evaluate_subquery;
+use crate::engine_distinct::apply_distinct_operator;
+use crate::engine_distinct::remove_duplicated_values_rows;
 use crate::engine_evaluator::evaluate_expression;
+use crate::engine_explain::object_rows_count;
+use crate::engine_explain::record_node_profile;
+use crate::engine_explain::ExplainNodeId;
+use crate::engine_explain::NodeProfile;
+use crate::engine_explain::QueryProfile;
 use crate::engine_filter::apply_filter_operation;
+use crate::engine_group::apply_grouping_sets_nulls;
+use crate::engine_group::evaluate_grouping_value;
 use crate::engine_group::execute_group_by_statement;
 use crate::engine_join::apply_join_operation;
+use crate::engine_ordering::compare_arguments_values;
 use crate::engine_ordering::execute_order_by_statement;
 use crate::engine_output_into::execute_into_statement;
+use crate::engine_planner::FilterKind;
+use crate::engine_planner::JoinNode;
+use crate::engine_planner::LogicalPlan;
+use crate::engine_planner::ScanNode;
+use crate::engine_provider_hints::ScanHints;
 use crate::engine_window_functions::execute_window_functions_statement;
 
 #[allow(clippy::borrowed_box)]
+#[allow(clippy::too_many_arguments)]
 pub fn execute_statement(
     env: &mut Environment,
     statement: &Statement,
@@ -36,6 +56,7 @@ pub fn execute_statement(
     gitql_object: &mut GitQLObject,
     alias_table: &mut HashMap<String, String>,
     hidden_selection: &HashMap<String, Vec<String>>,
+    scan_hints: &ScanHints,
     has_group_by_statement: bool,
 ) -> Result<(), String> {
     match statement {
@@ -46,34 +67,281 @@ pub fn execute_statement(
             data_provider,
             gitql_object,
             hidden_selection,
+            scan_hints,
         ),
-        Statement::Where(statement) => execute_where_statement(env, statement, gitql_object),
-        Statement::Having(statement) => execute_having_statement(env, statement, gitql_object),
-        Statement::Limit(statement) => execute_limit_statement(statement, gitql_object),
-        Statement::Offset(statement) => execute_offset_statement(env, statement, gitql_object),
+        Statement::Where(statement) => {
+            execute_filter(env, &FilterKind::Where, &statement.condition, gitql_object)
+        }
+        Statement::Having(statement) => {
+            execute_filter(env, &FilterKind::Having, &statement.condition, gitql_object)
+        }
+        Statement::Limit(statement) => execute_limit(statement.count, gitql_object),
+        Statement::Offset(statement) => execute_offset(env, &statement.start, gitql_object),
         Statement::OrderBy(statement) => {
             if gitql_object.len() > 1 {
                 gitql_object.flat();
             }
             let main_group_index = 0;
-            execute_order_by_statement(env, statement, gitql_object, main_group_index)
+            execute_order_by_statement(env, statement, gitql_object, main_group_index, None)
+        }
+        Statement::GroupBy(statement) => {
+            execute_group_by_statement(env, statement, gitql_object)?;
+            Ok(())
         }
-        Statement::GroupBy(statement) => execute_group_by_statement(env, statement, gitql_object),
         Statement::AggregateFunction(statement) => execute_aggregation_functions_statement(
             env,
             statement,
             gitql_object,
             alias_table,
             has_group_by_statement,
+            None,
         ),
         Statement::WindowFunction(statement) => {
             execute_window_functions_statement(env, statement, gitql_object, alias_table)
         }
-        Statement::Qualify(statement) => execute_qualify_statement(env, statement, gitql_object),
+        Statement::Qualify(statement) => execute_filter(
+            env,
+            &FilterKind::Qualify,
+            &statement.condition,
+            gitql_object,
+        ),
         Statement::Into(statement) => execute_into_statement(statement, gitql_object),
     }
 }
 
+/// Context of the logical plan execution
+#[allow(clippy::borrowed_box)]
+pub(crate) struct PlanContext<'a> {
+    pub data_provider: &'a Box<dyn DataProvider>,
+    pub alias_table: &'a mut HashMap<String, String>,
+    pub hidden_selections: &'a HashMap<String, Vec<String>>,
+    pub has_group_by_statement: bool,
+    pub profile: Option<&'a mut QueryProfile>,
+}
+
+/// Execute the logical plan from the leaves to the root, the node id is it's pre order index,
+/// return false if the query finished early because the selected rows are empty
+pub(crate) fn execute_logical_plan(
+    env: &mut Environment,
+    context: &mut PlanContext,
+    plan: &LogicalPlan,
+    node_id: usize,
+    gitql_object: &mut GitQLObject,
+) -> Result<bool, String> {
+    let (selected_expr_titles, selected_expr, source) = match plan {
+        LogicalPlan::Project(node) => (
+            node.selected_expr_titles.as_slice(),
+            node.selected_expr.as_slice(),
+            Some((node.input.as_ref(), node_id + 1)),
+        ),
+        LogicalPlan::Scan(_) | LogicalPlan::Join(_) => (&[][..], &[][..], Some((plan, node_id))),
+        _ => (&[][..], &[][..], None),
+    };
+
+    if let Some((source, source_id)) = source {
+        let mut selected_rows = execute_plan_source(
+            env,
+            context,
+            source,
+            source_id,
+            gitql_object,
+            selected_expr_titles,
+            selected_expr,
+        )?;
+
+        if let LogicalPlan::Project(_) = plan {
+            let start = Instant::now();
+            if !selected_expr.is_empty() {
+                execute_expression_selection(
+                    env,
+                    &mut selected_rows,
+                    &gitql_object.titles,
+                    selected_expr_titles,
+                    selected_expr,
+                )?;
+            }
+
+            let rows_count = selected_rows.len();
+            record_node_profile(
+                &mut context.profile,
+                ExplainNodeId::Plan(node_id),
+                Some(rows_count),
+                rows_count,
+                start,
+            );
+        }
+
+        let is_empty = selected_rows.is_empty();
+        gitql_object.groups.push(Group {
+            rows: selected_rows,
+        });
+
+        // If the main group is empty, no need to perform other nodes
+        return Ok(!is_empty);
+    }
+
+    if !execute_logical_plan(env, context, plan.inputs()[0], node_id + 1, gitql_object)? {
+        return Ok(false);
+    }
+
+    let start = Instant::now();
+    let rows_in = object_rows_count(gitql_object);
+    match plan {
+        LogicalPlan::Filter(node) => {
+            execute_filter(env, &node.kind, &node.condition, gitql_object)?;
+        }
+        LogicalPlan::Aggregate(node) => {
+            let mut groups_sets: Vec<usize> = vec![];
+            if let Some(group_by) = &node.group_by {
+                groups_sets = execute_group_by_statement(env, group_by, gitql_object)?;
+            }
+
+            let grouping = node
+                .group_by
+                .as_ref()
+                .map(|group_by| (group_by, groups_sets.as_slice()));
+
+            if let Some(aggregations) = &node.aggregations {
+                execute_aggregation_functions_statement(
+                    env,
+                    aggregations,
+                    gitql_object,
+                    context.alias_table,
+                    context.has_group_by_statement,
+                    grouping,
+                )?;
+            }
+
+            if let Some((group_by, groups_sets)) = grouping {
+                apply_grouping_sets_nulls(group_by, groups_sets, gitql_object, context.alias_table);
+            }
+        }
+        LogicalPlan::Window(node) => {
+            execute_window_functions_statement(
+                env,
+                &node.window_functions,
+                gitql_object,
+                context.alias_table,
+            )?;
+        }
+        LogicalPlan::Sort(node) => {
+            if gitql_object.len() > 1 {
+                gitql_object.flat();
+            }
+
+            // Only the top rows are needed by the next limit
+            execute_order_by_statement(env, &node.order_by, gitql_object, 0, node.fetch)?;
+        }
+        LogicalPlan::Limit(node) => {
+            if let Some(offset) = &node.offset {
+                execute_offset(env, offset, gitql_object)?;
+            }
+
+            if let Some(count) = node.count {
+                execute_limit(count, gitql_object)?;
+            }
+        }
+        LogicalPlan::Distinct(node) => {
+            let hidden_selections: Vec<String> = context
+                .hidden_selections
+                .values()
+                .flatten()
+                .cloned()
+                .collect();
+            apply_distinct_operator(&node.distinct, gitql_object, &hidden_selections);
+        }
+        LogicalPlan::Scan(_) | LogicalPlan::Join(_) | LogicalPlan::Project(_) => {}
+    }
+
+    let rows_out = object_rows_count(gitql_object);
+    record_node_profile(
+        &mut context.profile,
+        ExplainNodeId::Plan(node_id),
+        Some(rows_in),
+        rows_out,
+        start,
+    );
+
+    Ok(true)
+}
+
+/// Scan the tables of the plan source and join them, then append the tables titles to the object
+fn execute_plan_source(
+    env: &mut Environment,
+    context: &mut PlanContext,
+    source: &LogicalPlan,
+    source_id: usize,
+    gitql_object: &mut GitQLObject,
+    selected_expr_titles: &[String],
+    selected_expr: &[Box<dyn Expr>],
+) -> Result<Vec<Row>, String> {
+    let mut scans: Vec<(usize, &ScanNode)> = vec![];
+    let mut joins: Vec<(usize, &JoinNode)> = vec![];
+    collect_plan_source(source, source_id, &mut scans, &mut joins);
+
+    let mut scanned_tables: Vec<ScannedTable> = Vec::with_capacity(scans.len());
+    for (scan_id, scan) in scans {
+        let start = Instant::now();
+        let table_scan = TableScan {
+            table_selection: &scan.table_selection,
+            hints: &scan.hints,
+            filter: scan.filter.as_ref(),
+        };
+
+        let scanned_table = execute_table_scan(
+            env,
+            context.data_provider,
+            &table_scan,
+            context.alias_table,
+            context.hidden_selections,
+            selected_expr_titles,
+            selected_expr,
+        )?;
+
+        record_node_profile(
+            &mut context.profile,
+            ExplainNodeId::Plan(scan_id),
+            None,
+            scanned_table.rows.len(),
+            start,
+        );
+        scanned_tables.push(scanned_table);
+    }
+
+    let joins_list: Vec<&Join> = joins.iter().map(|(_, node)| &node.join).collect();
+    let (selected_rows, joins_profiles) =
+        join_scanned_tables(env, scanned_tables, &joins_list, gitql_object)?;
+
+    if let Some(profile) = context.profile.as_deref_mut() {
+        for ((join_id, _), join_profile) in joins.iter().zip(joins_profiles) {
+            profile
+                .nodes_profiles
+                .insert(ExplainNodeId::Plan(*join_id), join_profile);
+        }
+    }
+
+    Ok(selected_rows)
+}
+
+/// Collect the scans from left to right and the joins from bottom to top with their ids
+fn collect_plan_source<'a>(
+    plan: &'a LogicalPlan,
+    node_id: usize,
+    scans: &mut Vec<(usize, &'a ScanNode)>,
+    joins: &mut Vec<(usize, &'a JoinNode)>,
+) {
+    match plan {
+        LogicalPlan::Scan(node) => scans.push((node_id, node)),
+        LogicalPlan::Join(node) => {
+            collect_plan_source(&node.left, node_id + 1, scans, joins);
+            let right_id = node_id + 1 + node.left.nodes_count();
+            collect_plan_source(&node.right, right_id, scans, joins);
+            joins.push((node_id, node));
+        }
+        _ => {}
+    }
+}
+
 #[allow(clippy::borrowed_box)]
 fn execute_select_statement(
     env: &mut Environment,
@@ -82,48 +350,178 @@ fn execute_select_statement(
     data_provider: &Box<dyn DataProvider>,
     gitql_object: &mut GitQLObject,
     hidden_selections: &HashMap<String, Vec<String>>,
+    scan_hints: &ScanHints,
 ) -> Result<(), String> {
-    let mut selected_rows_per_table: HashMap<String, Vec<Row>> = HashMap::new();
-    let mut hidden_selection_count_per_table: HashMap<String, usize> = HashMap::new();
+    let mut scanned_tables: Vec<ScannedTable> =
+        Vec::with_capacity(statement.table_selections.len());
+    for table_selection in &statement.table_selections {
+        let table_scan = TableScan {
+            table_selection,
+            hints: scan_hints,
+            filter: None,
+        };
 
-    let mut titles: Vec<String> = vec![];
-    let mut hidden_sum = 0;
+        scanned_tables.push(execute_table_scan(
+            env,
+            data_provider,
+            &table_scan,
+            alias_table,
+            hidden_selections,
+            &statement.selected_expr_titles,
+            &statement.selected_expr,
+        )?);
+    }
 
-    for table_selection in &statement.table_selections {
-        // Select objects from the target table
-        let table_name = &table_selection.table_name;
-        let selected_columns = &mut table_selection.columns_names.to_owned();
-
-        // Insert Hidden selection items for this table first
-        let mut hidden_selection_count = 0;
-        if let Some(table_hidden_selection) = hidden_selections.get(table_name) {
-            for hidden_selection in table_hidden_selection {
-                if !selected_columns.contains(hidden_selection) {
-                    selected_columns.insert(0, hidden_selection.to_string());
-                    hidden_selection_count += 1;
-                }
-            }
-        }
+    // Apply joins operations if exists
+    let joins: Vec<&Join> = statement.joins.iter().collect();
+    let (mut selected_rows, _) = join_scanned_tables(env, scanned_tables, &joins, gitql_object)?;
 
-        hidden_selection_count_per_table.insert(table_name.to_string(), hidden_selection_count);
+    // Execute Selected expressions if exists
+    if !statement.selected_expr.is_empty() {
+        execute_expression_selection(
+            env,
+            &mut selected_rows,
+            &gitql_object.titles,
+            &statement.selected_expr_titles,
+            &statement.selected_expr,
+        )?;
+    }
+
+    let main_group = Group {
+        rows: selected_rows,
+    };
+
+    gitql_object.groups.push(main_group);
+
+    Ok(())
+}
+
+/// The table selection to scan with the hints and the pushed down filter
+#[allow(clippy::borrowed_box)]
+struct TableScan<'a> {
+    table_selection: &'a TableSelection,
+    hints: &'a ScanHints,
+    filter: Option<&'a Box<dyn Expr>>,
+}
+
+/// The rows of the scanned table and the titles of it's columns
+struct ScannedTable {
+    table_name: String,
+    rows: Vec<Row>,
+    titles: Vec<String>,
+    hidden_selection_count: usize,
+}
+
+#[allow(clippy::borrowed_box)]
+fn execute_table_scan(
+    env: &mut Environment,
+    data_provider: &Box<dyn DataProvider>,
+    table_scan: &TableScan,
+    alias_table: &HashMap<String, String>,
+    hidden_selections: &HashMap<String, Vec<String>>,
+    selected_expr_titles: &[String],
+    selected_expr: &[Box<dyn Expr>],
+) -> Result<ScannedTable, String> {
+    // Select objects from the target table
+    let table_selection = table_scan.table_selection;
+    let table_name = &table_selection.table_name;
+    let selected_columns = &mut table_selection.columns_names.to_owned();
 
-        // Calculate list of titles once per table
-        let mut table_titles = vec![];
-        for selected_column in selected_columns.iter_mut() {
-            table_titles.push(resolve_actual_column_name(alias_table, selected_column));
+    // Insert Hidden selection items for this table first
+    let mut hidden_selection_count = 0;
+    if let Some(table_hidden_selection) = hidden_selections.get(table_name) {
+        for hidden_selection in table_hidden_selection {
+            if !selected_columns.contains(hidden_selection) {
+                selected_columns.insert(0, hidden_selection.to_string());
+                hidden_selection_count += 1;
+            }
         }
+    }
 
-        // Call the provider only if table name is not empty
-        let selected_rows: Vec<Row> = if table_name.is_empty() {
-            vec![Row { values: vec![] }]
-        } else {
-            data_provider.provide(table_name, selected_columns)?
+    // Calculate list of titles once per table
+    let mut table_titles = vec![];
+    for selected_column in selected_columns.iter_mut() {
+        table_titles.push(resolve_actual_column_name(alias_table, selected_column));
+    }
+
+    // Evaluate the derived table query or table function, read the file,
+    // or call the provider if table name is not empty
+    let scan_hints = table_scan.hints;
+    let mut selected_rows: Vec<Row> = if let Some(derived_query) = &table_selection.derived_query {
+        let derived_object = evaluate_subquery(env, data_provider, (**derived_query).clone())?;
+        select_object_columns(&derived_object, selected_columns)
+    } else if let Some(function_call) = &table_selection.table_function {
+        let function_object = evaluate_table_function(env, function_call)?;
+        select_object_columns(&function_object, selected_columns)
+    } else if let Some(file_source) = &table_selection.file_source {
+        let file_provider = FileDataProvider {
+            source: file_source.clone(),
         };
+        file_provider.provide(table_name, selected_columns)?
+    } else if let Some(common_table) = env.common_tables.get(table_name) {
+        select_object_columns(common_table, selected_columns)
+    } else if table_name.is_empty() {
+        vec![Row { values: vec![] }]
+    } else if let Some(limit) = scan_hints.limit {
+        let rows =
+            data_provider.provide_iter(table_name, selected_columns, &scan_hints.provider_hints)?;
+        collect_provided_rows(
+            env,
+            rows,
+            limit,
+            &table_titles,
+            selected_expr_titles,
+            selected_expr,
+            scan_hints,
+        )?
+    } else {
+        data_provider.provide_with_hints(
+            table_name,
+            selected_columns,
+            &scan_hints.provider_hints,
+        )?
+    };
+
+    // Filter the table rows by the condition pushed down from the where statement
+    if let Some(filter) = table_scan.filter {
+        apply_filter_operation(env, filter, &table_titles, &mut selected_rows)?;
+    }
+
+    Ok(ScannedTable {
+        table_name: table_name.to_string(),
+        rows: selected_rows,
+        titles: table_titles,
+        hidden_selection_count,
+    })
+}
+
+/// Join the rows of the scanned tables and append their titles to the object,
+/// return the joined rows and the profile of each join
+fn join_scanned_tables(
+    env: &mut Environment,
+    scanned_tables: Vec<ScannedTable>,
+    joins: &[&Join],
+    gitql_object: &mut GitQLObject,
+) -> Result<(Vec<Row>, Vec<NodeProfile>), String> {
+    let mut selected_rows_per_table: HashMap<String, Vec<Row>> = HashMap::new();
+    let mut hidden_selection_count_per_table: HashMap<String, usize> = HashMap::new();
+    let mut columns_count_per_table: HashMap<String, usize> = HashMap::new();
+    let mut tables_names: Vec<String> = Vec::with_capacity(scanned_tables.len());
 
-        selected_rows_per_table.insert(table_name.to_string(), selected_rows);
+    let mut titles: Vec<String> = vec![];
+    let mut hidden_sum = 0;
+
+    for scanned_table in scanned_tables {
+        let table_name = scanned_table.table_name;
+        let hidden_selection_count = scanned_table.hidden_selection_count;
+        hidden_selection_count_per_table.insert(table_name.to_string(), hidden_selection_count);
+        columns_count_per_table.insert(table_name.to_string(), scanned_table.titles.len());
+        selected_rows_per_table.insert(table_name.to_string(), scanned_table.rows);
+        tables_names.push(table_name);
 
         // Append hidden selection in the right position
         // at the end all hidden selections will be first
+        let table_titles = &scanned_table.titles;
         let hidden_selection_titles = &table_titles[..hidden_selection_count];
         titles.splice(hidden_sum..hidden_sum, hidden_selection_titles.to_vec());
 
@@ -137,34 +535,129 @@ fn execute_select_statement(
 
     // Apply joins operations if exists
     let mut selected_rows: Vec<Row> = vec![];
-    apply_join_operation(
+    let joins_profiles = apply_join_operation(
         env,
         &mut selected_rows,
-        &statement.joins,
-        &statement.table_selections,
+        joins,
+        &tables_names,
         &mut selected_rows_per_table,
         &hidden_selection_count_per_table,
+        &columns_count_per_table,
         &gitql_object.titles,
     )?;
 
-    // Execute Selected expressions if exists
-    if !statement.selected_expr.is_empty() {
-        execute_expression_selection(
-            env,
-            &mut selected_rows,
-            &gitql_object.titles,
-            &statement.selected_expr_titles,
-            &statement.selected_expr,
-        )?;
+    Ok((selected_rows, joins_profiles))
+}
+
+/// Consume the provided rows, and stop once the number of rows that
+/// satisfy the where condition reach the limit
+fn collect_provided_rows(
+    env: &mut Environment,
+    rows: RowsIterator,
+    limit: usize,
+    titles: &[String],
+    selected_expr_titles: &[String],
+    selected_expr: &[Box<dyn Expr>],
+    scan_hints: &ScanHints,
+) -> Result<Vec<Row>, String> {
+    let mut selected_rows: Vec<Row> = vec![];
+    if limit == 0 {
+        return Ok(selected_rows);
     }
 
-    let main_group = Group {
-        rows: selected_rows,
+    for row in rows {
+        let mut row = row?;
+        if let Some(condition) = &scan_hints.condition {
+            // Selected expressions can be referenced in the condition, so must be evaluated first
+            execute_expression_selection(
+                env,
+                std::slice::from_mut(&mut row),
+                titles,
+                selected_expr_titles,
+                selected_expr,
+            )?;
+
+            // Rows are removed only if the condition is evaluated to false like the where statement
+            let value = evaluate_expression(env, condition, titles, &row.values)?;
+            if value.as_bool() == Some(false) {
+                continue;
+            }
+        }
+
+        selected_rows.push(row);
+
+        // Stop before pulling the next row from the provider
+        if selected_rows.len() >= limit {
+            break;
+        }
+    }
+
+    Ok(selected_rows)
+}
+
+/// Evaluate the table function arguments and call it, the object titles are the function columns names
+fn evaluate_table_function(
+    env: &mut Environment,
+    function_call: &TableFunctionCall,
+) -> Result<GitQLObject, String> {
+    let function_name = function_call.function_name.as_str();
+    let mut arguments = Vec::with_capacity(function_call.arguments.len());
+    for argument in function_call.arguments.iter() {
+        arguments.push(evaluate_expression(env, argument, &[], &vec![])?);
+    }
+
+    let titles = env
+        .table_function_signature(function_name)
+        .map(|signature| {
+            signature
+                .columns
+                .iter()
+                .map(|column| column.name.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let Some(function) = env.table_function(function_name).cloned() else {
+        return Err(format!(
+            "Can't find table function with name `{function_name}`"
+        ));
     };
 
-    gitql_object.groups.push(main_group);
+    let rows = function(&arguments)?;
+    Ok(GitQLObject {
+        titles,
+        groups: vec![Group { rows }],
+    })
+}
 
-    Ok(())
+/// Select the rows of derived or common table with only the selected columns in the same order
+fn select_object_columns(gitql_object: &GitQLObject, selected_columns: &[String]) -> Vec<Row> {
+    // Map each selected column to it's index in the table, expressions columns has no index
+    let columns_indices: Vec<Option<usize>> = selected_columns
+        .iter()
+        .map(|column_name| {
+            gitql_object
+                .titles
+                .iter()
+                .position(|title| title == column_name)
+        })
+        .collect();
+
+    let mut rows: Vec<Row> = vec![];
+    for group in gitql_object.groups.iter() {
+        for row in group.rows.iter() {
+            let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(columns_indices.len());
+            for column_index in columns_indices.iter() {
+                match column_index {
+                    Some(index) => values.push(row.values[*index].clone()),
+                    None => values.push(Box::new(NullValue)),
+                }
+            }
+            rows.push(Row { values });
+        }
+    }
+
+    rows
 }
 
 #[inline(always)]
@@ -211,43 +704,26 @@ fn execute_expression_selection(
     Ok(())
 }
 
-fn execute_where_statement(
-    env: &mut Environment,
-    statement: &WhereStatement,
-    gitql_object: &mut GitQLObject,
-) -> Result<(), String> {
-    if gitql_object.is_empty() {
-        return Ok(());
-    }
-
-    apply_filter_operation(
-        env,
-        &statement.condition,
-        &gitql_object.titles,
-        &mut gitql_object.groups[0].rows,
-    )?;
-
-    Ok(())
-}
-
-fn execute_having_statement(
+/// Remove the rows that doesn't satisfy the condition from the main group,
+/// the groups are merged first for `HAVING` because group by is executed before it
+#[allow(clippy::borrowed_box)]
+fn execute_filter(
     env: &mut Environment,
-    statement: &HavingStatement,
+    kind: &FilterKind,
+    condition: &Box<dyn Expr>,
     gitql_object: &mut GitQLObject,
 ) -> Result<(), String> {
     if gitql_object.is_empty() {
         return Ok(());
     }
 
-    if gitql_object.len() > 1 {
+    if *kind == FilterKind::Having && gitql_object.len() > 1 {
         gitql_object.flat()
     }
 
-    // Perform where command only on the first group
-    // because group by command not executed yet
     apply_filter_operation(
         env,
-        &statement.condition,
+        condition,
         &gitql_object.titles,
         &mut gitql_object.groups[0].rows,
     )?;
@@ -255,29 +731,7 @@ fn execute_having_statement(
     Ok(())
 }
 
-fn execute_qualify_statement(
-    env: &mut Environment,
-    statement: &QualifyStatement,
-    gitql_object: &mut GitQLObject,
-) -> Result<(), String> {
-    if gitql_object.is_empty() {
-        return Ok(());
-    }
-
-    apply_filter_operation(
-        env,
-        &statement.condition,
-        &gitql_object.titles,
-        &mut gitql_object.groups[0].rows,
-    )?;
-
-    Ok(())
-}
-
-fn execute_limit_statement(
-    statement: &LimitStatement,
-    gitql_object: &mut GitQLObject,
-) -> Result<(), String> {
+pub(crate) fn execute_limit(count: usize, gitql_object: &mut GitQLObject) -> Result<(), String> {
     if gitql_object.is_empty() {
         return Ok(());
     }
@@ -287,16 +741,17 @@ fn execute_limit_statement(
     }
 
     let main_group: &mut Group = &mut gitql_object.groups[0];
-    if statement.count <= main_group.len() {
-        main_group.rows.drain(statement.count..main_group.len());
+    if count <= main_group.len() {
+        main_group.rows.drain(count..main_group.len());
     }
 
     Ok(())
 }
 
-fn execute_offset_statement(
+#[allow(clippy::borrowed_box)]
+pub(crate) fn execute_offset(
     env: &mut Environment,
-    statement: &OffsetStatement,
+    start: &Box<dyn Expr>,
     gitql_object: &mut GitQLObject,
 ) -> Result<(), String> {
     if gitql_object.is_empty() {
@@ -308,12 +763,11 @@ fn execute_offset_statement(
     }
 
     let main_group: &mut Group = &mut gitql_object.groups[0];
-    let start = &evaluate_expression(
-        env,
-        &statement.start,
-        &gitql_object.titles,
-        &main_group.rows[0].values,
-    )?;
+    if main_group.is_empty() {
+        return Ok(());
+    }
+
+    let start = &evaluate_expression(env, start, &gitql_object.titles, &main_group.rows[0].values)?;
 
     // If start evaluates to NULL, it is treated the same as OFFSET 0
     if start.is_null() {
@@ -333,6 +787,7 @@ fn execute_aggregation_functions_statement(
     gitql_object: &mut GitQLObject,
     alias_table: &HashMap<String, String>,
     is_query_has_group_by: bool,
+    grouping: Option<(&GroupByStatement, &[usize])>,
 ) -> Result<(), String> {
     // Make sure you have at least one aggregation function to calculate
     let aggregations_map = &statement.aggregations;
@@ -341,7 +796,7 @@ fn execute_aggregation_functions_statement(
     }
 
     // We should run aggregation function for each group
-    for group in &mut gitql_object.groups {
+    for (group_index, group) in gitql_object.groups.iter_mut().enumerate() {
         // No need to apply all aggregation if there is no selected elements
         if group.is_empty() {
             continue;
@@ -349,7 +804,7 @@ fn execute_aggregation_functions_statement(
 
         // Resolve all aggregations functions first
         for (result_column_name, aggregation) in aggregations_map {
-            if let AggregateValue::Function(function, arguments) = aggregation {
+            if let AggregateValue::Function(function) = aggregation {
                 // Get alias name if exists or column name by default
                 let column_name = resolve_actual_column_name(alias_table, result_column_name);
                 let column_index = gitql_object
@@ -362,9 +817,18 @@ fn execute_aggregation_functions_statement(
                 let mut group_arguments: Vec<Vec<Box<dyn Value>>> =
                     Vec::with_capacity(group.rows.len());
                 for object in &mut group.rows {
+                    // Pass only the rows that match the `FILTER` condition to the function
+                    if let Some(filter) = &function.filter {
+                        let condition =
+                            evaluate_expression(env, filter, &gitql_object.titles, &object.values)?;
+                        if !condition.as_bool().unwrap_or(false) {
+                            continue;
+                        }
+                    }
+
                     let mut row_values: Vec<Box<dyn Value>> =
                         Vec::with_capacity(object.values.len());
-                    for argument in arguments {
+                    for argument in &function.arguments {
                         let value = evaluate_expression(
                             env,
                             argument,
@@ -378,8 +842,25 @@ fn execute_aggregation_functions_statement(
                     group_arguments.push(row_values);
                 }
 
+                if function.distinct {
+                    remove_duplicated_values_rows(&mut group_arguments);
+                }
+
+                // Sort the group values by the `WITHIN GROUP` ordering values that are the last arguments
+                if let Some(within_group) = &function.within_group {
+                    let ordering_start = function.arguments.len() - within_group.arguments.len();
+                    group_arguments.sort_by(|a, b| {
+                        compare_arguments_values(
+                            within_group,
+                            &a[ordering_start..],
+                            &b[ordering_start..],
+                        )
+                    });
+                }
+
                 // Get the target aggregation function
-                let aggregation_function = env.aggregation_function(function).unwrap();
+                let aggregation_function =
+                    env.aggregation_function(&function.function_name).unwrap();
                 let result = &aggregation_function(&group_arguments);
 
                 // Insert the calculated value in the group objects
@@ -393,6 +874,35 @@ fn execute_aggregation_functions_statement(
             }
         }
 
+        // Resolve `GROUPING` values from the grouping set of the group
+        for (result_column_name, aggregation) in aggregations_map {
+            if let AggregateValue::Grouping(arguments) = aggregation {
+                let column_name = resolve_actual_column_name(alias_table, result_column_name);
+                let column_index = gitql_object
+                    .titles
+                    .iter()
+                    .position(|r| r.eq(&column_name))
+                    .unwrap();
+
+                let grouping_value = match grouping {
+                    Some((group_by, groups_sets)) => {
+                        let set_index = groups_sets.get(group_index).copied().unwrap_or(0);
+                        evaluate_grouping_value(group_by, set_index, arguments)
+                    }
+                    None => 0,
+                };
+
+                let result: Box<dyn Value> = Box::new(IntValue::new(grouping_value));
+                for object in &mut group.rows {
+                    if column_index < object.values.len() {
+                        object.values[column_index] = result.clone();
+                    } else {
+                        object.values.push(result.clone());
+                    }
+                }
+            }
+        }
+
         // Resolve aggregations expressions
         for (result_column_name, aggregation) in aggregations_map {
             if let AggregateValue::Expression(expr) = aggregation {
@@ -435,3 +945,82 @@ pub fn resolve_actual_column_name(alias_table: &HashMap<String, String>, name: &
 
     name.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use gitql_core::values::integer::IntValue;
+
+    use super::*;
+    use crate::test_utils::first_column;
+    use crate::test_utils::query_rows;
+    use crate::test_utils::test_environment;
+
+    #[test]
+    fn collect_provided_rows_stops_pulling_at_limit() {
+        let mut env = test_environment();
+        let pulled_rows = Cell::new(0);
+        let rows: RowsIterator = Box::new((0..10).map(|index| {
+            pulled_rows.set(pulled_rows.get() + 1);
+            let values: Vec<Box<dyn Value>> = vec![Box::new(IntValue::new(index))];
+            Ok(Row { values })
+        }));
+
+        let titles = vec!["id".to_string()];
+        let rows =
+            collect_provided_rows(&mut env, rows, 3, &titles, &[], &[], &ScanHints::default())
+                .unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(pulled_rows.get(), 3);
+    }
+
+    #[test]
+    fn table_function_rows_in_from() {
+        let rows = query_rows("SELECT value FROM generate_series(1, 7, 3)").unwrap();
+        assert_eq!(first_column(&rows), vec!["1", "4", "7"]);
+
+        let rows = query_rows("SELECT value FROM generate_series(3, 1, -1)").unwrap();
+        assert_eq!(first_column(&rows), vec!["3", "2", "1"]);
+
+        let rows = query_rows("SELECT value FROM unnest(['a', 'b'])").unwrap();
+        assert_eq!(first_column(&rows), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn table_function_rows_in_join() {
+        let query =
+            "SELECT name, value FROM items INNER JOIN generate_series(4, 9) AS numbers ON id = value";
+        let rows = query_rows(query).unwrap();
+        assert_eq!(rows, vec![vec!["lib", "4"], vec!["main", "5"]]);
+    }
+
+    #[test]
+    fn table_function_error_is_query_error() {
+        let result = query_rows("SELECT value FROM generate_series(1, 5, 0)");
+        assert_eq!(result.unwrap_err(), "generate_series step can't be zero");
+    }
+
+    #[test]
+    fn ordered_set_aggregations_within_group() {
+        let rows = query_rows(
+            "SELECT PERCENTILE_DISC(0.5) WITHIN GROUP (ORDER BY id) AS p, PERCENTILE_CONT(0.1) WITHIN GROUP (ORDER BY id DESC) AS c FROM items",
+        )
+        .unwrap();
+        assert_eq!(rows, [["3", "4.6"]]);
+
+        let rows =
+            query_rows("SELECT MODE() WITHIN GROUP (ORDER BY parent_id) AS m FROM items").unwrap();
+        assert_eq!(rows, [["root"]]);
+    }
+
+    #[test]
+    fn statistical_aggregations_per_group() {
+        let rows = query_rows(
+            "SELECT parent_id, VAR_POP(id) AS v, MEDIAN(id) AS m FROM items WHERE id > 1 GROUP BY parent_id",
+        )
+        .unwrap();
+        assert_eq!(rows, [["root", "0.25", "2.5"], ["src", "0.25", "4.5"]]);
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use gitql_ast::operator::ComparisonOperator;
+use gitql_ast::query::SelectQuery;
+use gitql_ast::statement::AggregateValue;
+use gitql_ast::statement::Distinct;
+use gitql_ast::statement::IntoStatement;
+use gitql_ast::statement::JoinKind;
+use gitql_ast::statement::SortingOrder;
+use gitql_ast::statement::Statement;
+use gitql_ast::statement::WindowValue;
+use gitql_core::environment::Environment;
+use gitql_core::object::GitQLObject;
+use gitql_core::object::Group;
+use gitql_core::object::Row;
+use gitql_core::values::integer::IntValue;
+use gitql_core::values::null::NullValue;
+use gitql_core::values::text::TextValue;
+use gitql_core::values::Value;
+
+use crate::engine_planner::plan_select_query;
+use crate::engine_planner::FilterKind;
+use crate::engine_planner::LogicalPlan;
+use crate::engine_planner::ScanNode;
+
+/// Identifier of the node in the explained query
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum ExplainNodeId {
+    CommonTable(String),
+    Subquery(String),
+    /// Index of the node in the pre order traversal of the logical plan
+    Plan(usize),
+    Into,
+}
+
+/// Node of the explained query and the description of what it will do
+pub struct ExplainNode {
+    pub id: ExplainNodeId,
+    pub name: String,
+    pub details: String,
+}
+
+/// Number of rows and elapsed time of one executed node
+pub struct NodeProfile {
+    pub rows_in: Option<usize>,
+    pub rows_out: usize,
+    pub elapsed: Duration,
+}
+
+/// The explained nodes of the query and the profile of the executed nodes
+#[derive(Default)]
+pub struct QueryProfile {
+    pub nodes: Vec<ExplainNode>,
+    pub nodes_profiles: HashMap<ExplainNodeId, NodeProfile>,
+}
+
+/// Record the profile of the executed node if the query is profiled
+pub(crate) fn record_node_profile(
+    profile: &mut Option<&mut QueryProfile>,
+    id: ExplainNodeId,
+    rows_in: Option<usize>,
+    rows_out: usize,
+    start: Instant,
+) {
+    if let Some(profile) = profile.as_deref_mut() {
+        let node_profile = NodeProfile {
+            rows_in,
+            rows_out,
+            elapsed: start.elapsed(),
+        };
+        profile.nodes_profiles.insert(id, node_profile);
+    }
+}
+
+/// Return the number of rows in all the groups of the object
+pub(crate) fn object_rows_count(gitql_object: &GitQLObject) -> usize {
+    gitql_object.groups.iter().map(|group| group.len()).sum()
+}
+
+/// Build the nodes of the select query without executing it
+pub(crate) fn explain_select_query(env: &mut Environment, query: SelectQuery) -> Vec<ExplainNode> {
+    let mut nodes = explain_query_prelude(&query);
+
+    // Common tables are not evaluated, so register them as empty tables to plan them as common tables
+    for common_table in query.common_tables.iter() {
+        env.common_tables
+            .insert(common_table.name.to_string(), GitQLObject::default());
+    }
+
+    let mut statements = query.statements;
+    let into_statement = statements.remove("into");
+    if let Some(plan) = plan_select_query(
+        env,
+        statements,
+        &query.alias_table,
+        &query.hidden_selections,
+    ) {
+        nodes.extend(explain_logical_plan(&plan, &query.hidden_selections));
+    }
+
+    if let Some(Statement::Into(into_statement)) = into_statement {
+        nodes.push(explain_into_statement(&into_statement));
+    }
+
+    nodes
+}
+
+/// Build the nodes of the common tables and subqueries that are evaluated before the query plan
+pub(crate) fn explain_query_prelude(query: &SelectQuery) -> Vec<ExplainNode> {
+    let mut nodes: Vec<ExplainNode> = vec![];
+    for common_table in query.common_tables.iter() {
+        let mut details = format!("columns: {}", common_table.columns_names.join(", "));
+        if common_table.recursive_query.is_some() {
+            let union = if common_table.is_union_all {
+                "UNION ALL"
+            } else {
+                "UNION"
+            };
+            details.push_str(&format!("; recursive with {union}"));
+        }
+        nodes.push(ExplainNode {
+            id: ExplainNodeId::CommonTable(common_table.name.to_string()),
+            name: format!("With {}", common_table.name),
+            details,
+        });
+    }
+
+    let mut subqueries_names: Vec<&String> = query.subqueries.keys().collect();
+    subqueries_names.sort();
+    for name in subqueries_names {
+        nodes.push(ExplainNode {
+            id: ExplainNodeId::Subquery(name.to_string()),
+            name: format!("Subquery {name}"),
+            details: "evaluated once before the query".to_string(),
+        });
+    }
+
+    nodes
+}
+
+/// Build the nodes of the logical plan in pre order, each node is indented by it's depth
+pub(crate) fn explain_logical_plan(
+    plan: &LogicalPlan,
+    hidden_selections: &HashMap<String, Vec<String>>,
+) -> Vec<ExplainNode> {
+    let mut nodes: Vec<ExplainNode> = vec![];
+    explain_logical_plan_node(plan, hidden_selections, 0, &mut nodes);
+    nodes
+}
+
+pub(crate) fn explain_into_statement(into_statement: &IntoStatement) -> ExplainNode {
+    ExplainNode {
+        id: ExplainNodeId::Into,
+        name: "Into".to_string(),
+        details: format!("write rows into {}", into_statement.file_path),
+    }
+}
+
+/// Build the `EXPLAIN` result object, with the profile of the executed nodes for `EXPLAIN ANALYZE`
+pub(crate) fn explain_nodes_object(
+    nodes: Vec<ExplainNode>,
+    nodes_profiles: Option<HashMap<ExplainNodeId, NodeProfile>>,
+) -> GitQLObject {
+    let mut titles = vec!["node".to_string(), "details".to_string()];
+    if nodes_profiles.is_some() {
+        titles.push("rows_in".to_string());
+        titles.push("rows_out".to_string());
+        titles.push("time".to_string());
+    }
+
+    let mut rows: Vec<Row> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(titles.len());
+        values.push(Box::new(TextValue::new(node.name)));
+        values.push(Box::new(TextValue::new(node.details)));
+
+        if let Some(nodes_profiles) = &nodes_profiles {
+            // Nodes that are not executed because the query finished early have null profile
+            match nodes_profiles.get(&node.id) {
+                Some(node_profile) => {
+                    values.push(optional_count_value(node_profile.rows_in));
+                    values.push(optional_count_value(Some(node_profile.rows_out)));
+                    values.push(Box::new(TextValue::new(format!(
+                        "{:?}",
+                        node_profile.elapsed
+                    ))));
+                }
+                None => {
+                    values.push(Box::new(NullValue));
+                    values.push(Box::new(NullValue));
+                    values.push(Box::new(NullValue));
+                }
+            }
+        }
+
+        rows.push(Row { values });
+    }
+
+    GitQLObject {
+        titles,
+        groups: vec![Group { rows }],
+    }
+}
+
+fn optional_count_value(count: Option<usize>) -> Box<dyn Value> {
+    match count {
+        Some(count) => Box::new(IntValue::new(count as i64)),
+        None => Box::new(NullValue),
+    }
+}
+
+fn explain_logical_plan_node(
+    plan: &LogicalPlan,
+    hidden_selections: &HashMap<String, Vec<String>>,
+    depth: usize,
+    nodes: &mut Vec<ExplainNode>,
+) {
+    nodes.push(ExplainNode {
+        id: ExplainNodeId::Plan(nodes.len()),
+        name: format!("{}{}", "  ".repeat(depth), plan.name()),
+        details: explain_logical_plan_details(plan, hidden_selections),
+    });
+
+    for input in plan.inputs() {
+        explain_logical_plan_node(input, hidden_selections, depth + 1, nodes);
+    }
+}
+
+fn explain_logical_plan_details(
+    plan: &LogicalPlan,
+    hidden_selections: &HashMap<String, Vec<String>>,
+) -> String {
+    match plan {
+        LogicalPlan::Scan(node) => explain_scan_node(node, hidden_selections),
+        LogicalPlan::Join(node) => {
+            let kind = match node.join.kind {
+                JoinKind::Cross => "CROSS JOIN",
+                JoinKind::Inner => "INNER JOIN",
+                JoinKind::Left => "LEFT JOIN",
+                JoinKind::Right => "RIGHT JOIN",
+                JoinKind::Default => "JOIN",
+            };
+
+            if node.join.predicate.is_some() {
+                format!("{kind} with predicate")
+            } else {
+                kind.to_string()
+            }
+        }
+        LogicalPlan::Project(node) => format!("columns: {}", node.selected_expr_titles.join(", ")),
+        LogicalPlan::Filter(node) => match node.kind {
+            FilterKind::Where => "WHERE condition".to_string(),
+            FilterKind::Having => "HAVING condition".to_string(),
+            FilterKind::Qualify => "QUALIFY condition".to_string(),
+        },
+        LogicalPlan::Aggregate(node) => {
+            let mut details: Vec<String> = vec![];
+            if let Some(group_by) = &node.group_by {
+                let mut group_details = format!("group by {} values", group_by.values.len());
+                if group_by.has_grouping_sets() {
+                    let sets_count = group_by.grouping_sets.len();
+                    group_details.push_str(&format!(" in {sets_count} grouping sets"));
+                }
+                details.push(group_details);
+            }
+
+            if let Some(aggregations) = &node.aggregations {
+                let mut functions: Vec<(&String, String)> = aggregations
+                    .aggregations
+                    .iter()
+                    .filter_map(|(column, value)| match value {
+                        AggregateValue::Function(function) => {
+                            let mut literal = if function.distinct {
+                                format!("{}(DISTINCT)", function.function_name)
+                            } else {
+                                format!("{}()", function.function_name)
+                            };
+                            if function.filter.is_some() {
+                                literal.push_str(" FILTER");
+                            }
+                            Some((column, literal))
+                        }
+                        AggregateValue::Expression(_) | AggregateValue::Grouping(_) => None,
+                    })
+                    .collect();
+                functions.sort();
+
+                if !functions.is_empty() {
+                    let names: Vec<String> = functions.into_iter().map(|(_, name)| name).collect();
+                    details.push(format!("functions: {}", names.join(", ")));
+                }
+            }
+
+            details.join("; ")
+        }
+        LogicalPlan::Window(node) => {
+            let mut functions: Vec<(&String, String)> = node
+                .window_functions
+                .window_values
+                .iter()
+                .filter_map(|(column, value)| match value {
+                    WindowValue::Function(function) => {
+                        let mut literal = format!("{}() OVER", function.function_name);
+                        let definition = &function.window_definition;
+                        if let Some(name) = &definition.name {
+                            literal.push_str(&format!(" {name}"));
+                        }
+                        if definition.partitioning_clause.is_some() {
+                            literal.push_str(" PARTITION BY");
+                        }
+                        if definition.ordering_clause.is_some() {
+                            literal.push_str(" ORDER BY");
+                        }
+                        Some((column, literal))
+                    }
+                    WindowValue::Expression(_) => None,
+                })
+                .collect();
+            functions.sort();
+            let names: Vec<String> = functions.into_iter().map(|(_, name)| name).collect();
+            format!("functions: {}", names.join(", "))
+        }
+        LogicalPlan::Sort(node) => {
+            let orders: Vec<&str> = node
+                .order_by
+                .sorting_orders
+                .iter()
+                .map(|order| match order {
+                    SortingOrder::Ascending => "ASC",
+                    SortingOrder::Descending => "DESC",
+                })
+                .collect();
+
+            let mut details = format!("sort by {} values: {}", orders.len(), orders.join(", "));
+            if let Some(fetch) = node.fetch {
+                details.push_str(&format!("; keep top {fetch} rows"));
+            }
+            details
+        }
+        LogicalPlan::Limit(node) => {
+            let mut details: Vec<String> = vec![];
+            if node.offset.is_some() {
+                details.push("skip offset rows".to_string());
+            }
+            if let Some(count) = node.count {
+                details.push(format!("take {count} rows"));
+            }
+            details.join("; ")
+        }
+        LogicalPlan::Distinct(node) => match &node.distinct {
+            Distinct::None => String::new(),
+            Distinct::DistinctAll => "DISTINCT".to_string(),
+            Distinct::DistinctOn(fields) => format!("DISTINCT ON ({})", fields.join(", ")),
+        },
+    }
+}
+
+fn explain_scan_node(node: &ScanNode, hidden_selections: &HashMap<String, Vec<String>>) -> String {
+    let mut details: Vec<String> = vec![];
+    let table_name = &node.table_selection.table_name;
+    if node.table_selection.derived_query.is_some() {
+        details.push(format!("derived table: {table_name}"));
+    } else if let Some(function_call) = &node.table_selection.table_function {
+        details.push(format!(
+            "function: {}; table: {table_name}",
+            function_call.function_name
+        ));
+    } else if let Some(file_source) = &node.table_selection.file_source {
+        details.push(format!("file: {}", file_source.path));
+    } else if !table_name.is_empty() {
+        details.push(format!("table: {table_name}"));
+    }
+
+    if !node.table_selection.columns_names.is_empty() {
+        details.push(format!(
+            "columns: {}",
+            node.table_selection.columns_names.join(", ")
+        ));
+    }
+
+    if let Some(hidden_columns) = hidden_selections.get(table_name) {
+        if !hidden_columns.is_empty() {
+            details.push(format!("hidden: {}", hidden_columns.join(", ")));
+        }
+    }
+
+    let provider_hints = &node.hints.provider_hints;
+    if !provider_hints.conditions.is_empty() {
+        let conditions: Vec<String> = provider_hints
+            .conditions
+            .iter()
+            .map(|condition| {
+                format!(
+                    "{} {} {}",
+                    condition.column,
+                    comparison_operator_literal(&condition.operator),
+                    condition.value.literal()
+                )
+            })
+            .collect();
+        details.push(format!("provider conditions: {}", conditions.join(" AND ")));
+    }
+
+    if let Some(limit) = node.hints.limit {
+        details.push(format!("stop scan after {limit} rows"));
+    }
+
+    if node.filter.is_some() {
+        details.push("filter pushed down from WHERE".to_string());
+    }
+
+    details.join("; ")
+}
+
+fn comparison_operator_literal(operator: &ComparisonOperator) -> &'static str {
+    match operator {
+        ComparisonOperator::Greater => ">",
+        ComparisonOperator::GreaterEqual => ">=",
+        ComparisonOperator::Less => "<",
+        ComparisonOperator::LessEqual => "<=",
+        ComparisonOperator::Equal => "=",
+        ComparisonOperator::NotEqual => "!=",
+        ComparisonOperator::NullSafeEqual => "<=>",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::query_rows;
+
+    #[test]
+    fn explain_aggregation_distinct_and_filter() {
+        let rows = query_rows(
+            "EXPLAIN SELECT COUNT(DISTINCT parent_id) FILTER (WHERE id > 1) AS c FROM items",
+        )
+        .unwrap();
+        assert!(rows
+            .iter()
+            .any(|row| row[0] == "Aggregate" && row[1] == "functions: count(DISTINCT) FILTER"));
+    }
+}
@@ -4,69 +4,65 @@ use std::hash::DefaultHasher;
 use std::hash::Hash;
 use std::hash::Hasher;
 
+use gitql_ast::expression::Expr;
+use gitql_ast::expression::SymbolExpr;
 use gitql_ast::statement::GroupByStatement;
-use gitql_core::combinations_generator::generate_list_of_all_combinations;
 use gitql_core::environment::Environment;
 use gitql_core::object::GitQLObject;
 use gitql_core::object::Group;
+use gitql_core::values::null::NullValue;
 
 use crate::engine_evaluator::evaluate_expression;
+use crate::engine_executor::resolve_actual_column_name;
 
+/// Group the rows of the main group by each grouping set of the statement, and return the
+/// index of the grouping set of each group, the groups of each set are after the groups of the previous set
 pub(crate) fn execute_group_by_statement(
     env: &mut Environment,
     statement: &GroupByStatement,
     gitql_object: &mut GitQLObject,
-) -> Result<(), String> {
+) -> Result<Vec<usize>, String> {
     if gitql_object.is_empty() {
-        return Ok(());
+        return Ok(vec![]);
     }
 
     let main_group = gitql_object.groups.remove(0);
     if main_group.is_empty() {
-        return Ok(());
+        return Ok(vec![]);
     }
 
-    // Mapping each unique value to it group index
-    let mut groups_map: HashMap<u64, usize> = HashMap::new();
-
-    // Track current group index
-    let mut next_group_index = 0;
-    let values_count = statement.values.len();
-
-    let is_roll_up_enabled = statement.has_with_roll_up;
-    let indexes_combinations = if is_roll_up_enabled {
-        generate_list_of_all_combinations(values_count)
-    } else {
-        vec![(0..values_count).collect()]
-    };
-
-    // For each row should check the group by values combinations to build multi groups
+    // Evaluate the group by values once for each row as literals to be hashed
+    let mut rows_values: Vec<Vec<String>> = Vec::with_capacity(main_group.len());
     for row in main_group.rows.iter() {
-        // Create all combination of values for each row
-        for indexes in indexes_combinations.iter() {
-            let mut row_values: Vec<String> = Vec::with_capacity(indexes.len());
-            for index in indexes {
-                let value = evaluate_expression(
-                    env,
-                    &statement.values[*index],
-                    &gitql_object.titles,
-                    &row.values,
-                )?;
-                row_values.push(value.literal());
-            }
+        let mut row_values: Vec<String> = Vec::with_capacity(statement.values.len());
+        for value in statement.values.iter() {
+            let value = evaluate_expression(env, value, &gitql_object.titles, &row.values)?;
+            row_values.push(value.literal());
+        }
+        rows_values.push(row_values);
+    }
+
+    // Mapping each unique grouping set and values to it group index
+    let mut groups_map: HashMap<u64, usize> = HashMap::new();
+    let mut groups_sets: Vec<usize> = vec![];
 
-            // Compute the hash for row of values
+    for (set_index, grouping_set) in statement.grouping_sets.iter().enumerate() {
+        for (row, row_values) in main_group.rows.iter().zip(rows_values.iter()) {
+            // Compute the hash for the grouping set index and the row values of this set
             let mut hasher = DefaultHasher::new();
-            row_values.hash(&mut hasher);
+            set_index.hash(&mut hasher);
+            for index in grouping_set {
+                row_values[*index].hash(&mut hasher);
+            }
             let values_hash = hasher.finish();
 
             // Push a new group for this unique value and update the next index
             if let Vacant(e) = groups_map.entry(values_hash) {
-                e.insert(next_group_index);
-                next_group_index += 1;
+                e.insert(gitql_object.groups.len());
                 gitql_object.groups.push(Group {
                     rows: vec![row.clone()],
                 });
+                groups_sets.push(set_index);
                 continue;
             }
 
@@ -77,12 +73,156 @@ pub(crate) fn execute_group_by_statement(
         }
     }
 
-    // If the group by elements is one and ROLLUP is enabled
-    // For example: SELECT ... FROM <TABLE> GROUP BY X WITH ROLLUP
-    // Should append the the main group at the end
-    if is_roll_up_enabled && indexes_combinations.len() == 1 && indexes_combinations[0].len() == 1 {
-        gitql_object.groups.push(main_group);
+    Ok(groups_sets)
+}
+
+/// Return the value of `GROUPING(a, b)` for a group, it's a bit mask with bit for each argument
+/// from the most significant bit that is set if the argument is not grouped in the group grouping set
+pub(crate) fn evaluate_grouping_value(
+    statement: &GroupByStatement,
+    set_index: usize,
+    arguments: &[Box<dyn Expr>],
+) -> i64 {
+    let mut mask = 0;
+    for argument in arguments {
+        mask <<= 1;
+        if !is_grouped_in_set(statement, set_index, argument.as_ref()) {
+            mask |= 1;
+        }
+    }
+    mask
+}
+
+/// Set the columns that are not grouped in the grouping set of each group to NULL, so the super aggregate
+/// rows of `ROLLUP`, `CUBE` and `GROUPING SETS` has NULL values for the columns that are aggregated over
+pub(crate) fn apply_grouping_sets_nulls(
+    statement: &GroupByStatement,
+    groups_sets: &[usize],
+    gitql_object: &mut GitQLObject,
+    alias_table: &HashMap<String, String>,
+) {
+    if !statement.has_grouping_sets() {
+        return;
     }
 
-    Ok(())
+    for (group, set_index) in gitql_object.groups.iter_mut().zip(groups_sets.iter()) {
+        let grouping_set = &statement.grouping_sets[*set_index];
+        for (value_index, value) in statement.values.iter().enumerate() {
+            if grouping_set.contains(&value_index) {
+                continue;
+            }
+
+            let Some(symbol) = value.as_any().downcast_ref::<SymbolExpr>() else {
+                continue;
+            };
+
+            let column_name = resolve_actual_column_name(alias_table, &symbol.value);
+            let Some(column_index) = gitql_object
+                .titles
+                .iter()
+                .position(|title| title.eq(&symbol.value) || title.eq(&column_name))
+            else {
+                continue;
+            };
+
+            for row in group.rows.iter_mut() {
+                row.values[column_index] = Box::new(NullValue);
+            }
+        }
+    }
+}
+
+/// Return true if the column argument of `GROUPING` is grouped in the grouping set
+fn is_grouped_in_set(statement: &GroupByStatement, set_index: usize, argument: &dyn Expr) -> bool {
+    let Some(argument) = argument.as_any().downcast_ref::<SymbolExpr>() else {
+        return false;
+    };
+
+    statement.grouping_sets[set_index].iter().any(|index| {
+        statement.values[*index]
+            .as_any()
+            .downcast_ref::<SymbolExpr>()
+            .is_some_and(|value| value.value == argument.value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::query_rows;
+
+    #[test]
+    fn grouping_sets_groups_each_set() {
+        let rows = query_rows(
+            "SELECT parent_id, COUNT() AS c FROM items GROUP BY GROUPING SETS ((parent_id), ())",
+        )
+        .unwrap();
+        assert_eq!(
+            rows,
+            [["Null", "1"], ["root", "2"], ["src", "2"], ["Null", "5"]]
+        );
+    }
+
+    #[test]
+    fn cube_groups_all_subsets() {
+        let rows = query_rows(
+            "SELECT parent_id, name, COUNT() AS c FROM items WHERE id > 3 GROUP BY CUBE (parent_id, name)",
+        )
+        .unwrap();
+        assert_eq!(
+            rows,
+            [
+                ["src", "lib", "1"],
+                ["src", "main", "1"],
+                ["src", "Null", "2"],
+                ["Null", "lib", "1"],
+                ["Null", "main", "1"],
+                ["Null", "Null", "2"],
+            ]
+        );
+    }
+
+    #[test]
+    fn grouping_function_marks_rolled_up_columns() {
+        let rows = query_rows(
+            "SELECT parent_id, GROUPING(parent_id) AS g, COUNT() AS c FROM items GROUP BY ROLLUP (parent_id)",
+        )
+        .unwrap();
+        assert_eq!(
+            rows,
+            [
+                ["Null", "0", "1"],
+                ["root", "0", "2"],
+                ["src", "0", "2"],
+                ["Null", "1", "5"],
+            ]
+        );
+
+        let rows = query_rows(
+            "SELECT parent_id, name, GROUPING(parent_id, name) AS g FROM items WHERE id > 3 GROUP BY parent_id, ROLLUP (name)",
+        )
+        .unwrap();
+        assert_eq!(
+            rows,
+            [
+                ["src", "lib", "0"],
+                ["src", "main", "0"],
+                ["src", "Null", "1"]
+            ]
+        );
+    }
+
+    #[test]
+    fn grouping_function_in_having() {
+        let rows = query_rows(
+            "SELECT parent_id, COUNT() AS c FROM items GROUP BY ROLLUP (parent_id) HAVING GROUPING(parent_id) = 0",
+        )
+        .unwrap();
+        assert_eq!(rows, [["Null", "1"], ["root", "2"], ["src", "2"]]);
+    }
+
+    #[test]
+    fn grouping_function_without_group_by() {
+        let result = query_rows("SELECT id, GROUPING(id) AS g FROM items");
+        assert!(result.is_err_and(|error| error.contains("`GROUP BY` statement")));
+    }
 }
@@ -1,57 +1,77 @@
 use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hasher;
+use std::time::Instant;
 
+use gitql_ast::expression::ComparisonExpr;
+use gitql_ast::expression::Expr;
+use gitql_ast::expression::ExprKind;
+use gitql_ast::expression::SymbolExpr;
+use gitql_ast::operator::ComparisonOperator;
 use gitql_ast::statement::Join;
 use gitql_ast::statement::JoinKind;
 use gitql_ast::statement::JoinOperand;
-use gitql_ast::statement::TableSelection;
 use gitql_core::environment::Environment;
 use gitql_core::object::Row;
-use gitql_core::values::boolean::BoolValue;
 use gitql_core::values::null::NullValue;
 use gitql_core::values::Value;
 
 use crate::engine_evaluator::evaluate_expression;
+use crate::engine_explain::NodeProfile;
 
+/// Apply the joins in order and return the profile of each join
 #[inline(always)]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn apply_join_operation(
     env: &mut Environment,
     all_rows: &mut Vec<Row>,
-    joins: &Vec<Join>,
-    tables_selections: &Vec<TableSelection>,
+    joins: &[&Join],
+    tables_names: &[String],
     selected_rows_per_table: &mut HashMap<String, Vec<Row>>,
     hidden_selection_per_table: &HashMap<String, usize>,
+    columns_count_per_table: &HashMap<String, usize>,
     titles: &[String],
-) -> Result<(), String> {
+) -> Result<Vec<NodeProfile>, String> {
     // If no join, just merge them, can be optimized to append only the first value in the map
     if joins.is_empty() {
-        for table_selection in tables_selections {
-            let table_rows = selected_rows_per_table
-                .get_mut(&table_selection.table_name)
-                .unwrap();
+        for table_name in tables_names {
+            let table_rows = selected_rows_per_table.get_mut(table_name).unwrap();
             all_rows.append(table_rows);
         }
-        return Ok(());
+        return Ok(vec![]);
     }
 
+    let mut joins_profiles: Vec<NodeProfile> = Vec::with_capacity(joins.len());
+
     let mut current_tables_rows: Vec<Row> = vec![];
     let mut all_rows_hidden_count = 0;
+    let mut all_rows_columns_count = 0;
 
     // Apply join operator depend on the join type
     for join in joins {
+        let start = Instant::now();
         let mut current_join_rows: Vec<Row> = vec![];
 
         let left_rows: &Vec<Row>;
         let left_hidden_count: usize;
 
+        let left_row_len: usize;
+
         let right_rows: &Vec<Row>;
         let right_hidden_count: usize;
+        let right_row_len: usize;
 
+        // The rows length is taken from the columns count because one of the sides can be empty
         match &join.operand {
             JoinOperand::OuterAndInner(outer, inner) => {
                 left_hidden_count = *hidden_selection_per_table.get(outer).unwrap_or(&0);
                 right_hidden_count = *hidden_selection_per_table.get(inner).unwrap_or(&0);
                 all_rows_hidden_count += left_hidden_count + right_hidden_count;
 
+                left_row_len = *columns_count_per_table.get(outer).unwrap_or(&0);
+                right_row_len = *columns_count_per_table.get(inner).unwrap_or(&0);
+                all_rows_columns_count += left_row_len + right_row_len;
+
                 left_rows = selected_rows_per_table.get(outer).unwrap();
                 right_rows = selected_rows_per_table.get(inner).unwrap();
             }
@@ -61,85 +81,307 @@ pub(crate) fn apply_join_operation(
                 right_hidden_count = *hidden_selection_per_table.get(inner).unwrap_or(&0);
                 all_rows_hidden_count += right_hidden_count;
 
+                left_row_len = all_rows_columns_count;
+                right_row_len = *columns_count_per_table.get(inner).unwrap_or(&0);
+                all_rows_columns_count += right_row_len;
+
                 left_rows = &current_tables_rows;
                 right_rows = selected_rows_per_table.get(inner).unwrap();
             }
         }
 
-        // Don't apply CROSS JOIN if left or right rows are empty
+        // CROSS JOIN with empty left or right rows has no rows
+        let rows_in = left_rows.len() + right_rows.len();
         if join.kind == JoinKind::Cross && (left_rows.is_empty() || right_rows.is_empty()) {
+            current_tables_rows.clear();
+            joins_profiles.push(NodeProfile {
+                rows_in: Some(rows_in),
+                rows_out: current_tables_rows.len(),
+                elapsed: start.elapsed(),
+            });
             continue;
         }
 
-        // Perform nested loops straight forward join algorithm
-        for outer in left_rows {
-            for inner in right_rows {
-                let row_len = outer.values.len() + inner.values.len();
-                let mut joined_row: Vec<Box<dyn Value>> = Vec::with_capacity(row_len);
-                joined_row.append(&mut outer.values.clone());
-
-                let inner_rows = inner.values.clone();
-                let inner_hidden_values = &inner_rows[0..right_hidden_count];
-                joined_row.splice(
-                    left_hidden_count..left_hidden_count,
-                    inner_hidden_values.to_vec(),
-                );
+        let layout = JoinRowLayout {
+            left_row_len,
+            right_row_len,
+            left_hidden_count,
+            right_hidden_count,
+        };
+
+        let mut left_matched = vec![false; left_rows.len()];
+        let mut right_matched = vec![false; right_rows.len()];
 
-                let inner_other_values = &inner_rows[right_hidden_count..];
-                joined_row.extend_from_slice(inner_other_values);
+        // Use hash join if the predicate is equality between column from each side,
+        // otherwise perform nested loops straight forward join algorithm
+        let equi_join_columns = join
+            .predicate
+            .as_ref()
+            .and_then(|predicate| resolve_equi_join_columns(predicate, titles, &layout));
 
-                // If join has predicate, insert the joined row only if the predicate value is true
-                if let Some(predicate) = &join.predicate {
-                    let predicate_value = evaluate_expression(env, predicate, titles, &joined_row)?;
-                    if let Some(bool_value) = predicate_value.as_any().downcast_ref::<BoolValue>() {
-                        if bool_value.value {
-                            current_join_rows.push(Row { values: joined_row });
+        if let Some((left_column, right_column)) = equi_join_columns {
+            for (outer_index, inner_index) in
+                hash_join_matches(left_rows, right_rows, left_column, right_column)?
+            {
+                left_matched[outer_index] = true;
+                right_matched[inner_index] = true;
+                let joined_row = layout.join(
+                    &left_rows[outer_index].values,
+                    &right_rows[inner_index].values,
+                );
+                current_join_rows.push(Row { values: joined_row });
+            }
+        } else {
+            for (outer_index, outer) in left_rows.iter().enumerate() {
+                for (inner_index, inner) in right_rows.iter().enumerate() {
+                    let joined_row = layout.join(&outer.values, &inner.values);
+
+                    // If join has predicate, insert the joined row only if the predicate value is true
+                    if let Some(predicate) = &join.predicate {
+                        let predicate_value =
+                            evaluate_expression(env, predicate, titles, &joined_row)?;
+                        if !predicate_value.as_bool().unwrap_or(false) {
                             continue;
                         }
                     }
 
-                    // For LEFT and RIGHT Join only if the predicate is false we need to create new joined row
-                    // The new joined row will have nulls as LEFT table row values if the join type is `RIGHT OUTER` or
-                    // Nulls as RGIHT table row values if the join type is `LEFT OUTER`
-                    match join.kind {
-                        JoinKind::Left => {
-                            let mut left_joined_row: Vec<Box<dyn Value>> =
-                                Vec::with_capacity(row_len);
-                            // Push the LEFT values row
-                            left_joined_row.append(&mut outer.values.clone());
-                            // Push (N * NULL) values as RIGHT values row
-                            for _ in 0..inner.values.len() {
-                                left_joined_row.push(Box::new(NullValue));
-                            }
-                        }
-                        JoinKind::Right => {
-                            let mut right_joined_row: Vec<Box<dyn Value>> =
-                                Vec::with_capacity(row_len);
-                            // Push (N * NULL) values as LEFT values row
-                            for _ in 0..outer.values.len() {
-                                right_joined_row.push(Box::new(NullValue));
-                            }
-                            // Push the RIGHT values row
-                            right_joined_row.append(&mut inner.values.clone());
-                        }
-                        _ => {}
-                    }
-                    continue;
+                    left_matched[outer_index] = true;
+                    right_matched[inner_index] = true;
+                    current_join_rows.push(Row { values: joined_row });
                 }
+            }
+        }
 
-                // If the condition has no predicate, just insert it
-                current_join_rows.push(Row { values: joined_row });
+        // For LEFT and RIGHT Join, the rows that has no match are joined with nulls
+        // The new joined row will have nulls as LEFT table row values if the join type is `RIGHT OUTER` or
+        // Nulls as RIGHT table row values if the join type is `LEFT OUTER`
+        match join.kind {
+            JoinKind::Left => {
+                let nulls = null_values(layout.right_row_len);
+                for (outer, is_matched) in left_rows.iter().zip(left_matched) {
+                    if !is_matched {
+                        let joined_row = layout.join(&outer.values, &nulls);
+                        current_join_rows.push(Row { values: joined_row });
+                    }
+                }
             }
+            JoinKind::Right => {
+                let nulls = null_values(layout.left_row_len);
+                for (inner, is_matched) in right_rows.iter().zip(right_matched) {
+                    if !is_matched {
+                        let joined_row = layout.join(&nulls, &inner.values);
+                        current_join_rows.push(Row { values: joined_row });
+                    }
+                }
+            }
+            _ => {}
         }
 
         // Clear the previous join rows if exists
         current_tables_rows.clear();
         // Set the current tables rows as the result of the join
         current_tables_rows.append(&mut current_join_rows);
+
+        joins_profiles.push(NodeProfile {
+            rows_in: Some(rows_in),
+            rows_out: current_tables_rows.len(),
+            elapsed: start.elapsed(),
+        });
     }
 
     // Push the result to the all_rows ref
     all_rows.append(&mut current_tables_rows);
 
-    Ok(())
+    Ok(joins_profiles)
+}
+
+/// The position of the left and right rows values inside the joined row,
+/// hidden values of both sides come first then the other values of both sides
+struct JoinRowLayout {
+    left_row_len: usize,
+    right_row_len: usize,
+    left_hidden_count: usize,
+    right_hidden_count: usize,
+}
+
+impl JoinRowLayout {
+    fn join(&self, outer: &[Box<dyn Value>], inner: &[Box<dyn Value>]) -> Vec<Box<dyn Value>> {
+        let mut joined_row: Vec<Box<dyn Value>> = Vec::with_capacity(outer.len() + inner.len());
+        joined_row.extend_from_slice(&outer[..self.left_hidden_count]);
+        joined_row.extend_from_slice(&inner[..self.right_hidden_count]);
+        joined_row.extend_from_slice(&outer[self.left_hidden_count..]);
+        joined_row.extend_from_slice(&inner[self.right_hidden_count..]);
+        joined_row
+    }
+
+    /// Map index in the joined row to index in the left row or the right row
+    fn resolve_index(&self, index: usize) -> Option<JoinSide> {
+        let hidden_count = self.left_hidden_count + self.right_hidden_count;
+        if index < self.left_hidden_count {
+            return Some(JoinSide::Left(index));
+        }
+
+        if index < hidden_count {
+            return Some(JoinSide::Right(index - self.left_hidden_count));
+        }
+
+        if index < self.left_row_len + self.right_hidden_count {
+            return Some(JoinSide::Left(index - self.right_hidden_count));
+        }
+
+        if index < self.left_row_len + self.right_row_len {
+            return Some(JoinSide::Right(index - self.left_row_len));
+        }
+
+        None
+    }
+}
+
+enum JoinSide {
+    Left(usize),
+    Right(usize),
+}
+
+/// Return the index of the left and right columns if the predicate is `column = column`
+/// and each column is from different side of the join
+#[allow(clippy::borrowed_box)]
+fn resolve_equi_join_columns(
+    predicate: &Box<dyn Expr>,
+    titles: &[String],
+    layout: &JoinRowLayout,
+) -> Option<(usize, usize)> {
+    if predicate.kind() != ExprKind::Comparison {
+        return None;
+    }
+
+    let comparison = predicate.as_any().downcast_ref::<ComparisonExpr>()?;
+    if comparison.operator != ComparisonOperator::Equal {
+        return None;
+    }
+
+    let resolve_column_side = |expr: &dyn Expr| {
+        let symbol = expr.as_any().downcast_ref::<SymbolExpr>()?;
+        let index = titles.iter().position(|title| title.eq(&symbol.value))?;
+        layout.resolve_index(index)
+    };
+
+    match (
+        resolve_column_side(comparison.left.as_ref())?,
+        resolve_column_side(comparison.right.as_ref())?,
+    ) {
+        (JoinSide::Left(left), JoinSide::Right(right)) => Some((left, right)),
+        (JoinSide::Right(right), JoinSide::Left(left)) => Some((left, right)),
+        _ => None,
+    }
+}
+
+/// Build hash table on the smaller side and probe it with the other side,
+/// and return the indices of the matched left and right rows in the nested loops order
+fn hash_join_matches(
+    left_rows: &[Row],
+    right_rows: &[Row],
+    left_column: usize,
+    right_column: usize,
+) -> Result<Vec<(usize, usize)>, String> {
+    let is_build_right = right_rows.len() <= left_rows.len();
+    let (build_rows, build_column, probe_rows, probe_column) = if is_build_right {
+        (right_rows, right_column, left_rows, left_column)
+    } else {
+        (left_rows, left_column, right_rows, right_column)
+    };
+
+    // Null is not equal to any value so it's never inserted in the hash table
+    let mut hash_table: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (index, row) in build_rows.iter().enumerate() {
+        let key = &row.values[build_column];
+        if !key.is_null() {
+            hash_table.entry(value_hash(key)).or_default().push(index);
+        }
+    }
+
+    let mut matches: Vec<(usize, usize)> = vec![];
+    for (probe_index, row) in probe_rows.iter().enumerate() {
+        let key = &row.values[probe_column];
+        if key.is_null() {
+            continue;
+        }
+
+        if let Some(build_indices) = hash_table.get(&value_hash(key)) {
+            for build_index in build_indices {
+                // Different values can have the same hash, so compare them
+                let other_key = &build_rows[*build_index].values[build_column];
+                if !key.eq_op(other_key)?.as_bool().unwrap_or(false) {
+                    continue;
+                }
+
+                if is_build_right {
+                    matches.push((probe_index, *build_index));
+                } else {
+                    matches.push((*build_index, probe_index));
+                }
+            }
+        }
+    }
+
+    if !is_build_right {
+        matches.sort_unstable();
+    }
+
+    Ok(matches)
+}
+
+#[allow(clippy::borrowed_box)]
+fn value_hash(value: &Box<dyn Value>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn null_values(count: usize) -> Vec<Box<dyn Value>> {
+    let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(count);
+    for _ in 0..count {
+        values.push(Box::new(NullValue));
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::first_column;
+    use crate::test_utils::query_rows;
+
+    #[test]
+    fn outer_join_with_empty_side_keeps_unmatched_rows() {
+        let rows = query_rows(
+            "SELECT id, value FROM items LEFT JOIN (SELECT value FROM generate_series(1,3) WHERE value > 5) AS e ON value = id",
+        )
+        .unwrap();
+        assert_eq!(first_column(&rows), ["1", "2", "3", "4", "5"]);
+        assert!(rows.iter().all(|row| row[1] == "Null"));
+
+        let rows = query_rows(
+            "SELECT value, id FROM (SELECT value FROM generate_series(1,3) WHERE value > 5) AS e RIGHT JOIN items ON value = id",
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 5);
+        assert!(rows.iter().all(|row| row[0] == "Null"));
+    }
+
+    #[test]
+    fn hash_join_matches_equal_columns() {
+        let rows = query_rows(
+            "SELECT id, value FROM items JOIN (SELECT value FROM generate_series(2,3)) AS e ON id = value",
+        )
+        .unwrap();
+        assert_eq!(rows, [["2", "2"], ["3", "3"]]);
+    }
+
+    #[test]
+    fn cross_join_with_empty_side_has_no_rows() {
+        let rows = query_rows(
+            "SELECT id, value FROM items CROSS JOIN (SELECT value FROM generate_series(1,3) WHERE value > 5) AS e",
+        )
+        .unwrap();
+        assert!(rows.is_empty());
+    }
 }
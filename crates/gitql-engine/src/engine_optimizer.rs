@@ -0,0 +1,815 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use gitql_ast::expression::ArithmeticExpr;
+use gitql_ast::expression::ArrayExpr;
+use gitql_ast::expression::AssignmentExpr;
+use gitql_ast::expression::BenchmarkCallExpr;
+use gitql_ast::expression::BetweenExpr;
+use gitql_ast::expression::BitwiseExpr;
+use gitql_ast::expression::BooleanExpr;
+use gitql_ast::expression::CallExpr;
+use gitql_ast::expression::CaseExpr;
+use gitql_ast::expression::CastExpr;
+use gitql_ast::expression::ColumnExpr;
+use gitql_ast::expression::ComparisonExpr;
+use gitql_ast::expression::ContainedByExpr;
+use gitql_ast::expression::ContainsExpr;
+use gitql_ast::expression::Expr;
+use gitql_ast::expression::ExprKind;
+use gitql_ast::expression::GlobExpr;
+use gitql_ast::expression::GroupComparisonExpr;
+use gitql_ast::expression::InExpr;
+use gitql_ast::expression::IndexExpr;
+use gitql_ast::expression::IsNullExpr;
+use gitql_ast::expression::LikeExpr;
+use gitql_ast::expression::LogicalExpr;
+use gitql_ast::expression::MemberAccessExpr;
+use gitql_ast::expression::NullExpr;
+use gitql_ast::expression::Number;
+use gitql_ast::expression::NumberExpr;
+use gitql_ast::expression::RegexExpr;
+use gitql_ast::expression::RowExpr;
+use gitql_ast::expression::SliceExpr;
+use gitql_ast::expression::StringExpr;
+use gitql_ast::expression::SymbolExpr;
+use gitql_ast::expression::UnaryExpr;
+use gitql_ast::operator::BinaryLogicalOperator;
+use gitql_ast::statement::JoinKind;
+use gitql_core::environment::Environment;
+use gitql_core::values::boolean::BoolValue;
+use gitql_core::values::float::FloatValue;
+use gitql_core::values::integer::IntValue;
+use gitql_core::values::null::NullValue;
+use gitql_core::values::text::TextValue;
+use gitql_core::values::Value;
+
+use crate::engine_evaluator::evaluate_expression;
+use crate::engine_planner::FilterKind;
+use crate::engine_planner::FilterNode;
+use crate::engine_planner::LogicalPlan;
+use crate::engine_planner::ScanNode;
+
+/// Apply the rewrite rules on the logical plan
+pub(crate) fn optimize_logical_plan(
+    env: &mut Environment,
+    plan: LogicalPlan,
+    alias_table: &HashMap<String, String>,
+    hidden_selections: &HashMap<String, Vec<String>>,
+) -> LogicalPlan {
+    let plan = fold_plan_constants(env, plan);
+    let plan = push_down_filters(plan, alias_table, hidden_selections);
+    apply_top_n_sort(env, plan)
+}
+
+/// Replace the constant expressions by their values, and remove the filters that are always true
+fn fold_plan_constants(env: &mut Environment, plan: LogicalPlan) -> LogicalPlan {
+    let plan = plan.map_inputs(&mut |input| fold_plan_constants(env, input));
+    match plan {
+        LogicalPlan::Join(mut node) => {
+            node.join.predicate = node
+                .join
+                .predicate
+                .map(|predicate| fold_constants(env, predicate));
+            LogicalPlan::Join(node)
+        }
+        LogicalPlan::Project(mut node) => {
+            node.selected_expr = node
+                .selected_expr
+                .into_iter()
+                .map(|expr| fold_constants(env, expr))
+                .collect();
+            LogicalPlan::Project(node)
+        }
+        LogicalPlan::Filter(mut node) => {
+            // Conditions that are always true are removed from the filter conjunctions
+            let condition = fold_constants(env, node.condition);
+            let remaining_conjuncts = split_conjuncts(condition).into_iter().filter(|conjunct| {
+                conjunct
+                    .as_any()
+                    .downcast_ref::<BooleanExpr>()
+                    .is_none_or(|boolean| !boolean.is_true)
+            });
+
+            match remaining_conjuncts.reduce(and_expr) {
+                Some(condition) => {
+                    node.condition = condition;
+                    LogicalPlan::Filter(node)
+                }
+                None => *node.input,
+            }
+        }
+        LogicalPlan::Aggregate(mut node) => {
+            if let Some(group_by) = node.group_by.as_mut() {
+                group_by.values = std::mem::take(&mut group_by.values)
+                    .into_iter()
+                    .map(|value| fold_constants(env, value))
+                    .collect();
+            }
+            LogicalPlan::Aggregate(node)
+        }
+        LogicalPlan::Sort(mut node) => {
+            node.order_by.arguments = std::mem::take(&mut node.order_by.arguments)
+                .into_iter()
+                .map(|argument| fold_constants(env, argument))
+                .collect();
+            LogicalPlan::Sort(node)
+        }
+        LogicalPlan::Limit(mut node) => {
+            node.offset = node.offset.map(|offset| fold_constants(env, offset));
+            LogicalPlan::Limit(node)
+        }
+        plan => plan,
+    }
+}
+
+/// Move the conjuncts of the `WHERE` condition that use the columns of only one table
+/// below the joins, so the rows are filtered before joining them
+fn push_down_filters(
+    plan: LogicalPlan,
+    alias_table: &HashMap<String, String>,
+    hidden_selections: &HashMap<String, Vec<String>>,
+) -> LogicalPlan {
+    let plan =
+        plan.map_inputs(&mut |input| push_down_filters(input, alias_table, hidden_selections));
+
+    let LogicalPlan::Filter(mut filter) = plan else {
+        return plan;
+    };
+
+    if filter.kind != FilterKind::Where {
+        return LogicalPlan::Filter(filter);
+    }
+
+    let LogicalPlan::Project(project) = filter.input.as_mut() else {
+        return LogicalPlan::Filter(filter);
+    };
+
+    if !matches!(project.input.as_ref(), LogicalPlan::Join(_)) {
+        return LogicalPlan::Filter(filter);
+    }
+
+    let mut scans: Vec<&mut ScanNode> = vec![];
+    let mut joins_kinds: Vec<JoinKind> = vec![];
+    collect_join_inputs(&mut project.input, &mut scans, &mut joins_kinds);
+
+    // Conditions are not pushed down into the inputs of cross joins
+    if joins_kinds.contains(&JoinKind::Cross) {
+        return LogicalPlan::Filter(filter);
+    }
+
+    // Rows of the null supplying side of outer joins can't be filtered before the join,
+    // the join with tables at index i has the tables before it as left side and table i as right side
+    let mut is_null_supplying = vec![false; scans.len()];
+    for (index, kind) in joins_kinds.iter().enumerate() {
+        match kind {
+            JoinKind::Left => is_null_supplying[index + 1] = true,
+            JoinKind::Right => is_null_supplying[..=index].fill(true),
+            _ => {}
+        }
+    }
+
+    let tables_columns: Vec<HashSet<String>> = scans
+        .iter()
+        .map(|scan| scan_columns_titles(scan, alias_table, hidden_selections))
+        .collect();
+
+    // Titles of the selected expressions that are evaluated after the scan, not the table columns
+    let computed_titles: Vec<&String> = project
+        .selected_expr_titles
+        .iter()
+        .zip(project.selected_expr.iter())
+        .filter(|(title, expr)| {
+            expr.as_any()
+                .downcast_ref::<SymbolExpr>()
+                .is_none_or(|symbol| symbol.value != **title)
+        })
+        .map(|(title, _)| title)
+        .collect();
+
+    let mut remaining_conjuncts: Vec<Box<dyn Expr>> = vec![];
+    for conjunct in split_conjuncts(filter.condition) {
+        let mut symbols: Vec<&str> = vec![];
+        collect_symbols(conjunct.as_ref(), &mut symbols);
+
+        let table_index = if symbols.is_empty()
+            || symbols
+                .iter()
+                .any(|symbol| computed_titles.iter().any(|title| title == symbol))
+        {
+            None
+        } else {
+            resolve_single_table(&symbols, &tables_columns)
+        };
+
+        match table_index {
+            Some(index) if !is_null_supplying[index] => {
+                let scan = &mut scans[index];
+                scan.filter = Some(match scan.filter.take() {
+                    Some(filter) => and_expr(filter, conjunct),
+                    None => conjunct,
+                });
+            }
+            _ => remaining_conjuncts.push(conjunct),
+        }
+    }
+
+    match remaining_conjuncts.into_iter().reduce(and_expr) {
+        Some(condition) => LogicalPlan::Filter(FilterNode {
+            input: filter.input,
+            kind: FilterKind::Where,
+            condition,
+        }),
+        None => *filter.input,
+    }
+}
+
+/// Pass the number of needed rows to the sort node if it followed by limit,
+/// so it can keep only the top rows instead of sorting all of them
+fn apply_top_n_sort(env: &mut Environment, plan: LogicalPlan) -> LogicalPlan {
+    let plan = plan.map_inputs(&mut |input| apply_top_n_sort(env, input));
+
+    let LogicalPlan::Limit(mut limit) = plan else {
+        return plan;
+    };
+
+    let Some(count) = limit.count else {
+        return LogicalPlan::Limit(limit);
+    };
+
+    let offset = match &limit.offset {
+        Some(offset) if offset.is_const() => match evaluate_expression(env, offset, &[], &vec![]) {
+            Ok(value) => value.as_int().unwrap_or(0).max(0) as usize,
+            Err(_) => return LogicalPlan::Limit(limit),
+        },
+        Some(_) => return LogicalPlan::Limit(limit),
+        None => 0,
+    };
+
+    if let LogicalPlan::Sort(sort) = limit.input.as_mut() {
+        sort.fetch = Some(count + offset);
+    }
+
+    LogicalPlan::Limit(limit)
+}
+
+/// Collect the scans of the join tree from left to right and the kinds of the joins from bottom to top
+fn collect_join_inputs<'a>(
+    plan: &'a mut LogicalPlan,
+    scans: &mut Vec<&'a mut ScanNode>,
+    joins_kinds: &mut Vec<JoinKind>,
+) {
+    match plan {
+        LogicalPlan::Scan(scan) => scans.push(scan),
+        LogicalPlan::Join(join) => {
+            collect_join_inputs(&mut join.left, scans, joins_kinds);
+            collect_join_inputs(&mut join.right, scans, joins_kinds);
+            joins_kinds.push(join.join.kind.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Return the titles of the columns that the scan will provide
+fn scan_columns_titles(
+    scan: &ScanNode,
+    alias_table: &HashMap<String, String>,
+    hidden_selections: &HashMap<String, Vec<String>>,
+) -> HashSet<String> {
+    let table_name = &scan.table_selection.table_name;
+    scan.table_selection
+        .columns_names
+        .iter()
+        .chain(hidden_selections.get(table_name).into_iter().flatten())
+        .map(|column| alias_table.get(column).unwrap_or(column).to_string())
+        .collect()
+}
+
+/// Return the index of the table that provide all the symbols,
+/// or None if any symbol is provided by more than one table or no table
+fn resolve_single_table(symbols: &[&str], tables_columns: &[HashSet<String>]) -> Option<usize> {
+    let mut table_index: Option<usize> = None;
+    for symbol in symbols {
+        let mut tables = tables_columns
+            .iter()
+            .enumerate()
+            .filter(|(_, columns)| columns.contains(*symbol))
+            .map(|(index, _)| index);
+
+        let index = tables.next()?;
+        if tables.next().is_some() || table_index.is_some_and(|current| current != index) {
+            return None;
+        }
+        table_index = Some(index);
+    }
+    table_index
+}
+
+fn split_conjuncts(condition: Box<dyn Expr>) -> Vec<Box<dyn Expr>> {
+    if let Some(logical) = condition.as_any().downcast_ref::<LogicalExpr>() {
+        if logical.operator == BinaryLogicalOperator::And {
+            let mut conjuncts = split_conjuncts(logical.left.clone());
+            conjuncts.extend(split_conjuncts(logical.right.clone()));
+            return conjuncts;
+        }
+    }
+    vec![condition]
+}
+
+fn and_expr(left: Box<dyn Expr>, right: Box<dyn Expr>) -> Box<dyn Expr> {
+    Box::new(LogicalExpr {
+        left,
+        operator: BinaryLogicalOperator::And,
+        right,
+    })
+}
+
+fn collect_symbols<'a>(expr: &'a dyn Expr, symbols: &mut Vec<&'a str>) {
+    if let Some(symbol) = expr.as_any().downcast_ref::<SymbolExpr>() {
+        symbols.push(&symbol.value);
+        return;
+    }
+
+    for child in expr_children(expr) {
+        collect_symbols(child.as_ref(), symbols);
+    }
+}
+
+/// Evaluate the expression if it's constant and can be represented as literal,
+/// otherwise fold the constant expressions inside it
+fn fold_constants(env: &mut Environment, expr: Box<dyn Expr>) -> Box<dyn Expr> {
+    if expr.is_const() {
+        return expr;
+    }
+
+    if is_foldable_expr(expr.as_ref()) {
+        if let Ok(value) = evaluate_expression(env, &expr, &[], &vec![]) {
+            if let Some(literal) = value_literal_expr(&value) {
+                return literal;
+            }
+        }
+    }
+
+    map_expr_children(expr, &mut |child| fold_constants(env, child))
+}
+
+/// Return true if the expression value is the same for all rows and can be evaluated once,
+/// function calls are not folded because they can return different value on each call
+fn is_foldable_expr(expr: &dyn Expr) -> bool {
+    !matches!(
+        expr.kind(),
+        ExprKind::Symbol
+            | ExprKind::GlobalVariable
+            | ExprKind::Assignment
+            | ExprKind::Call
+            | ExprKind::BenchmarkCall
+            | ExprKind::Subquery
+    ) && expr_children(expr)
+        .iter()
+        .all(|child| is_foldable_expr(child.as_ref()))
+}
+
+#[allow(clippy::borrowed_box)]
+fn value_literal_expr(value: &Box<dyn Value>) -> Option<Box<dyn Expr>> {
+    let value = value.as_any();
+    if let Some(int) = value.downcast_ref::<IntValue>() {
+        return Some(Box::new(NumberExpr {
+            value: Number::Int(int.value),
+        }));
+    }
+
+    if let Some(float) = value.downcast_ref::<FloatValue>() {
+        return Some(Box::new(NumberExpr {
+            value: Number::Float(float.value),
+        }));
+    }
+
+    if let Some(text) = value.downcast_ref::<TextValue>() {
+        return Some(Box::new(StringExpr {
+            value: text.value.to_string(),
+        }));
+    }
+
+    if let Some(boolean) = value.downcast_ref::<BoolValue>() {
+        return Some(Box::new(BooleanExpr {
+            is_true: boolean.value,
+        }));
+    }
+
+    if value.downcast_ref::<NullValue>().is_some() {
+        return Some(Box::new(NullExpr));
+    }
+
+    None
+}
+
+/// Return the direct sub expressions of the expression
+#[allow(clippy::borrowed_box)]
+fn expr_children(expr: &dyn Expr) -> Vec<&Box<dyn Expr>> {
+    let any = expr.as_any();
+    match expr.kind() {
+        ExprKind::Assignment => {
+            let expr = any.downcast_ref::<AssignmentExpr>().unwrap();
+            vec![&expr.value]
+        }
+        ExprKind::Array => {
+            let expr = any.downcast_ref::<ArrayExpr>().unwrap();
+            expr.values.iter().collect()
+        }
+        ExprKind::PrefixUnary => {
+            let expr = any.downcast_ref::<UnaryExpr>().unwrap();
+            vec![&expr.right]
+        }
+        ExprKind::Index => {
+            let expr = any.downcast_ref::<IndexExpr>().unwrap();
+            vec![&expr.collection, &expr.index]
+        }
+        ExprKind::Slice => {
+            let expr = any.downcast_ref::<SliceExpr>().unwrap();
+            let mut children = vec![&expr.collection];
+            children.extend(expr.start.iter());
+            children.extend(expr.end.iter());
+            children
+        }
+        ExprKind::Arithmetic => {
+            let expr = any.downcast_ref::<ArithmeticExpr>().unwrap();
+            vec![&expr.left, &expr.right]
+        }
+        ExprKind::Comparison => {
+            let expr = any.downcast_ref::<ComparisonExpr>().unwrap();
+            vec![&expr.left, &expr.right]
+        }
+        ExprKind::GroupComparison => {
+            let expr = any.downcast_ref::<GroupComparisonExpr>().unwrap();
+            vec![&expr.left, &expr.right]
+        }
+        ExprKind::Contains => {
+            let expr = any.downcast_ref::<ContainsExpr>().unwrap();
+            vec![&expr.left, &expr.right]
+        }
+        ExprKind::ContainedBy => {
+            let expr = any.downcast_ref::<ContainedByExpr>().unwrap();
+            vec![&expr.left, &expr.right]
+        }
+        ExprKind::Like => {
+            let expr = any.downcast_ref::<LikeExpr>().unwrap();
+            vec![&expr.input, &expr.pattern]
+        }
+        ExprKind::Regex => {
+            let expr = any.downcast_ref::<RegexExpr>().unwrap();
+            vec![&expr.input, &expr.pattern]
+        }
+        ExprKind::Glob => {
+            let expr = any.downcast_ref::<GlobExpr>().unwrap();
+            vec![&expr.input, &expr.pattern]
+        }
+        ExprKind::Logical => {
+            let expr = any.downcast_ref::<LogicalExpr>().unwrap();
+            vec![&expr.left, &expr.right]
+        }
+        ExprKind::Bitwise => {
+            let expr = any.downcast_ref::<BitwiseExpr>().unwrap();
+            vec![&expr.left, &expr.right]
+        }
+        ExprKind::Call => {
+            let expr = any.downcast_ref::<CallExpr>().unwrap();
+            expr.arguments.iter().collect()
+        }
+        ExprKind::BenchmarkCall => {
+            let expr = any.downcast_ref::<BenchmarkCallExpr>().unwrap();
+            vec![&expr.expression, &expr.count]
+        }
+        ExprKind::Between => {
+            let expr = any.downcast_ref::<BetweenExpr>().unwrap();
+            vec![&expr.value, &expr.range_start, &expr.range_end]
+        }
+        ExprKind::Case => {
+            let expr = any.downcast_ref::<CaseExpr>().unwrap();
+            let mut children: Vec<&Box<dyn Expr>> = expr.conditions.iter().collect();
+            children.extend(expr.values.iter());
+            children.extend(expr.default_value.iter());
+            children
+        }
+        ExprKind::In => {
+            let expr = any.downcast_ref::<InExpr>().unwrap();
+            let mut children = vec![&expr.argument];
+            children.extend(expr.values.iter());
+            children
+        }
+        ExprKind::IsNull => {
+            let expr = any.downcast_ref::<IsNullExpr>().unwrap();
+            vec![&expr.argument]
+        }
+        ExprKind::Cast => {
+            let expr = any.downcast_ref::<CastExpr>().unwrap();
+            vec![&expr.value]
+        }
+        ExprKind::Column => {
+            let expr = any.downcast_ref::<ColumnExpr>().unwrap();
+            vec![&expr.expr]
+        }
+        ExprKind::Row => {
+            let expr = any.downcast_ref::<RowExpr>().unwrap();
+            expr.exprs.iter().collect()
+        }
+        ExprKind::MemberAccess => {
+            let expr = any.downcast_ref::<MemberAccessExpr>().unwrap();
+            vec![&expr.composite]
+        }
+        ExprKind::String
+        | ExprKind::Symbol
+        | ExprKind::GlobalVariable
+        | ExprKind::Number
+        | ExprKind::Boolean
+        | ExprKind::Interval
+        | ExprKind::Null
+        | ExprKind::Subquery => vec![],
+    }
+}
+
+/// Rebuild the expression with the direct sub expressions mapped by the function
+fn map_expr_children(
+    expr: Box<dyn Expr>,
+    function: &mut impl FnMut(Box<dyn Expr>) -> Box<dyn Expr>,
+) -> Box<dyn Expr> {
+    let any = expr.as_any();
+    match expr.kind() {
+        ExprKind::Assignment => {
+            let mut expr = any.downcast_ref::<AssignmentExpr>().unwrap().clone();
+            expr.value = function(expr.value);
+            Box::new(expr)
+        }
+        ExprKind::Array => {
+            let mut expr = any.downcast_ref::<ArrayExpr>().unwrap().clone();
+            expr.values = expr.values.into_iter().map(&mut *function).collect();
+            Box::new(expr)
+        }
+        ExprKind::PrefixUnary => {
+            let mut expr = any.downcast_ref::<UnaryExpr>().unwrap().clone();
+            expr.right = function(expr.right);
+            Box::new(expr)
+        }
+        ExprKind::Index => {
+            let mut expr = any.downcast_ref::<IndexExpr>().unwrap().clone();
+            expr.collection = function(expr.collection);
+            expr.index = function(expr.index);
+            Box::new(expr)
+        }
+        ExprKind::Slice => {
+            let mut expr = any.downcast_ref::<SliceExpr>().unwrap().clone();
+            expr.collection = function(expr.collection);
+            expr.start = expr.start.map(&mut *function);
+            expr.end = expr.end.map(&mut *function);
+            Box::new(expr)
+        }
+        ExprKind::Arithmetic => {
+            let mut expr = any.downcast_ref::<ArithmeticExpr>().unwrap().clone();
+            expr.left = function(expr.left);
+            expr.right = function(expr.right);
+            Box::new(expr)
+        }
+        ExprKind::Comparison => {
+            let mut expr = any.downcast_ref::<ComparisonExpr>().unwrap().clone();
+            expr.left = function(expr.left);
+            expr.right = function(expr.right);
+            Box::new(expr)
+        }
+        ExprKind::GroupComparison => {
+            let mut expr = any.downcast_ref::<GroupComparisonExpr>().unwrap().clone();
+            expr.left = function(expr.left);
+            expr.right = function(expr.right);
+            Box::new(expr)
+        }
+        ExprKind::Contains => {
+            let mut expr = any.downcast_ref::<ContainsExpr>().unwrap().clone();
+            expr.left = function(expr.left);
+            expr.right = function(expr.right);
+            Box::new(expr)
+        }
+        ExprKind::ContainedBy => {
+            let mut expr = any.downcast_ref::<ContainedByExpr>().unwrap().clone();
+            expr.left = function(expr.left);
+            expr.right = function(expr.right);
+            Box::new(expr)
+        }
+        ExprKind::Like => {
+            let mut expr = any.downcast_ref::<LikeExpr>().unwrap().clone();
+            expr.input = function(expr.input);
+            expr.pattern = function(expr.pattern);
+            Box::new(expr)
+        }
+        ExprKind::Regex => {
+            let mut expr = any.downcast_ref::<RegexExpr>().unwrap().clone();
+            expr.input = function(expr.input);
+            expr.pattern = function(expr.pattern);
+            Box::new(expr)
+        }
+        ExprKind::Glob => {
+            let mut expr = any.downcast_ref::<GlobExpr>().unwrap().clone();
+            expr.input = function(expr.input);
+            expr.pattern = function(expr.pattern);
+            Box::new(expr)
+        }
+        ExprKind::Logical => {
+            let mut expr = any.downcast_ref::<LogicalExpr>().unwrap().clone();
+            expr.left = function(expr.left);
+            expr.right = function(expr.right);
+            Box::new(expr)
+        }
+        ExprKind::Bitwise => {
+            let mut expr = any.downcast_ref::<BitwiseExpr>().unwrap().clone();
+            expr.left = function(expr.left);
+            expr.right = function(expr.right);
+            Box::new(expr)
+        }
+        ExprKind::Call => {
+            let mut expr = any.downcast_ref::<CallExpr>().unwrap().clone();
+            expr.arguments = expr.arguments.into_iter().map(&mut *function).collect();
+            Box::new(expr)
+        }
+        ExprKind::BenchmarkCall => {
+            let mut expr = any.downcast_ref::<BenchmarkCallExpr>().unwrap().clone();
+            expr.expression = function(expr.expression);
+            expr.count = function(expr.count);
+            Box::new(expr)
+        }
+        ExprKind::Between => {
+            let mut expr = any.downcast_ref::<BetweenExpr>().unwrap().clone();
+            expr.value = function(expr.value);
+            expr.range_start = function(expr.range_start);
+            expr.range_end = function(expr.range_end);
+            Box::new(expr)
+        }
+        ExprKind::Case => {
+            let mut expr = any.downcast_ref::<CaseExpr>().unwrap().clone();
+            expr.conditions = expr.conditions.into_iter().map(&mut *function).collect();
+            expr.values = expr.values.into_iter().map(&mut *function).collect();
+            expr.default_value = expr.default_value.map(&mut *function);
+            Box::new(expr)
+        }
+        ExprKind::In => {
+            let mut expr = any.downcast_ref::<InExpr>().unwrap().clone();
+            expr.argument = function(expr.argument);
+            expr.values = expr.values.into_iter().map(&mut *function).collect();
+            Box::new(expr)
+        }
+        ExprKind::IsNull => {
+            let mut expr = any.downcast_ref::<IsNullExpr>().unwrap().clone();
+            expr.argument = function(expr.argument);
+            Box::new(expr)
+        }
+        ExprKind::Cast => {
+            let mut expr = any.downcast_ref::<CastExpr>().unwrap().clone();
+            expr.value = function(expr.value);
+            Box::new(expr)
+        }
+        ExprKind::Column => {
+            let mut expr = any.downcast_ref::<ColumnExpr>().unwrap().clone();
+            expr.expr = function(expr.expr);
+            Box::new(expr)
+        }
+        ExprKind::Row => {
+            let mut expr = any.downcast_ref::<RowExpr>().unwrap().clone();
+            expr.exprs = expr.exprs.into_iter().map(&mut *function).collect();
+            Box::new(expr)
+        }
+        ExprKind::MemberAccess => {
+            let mut expr = any.downcast_ref::<MemberAccessExpr>().unwrap().clone();
+            expr.composite = function(expr.composite);
+            Box::new(expr)
+        }
+        ExprKind::String
+        | ExprKind::Symbol
+        | ExprKind::GlobalVariable
+        | ExprKind::Number
+        | ExprKind::Boolean
+        | ExprKind::Interval
+        | ExprKind::Null
+        | ExprKind::Subquery => expr,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gitql_ast::statement::Statement;
+
+    use super::*;
+    use crate::engine_planner::plan_select_query;
+    use crate::test_utils::parse_select_query;
+    use crate::test_utils::query_rows;
+    use crate::test_utils::test_environment;
+
+    fn optimized_plan(query: &str) -> LogicalPlan {
+        let mut env = test_environment();
+        let select_query = parse_select_query(&mut env, query);
+        plan_select_query(
+            &mut env,
+            select_query.statements,
+            &select_query.alias_table,
+            &select_query.hidden_selections,
+        )
+        .unwrap()
+    }
+
+    /// Return for each scan from left to right if it has pushed down filter
+    fn scans_filters(plan: &LogicalPlan, filters: &mut Vec<bool>) {
+        if let LogicalPlan::Scan(scan) = plan {
+            filters.push(scan.filter.is_some());
+        }
+
+        for input in plan.inputs() {
+            scans_filters(input, filters);
+        }
+    }
+
+    fn has_where_filter(plan: &LogicalPlan) -> bool {
+        matches!(plan, LogicalPlan::Filter(filter) if filter.kind == FilterKind::Where)
+            || plan.inputs().into_iter().any(has_where_filter)
+    }
+
+    fn joined_scans_filters(join: &str) -> (Vec<bool>, bool) {
+        let predicate = if join == "CROSS JOIN" {
+            ""
+        } else {
+            "ON id = value"
+        };
+        let plan = optimized_plan(&format!(
+            "SELECT id, value FROM items {join} (SELECT value FROM generate_series(1,3)) AS e {predicate} WHERE id > 1 AND value < 3"
+        ));
+        let mut filters = vec![];
+        scans_filters(&plan, &mut filters);
+        (filters, has_where_filter(&plan))
+    }
+
+    #[test]
+    fn push_down_filters_into_inner_join_inputs() {
+        let (filters, has_where_filter) = joined_scans_filters("JOIN");
+        assert_eq!(filters, [true, true]);
+        assert!(!has_where_filter);
+    }
+
+    #[test]
+    fn push_down_filters_skip_null_supplying_side() {
+        let (filters, has_where_filter) = joined_scans_filters("LEFT JOIN");
+        assert_eq!(filters, [true, false]);
+        assert!(has_where_filter);
+
+        let (filters, has_where_filter) = joined_scans_filters("RIGHT JOIN");
+        assert_eq!(filters, [false, true]);
+        assert!(has_where_filter);
+    }
+
+    #[test]
+    fn push_down_filters_skip_cross_join() {
+        let (filters, has_where_filter) = joined_scans_filters("CROSS JOIN");
+        assert_eq!(filters, [false, false]);
+        assert!(has_where_filter);
+    }
+
+    #[test]
+    fn push_down_filters_keep_left_join_result() {
+        let rows = query_rows(
+            "SELECT id, value FROM items LEFT JOIN (SELECT value FROM generate_series(1,3)) AS e ON id = value WHERE id > 3 AND (value IS NULL)",
+        )
+        .unwrap();
+        assert_eq!(rows, [["4", "Null"], ["5", "Null"]]);
+    }
+
+    #[test]
+    fn fold_constants_keep_calls_and_subqueries() {
+        let mut env = test_environment();
+        let select_query =
+            parse_select_query(&mut env, "SELECT 1 + 2, rand() + 1.0, (SELECT 1) + 1");
+        let statements = select_query.statements;
+        let Some(Statement::Select(select)) = statements.get("select") else {
+            panic!("Expect select statement");
+        };
+
+        let kinds: Vec<ExprKind> = select
+            .selected_expr
+            .iter()
+            .map(|expr| fold_constants(&mut env, expr.clone()).kind())
+            .collect();
+        assert!(matches!(
+            kinds.as_slice(),
+            [ExprKind::Number, ExprKind::Arithmetic, ExprKind::Arithmetic]
+        ));
+    }
+
+    #[test]
+    fn top_n_sort_with_offset_match_full_sort() {
+        let plan = optimized_plan("SELECT id, name FROM items ORDER BY name DESC LIMIT 2 OFFSET 1");
+        let LogicalPlan::Limit(limit) = &plan else {
+            panic!("Expect limit node");
+        };
+        let LogicalPlan::Sort(sort) = limit.input.as_ref() else {
+            panic!("Expect sort node");
+        };
+        assert_eq!(sort.fetch, Some(3));
+
+        let top_rows =
+            query_rows("SELECT id, name FROM items ORDER BY name DESC LIMIT 2 OFFSET 1").unwrap();
+        let sorted_rows = query_rows("SELECT id, name FROM items ORDER BY name DESC").unwrap();
+        assert_eq!(top_rows, sorted_rows[1..3]);
+    }
+}
@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use std::collections::HashMap;
 
 use gitql_ast::statement::NullsOrderPolicy;
@@ -7,16 +8,19 @@ use gitql_ast::statement::SortingOrder;
 use gitql_core::environment::Environment;
 use gitql_core::object::GitQLObject;
 use gitql_core::object::Group;
+use gitql_core::object::Row;
 use gitql_core::values::null::NullValue;
 use gitql_core::values::Value;
 
 use crate::engine_evaluator::evaluate_expression;
 
+/// Sort the rows of the group, if fetch count exists only the first rows are kept
 pub(crate) fn execute_order_by_statement(
     env: &mut Environment,
     statement: &OrderByStatement,
     gitql_object: &mut GitQLObject,
     group_index: usize,
+    fetch: Option<usize>,
 ) -> Result<(), String> {
     if gitql_object.is_empty() || group_index >= gitql_object.len() {
         return Ok(());
@@ -28,6 +32,11 @@ pub(crate) fn execute_order_by_statement(
     }
 
     let rows_len = main_group.rows.len();
+    if let Some(fetch) = fetch.filter(|fetch| *fetch < rows_len) {
+        let titles = &gitql_object.titles;
+        return execute_top_n_order_by(env, statement, titles, main_group, fetch);
+    }
+
     let arguments_len = statement.arguments.len();
     let main_group_rows = &main_group.rows;
     let titles = &gitql_object.titles;
@@ -52,55 +61,154 @@ pub(crate) fn execute_order_by_statement(
     }
 
     main_group.rows.sort_by(|a, b| {
-        for arg_index in 0..arguments_len {
-            let argument = &statement.arguments[arg_index];
+        // Use the Memory address of A, B as Map keys
+        let a_addr = a.values.as_ptr() as usize;
+        let b_addr = b.values.as_ptr() as usize;
+
+        // Get pre evaluated values from the eval map using addr as key
+        let a_values = eval_map.get(&a_addr).unwrap();
+        let b_values = eval_map.get(&b_addr).unwrap();
+        compare_arguments_values(statement, a_values, b_values)
+    });
+
+    Ok(())
+}
+
+/// Row with it's evaluated ordering arguments and it's index in the group,
+/// the index is used to keep the same order of the equal rows as the stable sort
+struct TopNRow<'a> {
+    statement: &'a OrderByStatement,
+    arguments_values: Vec<Box<dyn Value>>,
+    index: usize,
+    row: Row,
+}
+
+impl Ord for TopNRow<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_arguments_values(
+            self.statement,
+            &self.arguments_values,
+            &other.arguments_values,
+        )
+        .then(self.index.cmp(&other.index))
+    }
+}
+
+impl PartialOrd for TopNRow<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for TopNRow<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TopNRow<'_> {}
+
+/// Keep only the first `fetch` rows in order using a bounded max heap, the heap top is the last kept row
+/// and it's replaced by each row that should be ordered before it, so the other rows are dropped early
+fn execute_top_n_order_by(
+    env: &mut Environment,
+    statement: &OrderByStatement,
+    titles: &[String],
+    group: &mut Group,
+    fetch: usize,
+) -> Result<(), String> {
+    let rows = std::mem::take(&mut group.rows);
+    if fetch == 0 {
+        return Ok(());
+    }
+
+    let mut heap: BinaryHeap<TopNRow> = BinaryHeap::with_capacity(fetch + 1);
+    for (index, row) in rows.into_iter().enumerate() {
+        let mut arguments_values: Vec<Box<dyn Value>> =
+            Vec::with_capacity(statement.arguments.len());
+        for argument in statement.arguments.iter() {
             // No need to compare if the ordering argument is constants
             if argument.is_const() {
+                arguments_values.push(Box::new(NullValue));
                 continue;
             }
 
-            // Use the Memory address of A, B as Map keys
-            let a_addr = a.values.as_ptr() as usize;
-            let b_addr = b.values.as_ptr() as usize;
-
-            // Get pre evaluated values from the eval map using addr as key, arg index as offset
-            let a_value = &eval_map.get(&a_addr).unwrap()[arg_index];
-            let b_value = &eval_map.get(&b_addr).unwrap()[arg_index];
-
-            let null_ordering_policy = &statement.nulls_order_policies[arg_index];
-            if a_value.is_null() {
-                return if null_ordering_policy.eq(&NullsOrderPolicy::NullsFirst) {
-                    Ordering::Less
-                } else {
-                    Ordering::Greater
-                };
-            }
-
-            if b_value.is_null() {
-                return if null_ordering_policy.eq(&NullsOrderPolicy::NullsFirst) {
-                    Ordering::Greater
-                } else {
-                    Ordering::Less
-                };
-            }
+            let value = evaluate_expression(env, argument, titles, &row.values)?;
+            arguments_values.push(value);
+        }
 
-            // Calculate the ordering
-            if let Some(order) = a_value.compare(b_value) {
-                if order == Ordering::Equal {
-                    continue;
-                }
-
-                // Reverse the order if DESC order
-                return if statement.sorting_orders[arg_index] == SortingOrder::Descending {
-                    order.reverse()
-                } else {
-                    order
-                };
+        let top_n_row = TopNRow {
+            statement,
+            arguments_values,
+            index,
+            row,
+        };
+
+        if heap.len() < fetch {
+            heap.push(top_n_row);
+        } else if let Some(mut last_row) = heap.peek_mut() {
+            if top_n_row < *last_row {
+                *last_row = top_n_row;
             }
         }
+    }
 
-        Ordering::Equal
-    });
+    group.rows = heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|top_n_row| top_n_row.row)
+        .collect();
 
     Ok(())
 }
+
+/// Compare the evaluated ordering arguments of two rows
+pub(crate) fn compare_arguments_values(
+    statement: &OrderByStatement,
+    a_values: &[Box<dyn Value>],
+    b_values: &[Box<dyn Value>],
+) -> Ordering {
+    for arg_index in 0..statement.arguments.len() {
+        let argument = &statement.arguments[arg_index];
+        // No need to compare if the ordering argument is constants
+        if argument.is_const() {
+            continue;
+        }
+
+        let a_value = &a_values[arg_index];
+        let b_value = &b_values[arg_index];
+
+        let null_ordering_policy = &statement.nulls_order_policies[arg_index];
+        if a_value.is_null() {
+            return if null_ordering_policy.eq(&NullsOrderPolicy::NullsFirst) {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+        }
+
+        if b_value.is_null() {
+            return if null_ordering_policy.eq(&NullsOrderPolicy::NullsFirst) {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        // Calculate the ordering
+        if let Some(order) = a_value.compare(b_value) {
+            if order == Ordering::Equal {
+                continue;
+            }
+
+            // Reverse the order if DESC order
+            return if statement.sorting_orders[arg_index] == SortingOrder::Descending {
+                order.reverse()
+            } else {
+                order
+            };
+        }
+    }
+
+    Ordering::Equal
+}
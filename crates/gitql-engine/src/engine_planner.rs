@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use gitql_ast::expression::Expr;
+use gitql_ast::statement::AggregationsStatement;
+use gitql_ast::statement::Distinct;
+use gitql_ast::statement::GroupByStatement;
+use gitql_ast::statement::Join;
+use gitql_ast::statement::OrderByStatement;
+use gitql_ast::statement::Statement;
+use gitql_ast::statement::TableSelection;
+use gitql_ast::statement::WindowFunctionsStatement;
+use gitql_core::environment::Environment;
+
+use crate::engine_optimizer::optimize_logical_plan;
+use crate::engine_provider_hints::build_scan_hints;
+use crate::engine_provider_hints::ScanHints;
+
+/// Tree of logical operators that is executed from the leaves to the root
+pub enum LogicalPlan {
+    Scan(ScanNode),
+    Join(JoinNode),
+    Project(ProjectNode),
+    Filter(FilterNode),
+    Aggregate(AggregateNode),
+    Window(WindowNode),
+    Sort(SortNode),
+    Limit(LimitNode),
+    Distinct(DistinctNode),
+}
+
+/// Read the rows of one table, derived table or common table
+pub struct ScanNode {
+    pub table_selection: TableSelection,
+    pub hints: ScanHints,
+    /// Condition pushed down from the `WHERE` statement to filter the rows before joins
+    pub filter: Option<Box<dyn Expr>>,
+}
+
+/// Join the rows of the left and right inputs
+pub struct JoinNode {
+    pub left: Box<LogicalPlan>,
+    pub right: Box<LogicalPlan>,
+    pub join: Join,
+}
+
+/// Evaluate the selected expressions on the rows of the tables
+pub struct ProjectNode {
+    pub input: Box<LogicalPlan>,
+    pub selected_expr_titles: Vec<String>,
+    pub selected_expr: Vec<Box<dyn Expr>>,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum FilterKind {
+    Where,
+    Having,
+    Qualify,
+}
+
+/// Remove the rows that doesn't satisfy the condition
+pub struct FilterNode {
+    pub input: Box<LogicalPlan>,
+    pub kind: FilterKind,
+    pub condition: Box<dyn Expr>,
+}
+
+/// Split the rows into groups and calculate the aggregation functions of each group
+pub struct AggregateNode {
+    pub input: Box<LogicalPlan>,
+    pub group_by: Option<GroupByStatement>,
+    pub aggregations: Option<AggregationsStatement>,
+}
+
+/// Calculate the window functions of each row
+pub struct WindowNode {
+    pub input: Box<LogicalPlan>,
+    pub window_functions: WindowFunctionsStatement,
+}
+
+/// Sort the rows, if fetch count exists only the first rows are needed
+pub struct SortNode {
+    pub input: Box<LogicalPlan>,
+    pub order_by: OrderByStatement,
+    pub fetch: Option<usize>,
+}
+
+/// Skip the first rows by the offset and take rows up to the count
+pub struct LimitNode {
+    pub input: Box<LogicalPlan>,
+    pub offset: Option<Box<dyn Expr>>,
+    pub count: Option<usize>,
+}
+
+/// Remove the duplicated rows
+pub struct DistinctNode {
+    pub input: Box<LogicalPlan>,
+    pub distinct: Distinct,
+}
+
+impl LogicalPlan {
+    /// Return the inputs of this node from left to right
+    pub fn inputs(&self) -> Vec<&LogicalPlan> {
+        match self {
+            LogicalPlan::Scan(_) => vec![],
+            LogicalPlan::Join(node) => vec![&node.left, &node.right],
+            LogicalPlan::Project(node) => vec![&node.input],
+            LogicalPlan::Filter(node) => vec![&node.input],
+            LogicalPlan::Aggregate(node) => vec![&node.input],
+            LogicalPlan::Window(node) => vec![&node.input],
+            LogicalPlan::Sort(node) => vec![&node.input],
+            LogicalPlan::Limit(node) => vec![&node.input],
+            LogicalPlan::Distinct(node) => vec![&node.input],
+        }
+    }
+
+    /// Rebuild this node with the inputs mapped by the function
+    pub fn map_inputs(self, function: &mut impl FnMut(LogicalPlan) -> LogicalPlan) -> LogicalPlan {
+        match self {
+            LogicalPlan::Scan(node) => LogicalPlan::Scan(node),
+            LogicalPlan::Join(mut node) => {
+                node.left = Box::new(function(*node.left));
+                node.right = Box::new(function(*node.right));
+                LogicalPlan::Join(node)
+            }
+            LogicalPlan::Project(mut node) => {
+                node.input = Box::new(function(*node.input));
+                LogicalPlan::Project(node)
+            }
+            LogicalPlan::Filter(mut node) => {
+                node.input = Box::new(function(*node.input));
+                LogicalPlan::Filter(node)
+            }
+            LogicalPlan::Aggregate(mut node) => {
+                node.input = Box::new(function(*node.input));
+                LogicalPlan::Aggregate(node)
+            }
+            LogicalPlan::Window(mut node) => {
+                node.input = Box::new(function(*node.input));
+                LogicalPlan::Window(node)
+            }
+            LogicalPlan::Sort(mut node) => {
+                node.input = Box::new(function(*node.input));
+                LogicalPlan::Sort(node)
+            }
+            LogicalPlan::Limit(mut node) => {
+                node.input = Box::new(function(*node.input));
+                LogicalPlan::Limit(node)
+            }
+            LogicalPlan::Distinct(mut node) => {
+                node.input = Box::new(function(*node.input));
+                LogicalPlan::Distinct(node)
+            }
+        }
+    }
+
+    /// Return the number of nodes in this plan tree,
+    /// nodes are identified by their index in the pre order traversal of the tree
+    pub fn nodes_count(&self) -> usize {
+        1 + self
+            .inputs()
+            .iter()
+            .map(|input| input.nodes_count())
+            .sum::<usize>()
+    }
+
+    /// Return the literal name of the node
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogicalPlan::Scan(_) => "Scan",
+            LogicalPlan::Join(_) => "Join",
+            LogicalPlan::Project(_) => "Project",
+            LogicalPlan::Filter(_) => "Filter",
+            LogicalPlan::Aggregate(_) => "Aggregate",
+            LogicalPlan::Window(_) => "Window",
+            LogicalPlan::Sort(_) => "Sort",
+            LogicalPlan::Limit(_) => "Limit",
+            LogicalPlan::Distinct(_) => "Distinct",
+        }
+    }
+}
+
+/// Build the logical plan of the select query statements and optimize it,
+/// the `INTO` statement is not part of the plan because it's executed on the final rows
+pub(crate) fn plan_select_query(
+    env: &mut Environment,
+    statements: HashMap<&'static str, Statement>,
+    alias_table: &HashMap<String, String>,
+    hidden_selections: &HashMap<String, Vec<String>>,
+) -> Option<LogicalPlan> {
+    let plan = build_logical_plan(env, statements, alias_table)?;
+    Some(optimize_logical_plan(
+        env,
+        plan,
+        alias_table,
+        hidden_selections,
+    ))
+}
+
+/// Build the logical plan in the same order of the statements execution,
+/// scan, join, project, where, group and aggregation, having, window functions,
+/// qualify, order by, offset and limit and finally distinct
+fn build_logical_plan(
+    env: &mut Environment,
+    mut statements: HashMap<&'static str, Statement>,
+    alias_table: &HashMap<String, String>,
+) -> Option<LogicalPlan> {
+    let mut scan_hints = Some(build_scan_hints(env, &statements, alias_table));
+
+    let Some(Statement::Select(select_statement)) = statements.remove("select") else {
+        return None;
+    };
+
+    // Scan hints are built only for single table without joins
+    let mut scans = select_statement
+        .table_selections
+        .into_iter()
+        .map(|table_selection| {
+            LogicalPlan::Scan(ScanNode {
+                table_selection,
+                hints: scan_hints.take().unwrap_or_default(),
+                filter: None,
+            })
+        });
+
+    // Select without table has a scan of one empty row
+    let mut plan = scans.next().unwrap_or_else(|| {
+        LogicalPlan::Scan(ScanNode {
+            table_selection: TableSelection {
+                table_name: String::new(),
+                columns_names: vec![],
+                derived_query: None,
+                file_source: None,
+                table_function: None,
+            },
+            hints: ScanHints::default(),
+            filter: None,
+        })
+    });
+
+    // Each join has the previous tables as left input and the joined table as right input
+    for (join, right) in select_statement.joins.into_iter().zip(scans) {
+        plan = LogicalPlan::Join(JoinNode {
+            left: Box::new(plan),
+            right: Box::new(right),
+            join,
+        });
+    }
+
+    plan = LogicalPlan::Project(ProjectNode {
+        input: Box::new(plan),
+        selected_expr_titles: select_statement.selected_expr_titles,
+        selected_expr: select_statement.selected_expr,
+    });
+
+    if let Some(Statement::Where(where_statement)) = statements.remove("where") {
+        plan = LogicalPlan::Filter(FilterNode {
+            input: Box::new(plan),
+            kind: FilterKind::Where,
+            condition: where_statement.condition,
+        });
+    }
+
+    let group_by = match statements.remove("group") {
+        Some(Statement::GroupBy(group_by)) => Some(group_by),
+        _ => None,
+    };
+
+    let aggregations = match statements.remove("aggregation") {
+        Some(Statement::AggregateFunction(aggregations)) => Some(aggregations),
+        _ => None,
+    };
+
+    if group_by.is_some() || aggregations.is_some() {
+        plan = LogicalPlan::Aggregate(AggregateNode {
+            input: Box::new(plan),
+            group_by,
+            aggregations,
+        });
+    }
+
+    if let Some(Statement::Having(having_statement)) = statements.remove("having") {
+        plan = LogicalPlan::Filter(FilterNode {
+            input: Box::new(plan),
+            kind: FilterKind::Having,
+            condition: having_statement.condition,
+        });
+    }
+
+    if let Some(Statement::WindowFunction(window_functions)) = statements.remove("window_functions")
+    {
+        plan = LogicalPlan::Window(WindowNode {
+            input: Box::new(plan),
+            window_functions,
+        });
+    }
+
+    if let Some(Statement::Qualify(qualify_statement)) = statements.remove("qualify") {
+        plan = LogicalPlan::Filter(FilterNode {
+            input: Box::new(plan),
+            kind: FilterKind::Qualify,
+            condition: qualify_statement.condition,
+        });
+    }
+
+    if let Some(Statement::OrderBy(order_by)) = statements.remove("order") {
+        plan = LogicalPlan::Sort(SortNode {
+            input: Box::new(plan),
+            order_by,
+            fetch: None,
+        });
+    }
+
+    let offset = match statements.remove("offset") {
+        Some(Statement::Offset(offset_statement)) => Some(offset_statement.start),
+        _ => None,
+    };
+
+    let count = match statements.remove("limit") {
+        Some(Statement::Limit(limit_statement)) => Some(limit_statement.count),
+        _ => None,
+    };
+
+    if offset.is_some() || count.is_some() {
+        plan = LogicalPlan::Limit(LimitNode {
+            input: Box::new(plan),
+            offset,
+            count,
+        });
+    }
+
+    if !matches!(select_statement.distinct, Distinct::None) {
+        plan = LogicalPlan::Distinct(DistinctNode {
+            input: Box::new(plan),
+            distinct: select_statement.distinct,
+        });
+    }
+
+    Some(plan)
+}
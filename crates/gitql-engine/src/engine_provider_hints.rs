@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use gitql_ast::expression::CastExpr;
+use gitql_ast::expression::ComparisonExpr;
+use gitql_ast::expression::Expr;
+use gitql_ast::expression::ExprKind;
+use gitql_ast::expression::LogicalExpr;
+use gitql_ast::expression::SymbolExpr;
+use gitql_ast::operator::BinaryLogicalOperator;
+use gitql_ast::operator::ComparisonOperator;
+use gitql_ast::statement::Distinct;
+use gitql_ast::statement::Statement;
+use gitql_core::environment::Environment;
+
+use crate::data_provider::ColumnCondition;
+use crate::data_provider::ProviderHints;
+use crate::engine_evaluator::evaluate_expression;
+
+/// Statements that change the number of rows after the `WHERE` statement,
+/// if any of them exists the limit can't be passed to the provider
+const LIMIT_BLOCKING_STATEMENTS: [&str; 6] = [
+    "group",
+    "aggregation",
+    "having",
+    "window_functions",
+    "qualify",
+    "order",
+];
+
+/// Hints used while scanning the rows of the select statement table
+#[derive(Default)]
+pub struct ScanHints {
+    /// Hints passed to the data provider
+    pub provider_hints: ProviderHints,
+    /// Number of rows that satisfy the where condition which the query need,
+    /// once it's reached the engine stop consuming the provided rows
+    pub limit: Option<usize>,
+    /// The where condition used to count the rows before the limit
+    pub condition: Option<Box<dyn Expr>>,
+}
+
+/// Build the scan hints from the `WHERE` and `LIMIT` statements,
+/// hints are built only if the query select from one schema table without joins
+pub(crate) fn build_scan_hints(
+    env: &mut Environment,
+    statements: &HashMap<&'static str, Statement>,
+    alias_table: &HashMap<String, String>,
+) -> ScanHints {
+    let mut hints = ScanHints::default();
+
+    let Some(Statement::Select(select_statement)) = statements.get("select") else {
+        return hints;
+    };
+
+    if select_statement.table_selections.len() != 1 || !select_statement.joins.is_empty() {
+        return hints;
+    }
+
+    let table_selection = &select_statement.table_selections[0];
+    if table_selection.derived_query.is_some()
+        || env.common_tables.contains_key(&table_selection.table_name)
+    {
+        return hints;
+    }
+
+    let Some(table_columns) = env.schema.table(&table_selection.table_name).map(|table| {
+        table
+            .columns
+            .iter()
+            .map(|column| column.name.to_string())
+            .collect::<Vec<String>>()
+    }) else {
+        return hints;
+    };
+
+    // Collect the simple conditions from the `AND` conjuncts of the where condition
+    let mut has_only_simple_conditions = true;
+    if let Some(Statement::Where(where_statement)) = statements.get("where") {
+        let mut conjuncts = vec![];
+        collect_conjuncts(&where_statement.condition, &mut conjuncts);
+        for conjunct in conjuncts {
+            match extract_column_condition(env, conjunct, alias_table, &table_columns) {
+                Some(condition) => hints.provider_hints.conditions.push(condition),
+                None => has_only_simple_conditions = false,
+            }
+        }
+        hints.condition = Some(where_statement.condition.clone());
+    }
+
+    hints.limit = rows_limit(env, statements, &select_statement.distinct);
+
+    // Limit is passed to the provider only if it can apply all the where conditions
+    if has_only_simple_conditions {
+        hints.provider_hints.limit = hints.limit;
+    }
+
+    hints
+}
+
+/// Return the number of rows needed from the table before the `OFFSET` and `LIMIT` statements,
+/// or None if there is no limit or other statements change the rows before it
+fn rows_limit(
+    env: &mut Environment,
+    statements: &HashMap<&'static str, Statement>,
+    distinct: &Distinct,
+) -> Option<usize> {
+    if !matches!(distinct, Distinct::None)
+        || LIMIT_BLOCKING_STATEMENTS
+            .iter()
+            .any(|name| statements.contains_key(name))
+    {
+        return None;
+    }
+
+    let Some(Statement::Limit(limit_statement)) = statements.get("limit") else {
+        return None;
+    };
+
+    let offset = match statements.get("offset") {
+        Some(Statement::Offset(offset_statement)) => {
+            if !is_constant_expr(&offset_statement.start) {
+                return None;
+            }
+
+            let value = evaluate_expression(env, &offset_statement.start, &[], &vec![]).ok()?;
+            value.as_int().unwrap_or(0).max(0) as usize
+        }
+        _ => 0,
+    };
+
+    Some(limit_statement.count + offset)
+}
+
+#[allow(clippy::borrowed_box)]
+fn collect_conjuncts<'a>(condition: &'a Box<dyn Expr>, conjuncts: &mut Vec<&'a Box<dyn Expr>>) {
+    if condition.kind() == ExprKind::Logical {
+        let logical = condition.as_any().downcast_ref::<LogicalExpr>().unwrap();
+        if logical.operator == BinaryLogicalOperator::And {
+            collect_conjuncts(&logical.left, conjuncts);
+            collect_conjuncts(&logical.right, conjuncts);
+            return;
+        }
+    }
+    conjuncts.push(condition);
+}
+
+/// Convert comparison between table column and constant value into column condition
+#[allow(clippy::borrowed_box)]
+fn extract_column_condition(
+    env: &mut Environment,
+    condition: &Box<dyn Expr>,
+    alias_table: &HashMap<String, String>,
+    table_columns: &[String],
+) -> Option<ColumnCondition> {
+    if condition.kind() != ExprKind::Comparison {
+        return None;
+    }
+
+    let comparison = condition.as_any().downcast_ref::<ComparisonExpr>().unwrap();
+    let (symbol, constant, operator) = if comparison.left.kind() == ExprKind::Symbol
+        && is_constant_expr(&comparison.right)
+    {
+        (
+            &comparison.left,
+            &comparison.right,
+            comparison.operator.clone(),
+        )
+    } else if comparison.right.kind() == ExprKind::Symbol && is_constant_expr(&comparison.left) {
+        let operator = match comparison.operator {
+            ComparisonOperator::Greater => ComparisonOperator::Less,
+            ComparisonOperator::GreaterEqual => ComparisonOperator::LessEqual,
+            ComparisonOperator::Less => ComparisonOperator::Greater,
+            ComparisonOperator::LessEqual => ComparisonOperator::GreaterEqual,
+            ref operator => operator.clone(),
+        };
+        (&comparison.right, &comparison.left, operator)
+    } else {
+        return None;
+    };
+
+    // Symbols in the where condition are replaced by their alias if exists
+    let symbol_name = &symbol.as_any().downcast_ref::<SymbolExpr>().unwrap().value;
+    let column = alias_table
+        .iter()
+        .find(|(_, alias)| *alias == symbol_name)
+        .map(|(column, _)| column)
+        .unwrap_or(symbol_name);
+
+    if !table_columns.contains(column) {
+        return None;
+    }
+
+    let value = evaluate_expression(env, constant, &[], &vec![]).ok()?;
+    Some(ColumnCondition {
+        column: column.to_string(),
+        operator,
+        value,
+    })
+}
+
+/// Return true if the expression is a constant or implicit cast of constant
+#[allow(clippy::borrowed_box)]
+fn is_constant_expr(expr: &Box<dyn Expr>) -> bool {
+    if expr.kind() == ExprKind::Cast {
+        let cast = expr.as_any().downcast_ref::<CastExpr>().unwrap();
+        return cast.value.is_const();
+    }
+    expr.is_const()
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hasher;
+
+use gitql_ast::query::SetOperator;
+use gitql_core::object::GitQLObject;
+use gitql_core::object::Row;
+use gitql_core::values::Value;
+
+/// Combine the rows of the left and right objects depending on the set operator,
+/// the result is stored in the left object with it's titles
+pub(crate) fn apply_set_operation(
+    operator: &SetOperator,
+    is_all: bool,
+    left: &mut GitQLObject,
+    mut right: GitQLObject,
+) {
+    left.flat();
+    right.flat();
+
+    let left_rows = std::mem::take(&mut left.groups[0].rows);
+    let right_rows = std::mem::take(&mut right.groups[0].rows);
+
+    let rows = match operator {
+        SetOperator::Union => {
+            let mut rows = left_rows;
+            rows.extend(right_rows);
+            if !is_all {
+                let mut distinct_rows = DistinctRows::default();
+                rows.retain(|row| distinct_rows.insert(&row.values));
+            }
+            rows
+        }
+        SetOperator::Intersect | SetOperator::Except => {
+            // Right rows bucketed by hash with the count of each distinct row
+            let mut right_counts: HashMap<u64, Vec<(Row, usize)>> = HashMap::new();
+            for row in right_rows {
+                let bucket = right_counts
+                    .entry(row_values_hash(&row.values))
+                    .or_default();
+                match bucket
+                    .iter_mut()
+                    .find(|(other, _)| rows_values_equals(&row.values, &other.values))
+                {
+                    Some((_, count)) => *count += 1,
+                    None => bucket.push((row, 1)),
+                }
+            }
+
+            let is_intersect = *operator == SetOperator::Intersect;
+            let mut distinct_rows = DistinctRows::default();
+            let mut rows = Vec::with_capacity(left_rows.len());
+            for row in left_rows {
+                // Without `ALL` each row is compared once and appear once in the result
+                if !is_all && !distinct_rows.insert(&row.values) {
+                    continue;
+                }
+
+                // With `ALL` each right row can match only one left row
+                let count = right_counts
+                    .get_mut(&row_values_hash(&row.values))
+                    .and_then(|bucket| {
+                        bucket
+                            .iter_mut()
+                            .find(|(other, _)| rows_values_equals(&row.values, &other.values))
+                    })
+                    .map(|(_, count)| count);
+
+                let is_matched = match count {
+                    Some(count) if *count > 0 => {
+                        if is_all {
+                            *count -= 1;
+                        }
+                        true
+                    }
+                    _ => false,
+                };
+
+                if is_matched == is_intersect {
+                    rows.push(row);
+                }
+            }
+            rows
+        }
+    };
+
+    left.groups[0].rows = rows;
+}
+
+/// Set of distinct rows values, the values hash used only to find the bucket
+/// and the rows in the same bucket are compared value by value
+#[derive(Default)]
+pub(crate) struct DistinctRows {
+    buckets: HashMap<u64, Vec<Vec<Box<dyn Value>>>>,
+}
+
+impl DistinctRows {
+    /// Insert the row values and return true if there is no equal row in the set
+    pub(crate) fn insert(&mut self, values: &[Box<dyn Value>]) -> bool {
+        let bucket = self.buckets.entry(row_values_hash(values)).or_default();
+        if bucket.iter().any(|other| rows_values_equals(values, other)) {
+            return false;
+        }
+        bucket.push(values.to_vec());
+        true
+    }
+}
+
+/// Compute the hash of the row values
+fn row_values_hash(values: &[Box<dyn Value>]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for value in values {
+        value.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Return true if both rows has the same values
+fn rows_values_equals(left: &[Box<dyn Value>], right: &[Box<dyn Value>]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right.iter())
+            .all(|(left, right)| left.equals(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::first_column;
+    use crate::test_utils::query_rows;
+
+    #[test]
+    fn union_keeps_null_and_null_text() {
+        let rows =
+            query_rows("SELECT parent_id FROM items WHERE id = 1 UNION SELECT 'Null'").unwrap();
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    fn union_removes_duplicated_rows() {
+        let rows =
+            query_rows("SELECT parent_id FROM items UNION SELECT parent_id FROM items").unwrap();
+        assert_eq!(first_column(&rows), ["Null", "root", "src"]);
+    }
+
+    #[test]
+    fn intersect_and_except_all_match_each_row_once() {
+        let rows = query_rows(
+            "SELECT parent_id FROM items INTERSECT ALL SELECT parent_id FROM items WHERE id > 3",
+        )
+        .unwrap();
+        assert_eq!(first_column(&rows), ["src", "src"]);
+
+        let rows = query_rows(
+            "SELECT parent_id FROM items EXCEPT ALL SELECT parent_id FROM items WHERE id > 2",
+        )
+        .unwrap();
+        assert_eq!(first_column(&rows), ["Null", "root"]);
+    }
+
+    #[test]
+    fn order_by_and_limit_apply_on_combined_rows() {
+        let rows = query_rows(
+            "SELECT value FROM generate_series(1,3) UNION ALL SELECT value FROM generate_series(10,12) ORDER BY value DESC LIMIT 2",
+        )
+        .unwrap();
+        assert_eq!(first_column(&rows), ["12", "11"]);
+
+        let rows = query_rows(
+            "SELECT value FROM generate_series(1,3) UNION ALL SELECT value FROM generate_series(10,12) OFFSET 2 LIMIT 2",
+        )
+        .unwrap();
+        assert_eq!(first_column(&rows), ["3", "10"]);
+    }
+
+    #[test]
+    fn limit_before_set_operator_is_rejected() {
+        let result = query_rows(
+            "SELECT value FROM generate_series(1,3) LIMIT 1 UNION SELECT value FROM generate_series(1,3)",
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,18 +1,28 @@
 use std::collections::HashMap;
 
 use gitql_ast::statement::GroupByStatement;
+use gitql_ast::statement::SortingOrder;
 use gitql_ast::statement::WindowDefinition;
+use gitql_ast::statement::WindowFrame;
+use gitql_ast::statement::WindowFrameBound;
+use gitql_ast::statement::WindowFrameUnit;
 use gitql_ast::statement::WindowFunctionKind;
 use gitql_ast::statement::WindowFunctionsStatement;
 use gitql_ast::statement::WindowValue;
 use gitql_core::environment::Environment;
 use gitql_core::object::GitQLObject;
+use gitql_core::object::Row;
+use gitql_core::values::null::NullValue;
+use gitql_core::values::Value;
 
 use crate::engine_evaluator::evaluate_expression;
 use crate::engine_executor::resolve_actual_column_name;
 use crate::engine_group::execute_group_by_statement;
 use crate::engine_ordering::execute_order_by_statement;
 
+/// Window functions that return one of the values in the frame of the row
+const FRAME_VALUE_FUNCTIONS: [&str; 3] = ["first_value", "last_value", "nth_value"];
+
 pub(crate) fn execute_window_functions_statement(
     env: &mut Environment,
     statement: &WindowFunctionsStatement,
@@ -67,6 +77,64 @@ pub(crate) fn execute_window_functions_statement(
                     continue;
                 }
 
+                // Peers are rows with equal ordering values, they are needed for window functions
+                // such as `rank` and for `GROUPS` and `RANGE` frames
+                let window_frame = &function.window_definition.frame;
+                let is_pure_window_function =
+                    matches!(function.kind, WindowFunctionKind::PureWindowFunction);
+                let peers = if is_pure_window_function
+                    || window_frame
+                        .as_ref()
+                        .is_some_and(|window_frame| window_frame.unit != WindowFrameUnit::Rows)
+                {
+                    PeerGroups::new(
+                        env,
+                        &function.window_definition,
+                        &gitql_object.titles,
+                        &frame.rows,
+                    )?
+                } else {
+                    PeerGroups::default()
+                };
+
+                // Evaluate aggregation and value functions on the frame of each row if the window has frame clause,
+                // other window functions such as `rank` and `lag` are evaluated on the whole partition
+                let is_frame_function = match function.kind {
+                    WindowFunctionKind::AggregatedWindowFunction => true,
+                    WindowFunctionKind::PureWindowFunction => {
+                        FRAME_VALUE_FUNCTIONS.contains(&function.function_name.as_str())
+                    }
+                };
+
+                if let Some(window_frame) = window_frame.as_ref().filter(|_| is_frame_function) {
+                    let rows_frames = resolve_rows_frames(
+                        env,
+                        window_frame,
+                        &function.window_definition,
+                        &gitql_object.titles,
+                        &frame.rows,
+                        &peers,
+                    )?;
+
+                    for (row_index, (start, end)) in rows_frames.into_iter().enumerate() {
+                        frame.rows[row_index].values[column_index] = if start >= end {
+                            Box::new(NullValue)
+                        } else {
+                            match function.kind {
+                                WindowFunctionKind::AggregatedWindowFunction => {
+                                    let aggregation_function =
+                                        env.aggregation_function(&function.function_name).unwrap();
+                                    aggregation_function(&frame_values[start..end])
+                                }
+                                WindowFunctionKind::PureWindowFunction => {
+                                    frame_value(&function.function_name, &frame_values, start, end)
+                                }
+                            }
+                        };
+                    }
+                    continue;
+                }
+
                 // Evaluate function for this frame
                 match function.kind {
                     WindowFunctionKind::AggregatedWindowFunction => {
@@ -79,7 +147,7 @@ pub(crate) fn execute_window_functions_statement(
                     }
                     WindowFunctionKind::PureWindowFunction => {
                         let window_function = env.window_function(&function.function_name).unwrap();
-                        let window_values = window_function(&frame_values);
+                        let window_values = window_function(&frame_values, &peers.row_group);
                         for (index, value) in window_values.iter().enumerate() {
                             frame.rows[index].values[column_index] = value.clone();
                         }
@@ -115,6 +183,30 @@ pub(crate) fn execute_window_functions_statement(
     Ok(())
 }
 
+/// Return the value of `first_value`, `last_value` or `nth_value` in the frame `start..end`
+/// without evaluating the function on all the frame rows
+fn frame_value(
+    function_name: &str,
+    frame_values: &[Vec<Box<dyn Value>>],
+    start: usize,
+    end: usize,
+) -> Box<dyn Value> {
+    let index = match function_name {
+        "first_value" => Some(start),
+        "last_value" => Some(end - 1),
+        _ => frame_values[start][1]
+            .as_int()
+            .filter(|index| *index >= 0)
+            .map(|index| start + index as usize)
+            .filter(|index| *index < end),
+    };
+
+    match index {
+        Some(index) => frame_values[index][0].clone(),
+        None => Box::new(NullValue),
+    }
+}
+
 fn apply_window_definition_on_gitql_object(
     env: &mut Environment,
     gitql_object: &mut GitQLObject,
@@ -122,20 +214,342 @@ fn apply_window_definition_on_gitql_object(
 ) -> Result<(), String> {
     // Apply partitioning on the main group
     if let Some(partition_by) = &window_definition.partitioning_clause {
-        let group_by = GroupByStatement {
-            values: vec![partition_by.expr.clone()],
-            has_with_roll_up: false,
-        };
+        let group_by = GroupByStatement::new(vec![partition_by.expr.clone()]);
         execute_group_by_statement(env, &group_by, gitql_object)?;
     }
 
     // Apply ordering each partition
     if let Some(window_ordering) = &window_definition.ordering_clause {
         for index in 0..gitql_object.len() {
-            execute_order_by_statement(env, &window_ordering.order_by, gitql_object, index)?;
+            execute_order_by_statement(env, &window_ordering.order_by, gitql_object, index, None)?;
         }
     }
 
-    // TODO: Convert groups into window frames
     Ok(())
 }
+
+/// Resolve the frame of each row in the ordered partition as range of rows indices `start..end`
+fn resolve_rows_frames(
+    env: &mut Environment,
+    window_frame: &WindowFrame,
+    window_definition: &WindowDefinition,
+    titles: &[String],
+    rows: &[Row],
+    peers: &PeerGroups,
+) -> Result<Vec<(usize, usize)>, String> {
+    let rows_len = rows.len();
+    let start_offset = evaluate_frame_offset(env, &window_frame.unit, &window_frame.start)?;
+    let end_offset = evaluate_frame_offset(env, &window_frame.unit, &window_frame.end)?;
+
+    // Distance between rows in `RANGE` frame with offset is calculated from the first ordering value
+    let ordering_keys = if window_frame.unit == WindowFrameUnit::Range
+        && (start_offset.is_some() || end_offset.is_some())
+    {
+        range_ordering_keys(env, window_definition, titles, rows)?
+    } else {
+        vec![]
+    };
+    let non_null_bounds = non_null_keys_bounds(&ordering_keys);
+
+    let mut rows_frames = Vec::with_capacity(rows_len);
+    for row_index in 0..rows_len {
+        let (start, end) = match window_frame.unit {
+            WindowFrameUnit::Rows => {
+                let start = match &window_frame.start {
+                    WindowFrameBound::UnboundedPreceding => 0,
+                    WindowFrameBound::Preceding(_) => {
+                        row_index.saturating_sub(start_offset.unwrap() as usize)
+                    }
+                    WindowFrameBound::CurrentRow => row_index,
+                    WindowFrameBound::Following(_) => row_index + start_offset.unwrap() as usize,
+                    WindowFrameBound::UnboundedFollowing => rows_len,
+                };
+
+                let end = match &window_frame.end {
+                    WindowFrameBound::UnboundedPreceding => 0,
+                    WindowFrameBound::Preceding(_) => {
+                        (row_index + 1).saturating_sub(end_offset.unwrap() as usize)
+                    }
+                    WindowFrameBound::CurrentRow => row_index + 1,
+                    WindowFrameBound::Following(_) => row_index + 1 + end_offset.unwrap() as usize,
+                    WindowFrameBound::UnboundedFollowing => rows_len,
+                };
+
+                (start, end)
+            }
+            WindowFrameUnit::Groups => {
+                let group = peers.row_group[row_index];
+                let groups_len = peers.groups_bounds.len();
+                let start = match &window_frame.start {
+                    WindowFrameBound::UnboundedPreceding => 0,
+                    WindowFrameBound::Preceding(_) => {
+                        let offset = start_offset.unwrap() as usize;
+                        peers.groups_bounds[group.saturating_sub(offset)].0
+                    }
+                    WindowFrameBound::CurrentRow => peers.groups_bounds[group].0,
+                    WindowFrameBound::Following(_) => {
+                        let offset = start_offset.unwrap() as usize;
+                        peers
+                            .groups_bounds
+                            .get(group + offset)
+                            .map_or(rows_len, |bounds| bounds.0)
+                    }
+                    WindowFrameBound::UnboundedFollowing => rows_len,
+                };
+
+                let end = match &window_frame.end {
+                    WindowFrameBound::UnboundedPreceding => 0,
+                    WindowFrameBound::Preceding(_) => {
+                        let offset = end_offset.unwrap() as usize;
+                        group
+                            .checked_sub(offset)
+                            .map_or(0, |group| peers.groups_bounds[group].1)
+                    }
+                    WindowFrameBound::CurrentRow => peers.groups_bounds[group].1,
+                    WindowFrameBound::Following(_) => {
+                        let offset = end_offset.unwrap() as usize;
+                        peers.groups_bounds[(group + offset).min(groups_len - 1)].1
+                    }
+                    WindowFrameBound::UnboundedFollowing => rows_len,
+                };
+
+                (start, end)
+            }
+            WindowFrameUnit::Range => {
+                let peer_bounds = peers.groups_bounds[peers.row_group[row_index]];
+                let row_key = ordering_keys.get(row_index).copied().flatten();
+                let start = match (&window_frame.start, row_key) {
+                    (WindowFrameBound::UnboundedPreceding, _) => 0,
+                    (WindowFrameBound::Preceding(_), Some(key)) => {
+                        first_key_index(&ordering_keys, non_null_bounds, |other| {
+                            other >= key - start_offset.unwrap()
+                        })
+                    }
+                    (WindowFrameBound::Following(_), Some(key)) => {
+                        first_key_index(&ordering_keys, non_null_bounds, |other| {
+                            other >= key + start_offset.unwrap()
+                        })
+                    }
+                    (WindowFrameBound::UnboundedFollowing, _) => rows_len,
+                    _ => peer_bounds.0,
+                };
+
+                let end = match (&window_frame.end, row_key) {
+                    (WindowFrameBound::UnboundedPreceding, _) => 0,
+                    (WindowFrameBound::Preceding(_), Some(key)) => {
+                        first_key_index(&ordering_keys, non_null_bounds, |other| {
+                            other > key - end_offset.unwrap()
+                        })
+                    }
+                    (WindowFrameBound::Following(_), Some(key)) => {
+                        first_key_index(&ordering_keys, non_null_bounds, |other| {
+                            other > key + end_offset.unwrap()
+                        })
+                    }
+                    (WindowFrameBound::UnboundedFollowing, _) => rows_len,
+                    _ => peer_bounds.1,
+                };
+
+                (start, end)
+            }
+        };
+
+        let end = end.min(rows_len);
+        rows_frames.push((start.min(end), end));
+    }
+
+    Ok(rows_frames)
+}
+
+/// Evaluate the offset of the frame bound, for `RANGE` frame the interval offset is converted to seconds
+fn evaluate_frame_offset(
+    env: &mut Environment,
+    unit: &WindowFrameUnit,
+    bound: &WindowFrameBound,
+) -> Result<Option<f64>, String> {
+    let expression = match bound {
+        WindowFrameBound::Preceding(offset) | WindowFrameBound::Following(offset) => offset,
+        _ => return Ok(None),
+    };
+
+    let value = evaluate_expression(env, expression, &[], &vec![])?;
+    let offset = if let Some(interval) = value.as_interval() {
+        interval.to_seconds() as f64
+    } else if let Some(offset) = value.as_int() {
+        offset as f64
+    } else if let Some(offset) = value.as_float() {
+        offset
+    } else {
+        return Err("Window frame offset can't be NULL".to_string());
+    };
+
+    if offset < 0.0 {
+        return Err("Window frame offset can't be negative".to_string());
+    }
+
+    // `ROWS` and `GROUPS` offsets are counts of rows and peers groups
+    if *unit != WindowFrameUnit::Range {
+        return Ok(Some(offset.trunc()));
+    }
+
+    Ok(Some(offset))
+}
+
+/// Rows with equal ordering values in the ordered partition
+#[derive(Default)]
+struct PeerGroups {
+    /// The index of the peer group of each row
+    row_group: Vec<usize>,
+    /// The range of rows indices `start..end` of each peer group
+    groups_bounds: Vec<(usize, usize)>,
+}
+
+impl PeerGroups {
+    fn new(
+        env: &mut Environment,
+        window_definition: &WindowDefinition,
+        titles: &[String],
+        rows: &[Row],
+    ) -> Result<Self, String> {
+        let ordering_arguments = match &window_definition.ordering_clause {
+            Some(ordering_clause) => ordering_clause.order_by.arguments.as_slice(),
+            None => &[],
+        };
+
+        let mut peers = PeerGroups::default();
+        let mut previous_values: Option<Vec<Box<dyn Value>>> = None;
+        for (row_index, row) in rows.iter().enumerate() {
+            let mut values = Vec::with_capacity(ordering_arguments.len());
+            for argument in ordering_arguments {
+                values.push(evaluate_expression(env, argument, titles, &row.values)?);
+            }
+
+            let is_peer = previous_values.as_ref().is_some_and(|previous_values| {
+                previous_values
+                    .iter()
+                    .zip(values.iter())
+                    .all(|(previous, current)| previous.equals(current))
+            });
+
+            if is_peer {
+                peers.groups_bounds.last_mut().unwrap().1 = row_index + 1;
+            } else {
+                peers.groups_bounds.push((row_index, row_index + 1));
+            }
+
+            peers.row_group.push(peers.groups_bounds.len() - 1);
+            previous_values = Some(values);
+        }
+
+        Ok(peers)
+    }
+}
+
+/// Return the first ordering value of each row as number in ascending order, or None for NULL values
+fn range_ordering_keys(
+    env: &mut Environment,
+    window_definition: &WindowDefinition,
+    titles: &[String],
+    rows: &[Row],
+) -> Result<Vec<Option<f64>>, String> {
+    let order_by = &window_definition.ordering_clause.as_ref().unwrap().order_by;
+    let direction = match order_by.sorting_orders[0] {
+        SortingOrder::Ascending => 1.0,
+        SortingOrder::Descending => -1.0,
+    };
+
+    let mut keys = Vec::with_capacity(rows.len());
+    for row in rows {
+        let value = evaluate_expression(env, &order_by.arguments[0], titles, &row.values)?;
+        let key = value
+            .as_int()
+            .map(|value| value as f64)
+            .or_else(|| value.as_float())
+            .or_else(|| value.as_date_time().map(|value| value as f64))
+            .or_else(|| value.as_date().map(|value| value as f64));
+        keys.push(key.map(|key| key * direction));
+    }
+
+    Ok(keys)
+}
+
+/// Return the range of rows indices with non NULL keys, the keys are sorted so NULL keys are grouped
+/// at the start or the end of the partition
+fn non_null_keys_bounds(keys: &[Option<f64>]) -> (usize, usize) {
+    let start = keys.iter().position(Option::is_some).unwrap_or(keys.len());
+    let end = keys
+        .iter()
+        .rposition(Option::is_some)
+        .map_or(start, |index| index + 1);
+    (start, end)
+}
+
+/// Return the index of the first row with non NULL key that match the predicate using binary search
+fn first_key_index(
+    keys: &[Option<f64>],
+    non_null_bounds: (usize, usize),
+    predicate: impl Fn(f64) -> bool,
+) -> usize {
+    let (start, end) = non_null_bounds;
+    start + keys[start..end].partition_point(|key| !predicate(key.unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::query_rows;
+
+    fn window_column(window: &str) -> Vec<String> {
+        let rows = query_rows(&format!("SELECT id, {window} AS w FROM items")).unwrap();
+        rows.into_iter().map(|mut row| row.remove(1)).collect()
+    }
+
+    #[test]
+    fn ranking_and_offset_functions_ignore_frame() {
+        let frame = "ORDER BY id ROWS BETWEEN CURRENT ROW AND CURRENT ROW";
+        assert_eq!(
+            window_column(&format!("row_number() OVER ({frame})")),
+            ["1", "2", "3", "4", "5"]
+        );
+        assert_eq!(
+            window_column("rank() OVER (ORDER BY parent_id ROWS 1 PRECEDING)"),
+            window_column("rank() OVER (ORDER BY parent_id)")
+        );
+        assert_eq!(
+            window_column(&format!("lag(id) OVER ({frame})")),
+            ["Null", "1", "2", "3", "4"]
+        );
+        assert_eq!(
+            window_column(&format!("lead(id) OVER ({frame})")),
+            ["2", "3", "4", "5", "Null"]
+        );
+    }
+
+    #[test]
+    fn value_functions_use_frame() {
+        let frame = "ORDER BY id ROWS BETWEEN 1 PRECEDING AND 1 FOLLOWING";
+        assert_eq!(
+            window_column(&format!("first_value(id) OVER ({frame})")),
+            ["1", "1", "2", "3", "4"]
+        );
+        assert_eq!(
+            window_column(&format!("last_value(id) OVER ({frame})")),
+            ["2", "3", "4", "5", "5"]
+        );
+        assert_eq!(
+            window_column(&format!("nth_value(id, 2) OVER ({frame})")),
+            ["Null", "3", "4", "5", "Null"]
+        );
+    }
+
+    #[test]
+    fn aggregation_functions_use_frame() {
+        assert_eq!(
+            window_column("count(id) OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)"),
+            ["1", "2", "2", "2", "2"]
+        );
+        assert_eq!(
+            window_column("count(id) OVER (ORDER BY id ROWS BETWEEN 1 FOLLOWING AND 1 FOLLOWING)"),
+            ["1", "1", "1", "1", "Null"]
+        );
+    }
+}
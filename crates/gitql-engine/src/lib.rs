@@ -3,9 +3,17 @@ pub mod engine;
 pub mod engine_distinct;
 pub mod engine_evaluator;
 pub mod engine_executor;
+pub mod engine_explain;
 pub mod engine_filter;
 pub mod engine_group;
 pub mod engine_join;
+pub mod engine_optimizer;
 pub mod engine_ordering;
 pub mod engine_output_into;
+pub mod engine_planner;
+pub mod engine_provider_hints;
+pub mod engine_set_operation;
 pub mod engine_window_functions;
+
+#[cfg(test)]
+pub(crate) mod test_utils;
@@ -0,0 +1,135 @@
+use gitql_ast::query::Query;
+use gitql_ast::query::SelectQuery;
+use gitql_ast::types::integer::IntType;
+use gitql_ast::types::text::TextType;
+use gitql_core::environment::Environment;
+use gitql_core::object::Row;
+use gitql_core::schema::ColumnDefinition;
+use gitql_core::schema::Schema;
+use gitql_core::schema::TableDefinition;
+use gitql_core::values::integer::IntValue;
+use gitql_core::values::null::NullValue;
+use gitql_core::values::text::TextValue;
+use gitql_core::values::Value;
+use gitql_parser::parser;
+use gitql_parser::tokenizer::Tokenizer;
+use gitql_std::aggregation::aggregation_function_signatures;
+use gitql_std::aggregation::aggregation_functions;
+use gitql_std::aggregation::ordered_set_aggregation_function_signatures;
+use gitql_std::aggregation::ordered_set_aggregation_functions;
+use gitql_std::standard::standard_function_signatures;
+use gitql_std::standard::standard_functions;
+use gitql_std::table::table_function_signatures;
+use gitql_std::table::table_functions;
+use gitql_std::window::window_function_signatures;
+use gitql_std::window::window_functions;
+
+use crate::data_provider::DataProvider;
+use crate::engine;
+use crate::engine::EvaluationResult;
+
+/// Rows of the `items` table as (id, name, parent_id)
+const ITEMS: [(i64, &str, Option<&str>); 5] = [
+    (1, "root", None),
+    (2, "src", Some("root")),
+    (3, "docs", Some("root")),
+    (4, "lib", Some("src")),
+    (5, "main", Some("src")),
+];
+
+/// Provide the in memory `items` table used by the engine tests
+pub(crate) struct ItemsDataProvider;
+
+impl DataProvider for ItemsDataProvider {
+    fn provide(&self, table: &str, selected_columns: &[String]) -> Result<Vec<Row>, String> {
+        if table != "items" {
+            return Err(format!("Unexpected table `{table}`"));
+        }
+
+        let mut rows: Vec<Row> = Vec::with_capacity(ITEMS.len());
+        for (id, name, parent_id) in ITEMS {
+            let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+            for column_name in selected_columns {
+                let value: Box<dyn Value> = match (column_name.as_str(), parent_id) {
+                    ("id", _) => Box::new(IntValue::new(id)),
+                    ("name", _) => Box::new(TextValue::new(name.to_string())),
+                    ("parent_id", Some(parent_id)) => {
+                        Box::new(TextValue::new(parent_id.to_string()))
+                    }
+                    _ => Box::new(NullValue),
+                };
+                values.push(value);
+            }
+            rows.push(Row { values });
+        }
+        Ok(rows)
+    }
+}
+
+/// Create environment with the `items` table and the standard functions
+pub(crate) fn test_environment() -> Environment {
+    let schema = Schema::with_tables(vec![TableDefinition::new(
+        "items",
+        vec![
+            ColumnDefinition::new("id", Box::new(IntType)),
+            ColumnDefinition::new("name", Box::new(TextType)),
+            ColumnDefinition::new("parent_id", Box::new(TextType)).nullable(),
+        ],
+    )]);
+
+    let mut env = Environment::new(schema);
+    env.with_standard_functions(&standard_function_signatures(), standard_functions());
+    env.with_aggregation_functions(&aggregation_function_signatures(), aggregation_functions());
+    env.with_ordered_set_aggregation_functions(
+        &ordered_set_aggregation_function_signatures(),
+        ordered_set_aggregation_functions(),
+    );
+    env.with_window_functions(&window_function_signatures(), window_functions());
+    env.with_table_functions(&table_function_signatures(), table_functions());
+    env
+}
+
+/// Parse the select query in the environment
+pub(crate) fn parse_select_query(env: &mut Environment, query: &str) -> SelectQuery {
+    let tokens = Tokenizer::tokenize(query)
+        .unwrap_or_else(|diagnostic| panic!("Invalid query {query}, {}", diagnostic.message()));
+    let queries = parser::parse_gql(tokens, env)
+        .unwrap_or_else(|diagnostic| panic!("Invalid query {query}, {}", diagnostic.message()));
+    match queries.into_iter().next() {
+        Some(Query::Select(select_query)) => select_query,
+        _ => panic!("Expect select query {query}"),
+    }
+}
+
+/// Parse and evaluate the query and return the literals of the last selected rows
+pub(crate) fn query_rows(query: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut env = test_environment();
+    let tokens =
+        Tokenizer::tokenize(query).map_err(|diagnostic| diagnostic.message().to_owned())?;
+    let queries = parser::parse_gql(tokens, &mut env)
+        .map_err(|diagnostic| diagnostic.message().to_owned())?;
+
+    let provider: Box<dyn DataProvider> = Box::new(ItemsDataProvider);
+    let mut rows = vec![];
+    for evaluation_result in engine::evaluate(&mut env, &provider, queries)? {
+        if let EvaluationResult::SelectedGroups(object) = evaluation_result {
+            rows = object
+                .groups
+                .first()
+                .map(|group| {
+                    group
+                        .rows
+                        .iter()
+                        .map(|row| row.values.iter().map(|value| value.literal()).collect())
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+    }
+    Ok(rows)
+}
+
+/// Return the first value of each row
+pub(crate) fn first_column(rows: &[Vec<String>]) -> Vec<&str> {
+    rows.iter().map(|row| row[0].as_str()).collect()
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
+use gitql_ast::query::SelectQuery;
 use gitql_ast::statement::AggregateValue;
+use gitql_ast::statement::FileSource;
+use gitql_ast::statement::TableFunctionCall;
 use gitql_ast::statement::WindowDefinition;
 use gitql_ast::statement::WindowValue;
 
@@ -21,6 +24,11 @@ pub struct ParserContext {
     pub projection_names: Vec<String>,
     pub projection_locations: Vec<SourceLocation>,
 
+    pub derived_tables: HashMap<String, SelectQuery>,
+    pub file_tables: HashMap<String, FileSource>,
+    pub table_functions: HashMap<String, TableFunctionCall>,
+    pub subqueries: HashMap<String, SelectQuery>,
+
     pub name_alias_table: HashMap<String, String>,
     pub name_generator: NameGenerator,
 
@@ -32,4 +40,6 @@ pub struct ParserContext {
     pub inside_having: bool,
     pub inside_order_by: bool,
     pub inside_over_clauses: bool,
+    pub inside_join_predicate: bool,
+    pub inside_aggregation_filter: bool,
 }
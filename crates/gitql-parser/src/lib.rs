@@ -9,10 +9,14 @@ pub mod tokenizer;
 
 pub(crate) mod parse_cast;
 pub(crate) mod parse_comparisons;
+pub(crate) mod parse_file_table;
 pub(crate) mod parse_function_call;
+pub(crate) mod parse_group_by;
 pub(crate) mod parse_interval;
 pub(crate) mod parse_into;
 pub(crate) mod parse_ordering;
 pub(crate) mod parse_string_matchers;
+pub(crate) mod parse_table_function;
 pub(crate) mod parse_type;
+pub(crate) mod parse_window_frame;
 pub mod parser;
@@ -1,5 +1,6 @@
 static COLUMN_NAME_PREFIX: &str = "column_";
 static HIDDEN_NAME_PREFIX: &str = "_@temp_";
+static SUBQUERY_NAME_PREFIX: &str = "_@subquery_";
 
 /// Component to generate name for visible and hidden columns with number as prefix started from 0
 #[derive(Default)]
@@ -22,6 +23,12 @@ impl NameGenerator {
         name
     }
 
+    /// Generate name for subquery, the subquery position in the tokens keeps the name unique
+    /// between the nested queries that have their own generators
+    pub fn generate_subquery_name(&self, position: usize) -> String {
+        format!("{SUBQUERY_NAME_PREFIX}{position}")
+    }
+
     /// Reset the name counter to start from 0 in new session
     pub fn reset_numbers(&mut self) {
         self.column_name_number = 0;
@@ -0,0 +1,87 @@
+use gitql_ast::statement::FileFormat;
+use gitql_ast::statement::FileSource;
+use gitql_core::environment::Environment;
+use gitql_core::file_table::read_file_table;
+
+use crate::context::ParserContext;
+use crate::diagnostic::Diagnostic;
+use crate::parser::calculate_safe_location;
+use crate::parser::consume_conditional_token_or_errors;
+use crate::parser::consume_token_or_error;
+use crate::parser::is_next_token;
+use crate::parser::parse_optional_table_alias;
+use crate::token::Token;
+use crate::token::TokenKind;
+
+/// Return true if the current tokens are file table function call like `read_csv(`
+pub(crate) fn is_file_table_call(tokens: &[Token], position: &usize) -> bool {
+    *position < tokens.len()
+        && matches!(tokens[*position].kind, TokenKind::Symbol(_))
+        && file_table_format(&tokens[*position].to_string()).is_some()
+        && is_next_token(tokens, position, TokenKind::LeftParen)
+}
+
+/// Parse file table `read_csv('path') [AS name]` or `read_json('path') [AS name]`,
+/// infer the columns types from the file and return the table name,
+/// by default the table name is the file path
+pub(crate) fn parse_file_table(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<String, Box<Diagnostic>> {
+    let location = calculate_safe_location(tokens, *position);
+    let format = file_table_format(&tokens[*position].to_string()).unwrap();
+
+    // Consume the function name and `(`
+    *position += 2;
+
+    let path = consume_conditional_token_or_errors(
+        tokens,
+        position,
+        |token| matches!(token.kind, TokenKind::String(_)),
+        "Expect file path as `Text` literal, for example `read_csv('file.csv')`",
+    )?
+    .to_string();
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` after the file path",
+    )?;
+
+    let table_name = parse_optional_table_alias(tokens, position)?.unwrap_or(path.to_string());
+
+    if env.table_columns(&table_name).is_some() {
+        return Err(Diagnostic::error(&format!(
+            "There is already a table with name `{table_name}`"
+        ))
+        .add_help("Try to use a new unique name for the file table using `AS name`")
+        .with_location(location)
+        .as_boxed());
+    }
+
+    let source = FileSource { path, format };
+    let file_table = read_file_table(&table_name, &source)
+        .map_err(|error| Diagnostic::error(&error).with_location(location).as_boxed())?;
+
+    let columns = file_table
+        .definition
+        .columns
+        .into_iter()
+        .map(|column| (column.name, column.data_type))
+        .collect();
+
+    env.derived_tables.insert(table_name.to_string(), columns);
+    context.file_tables.insert(table_name.to_string(), source);
+    Ok(table_name)
+}
+
+fn file_table_format(function_name: &str) -> Option<FileFormat> {
+    match function_name.to_lowercase().as_str() {
+        "read_csv" => Some(FileFormat::Csv),
+        "read_json" => Some(FileFormat::Json),
+        _ => None,
+    }
+}
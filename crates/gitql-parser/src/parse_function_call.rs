@@ -2,7 +2,9 @@ use gitql_ast::expression::CallExpr;
 use gitql_ast::expression::Expr;
 use gitql_ast::expression::SymbolExpr;
 use gitql_ast::expression::SymbolFlag;
+use gitql_ast::statement::AggregateFunction;
 use gitql_ast::statement::AggregateValue;
+use gitql_ast::statement::OrderByStatement;
 use gitql_ast::statement::Statement;
 use gitql_ast::statement::WindowDefinition;
 use gitql_ast::statement::WindowFunction;
@@ -10,17 +12,23 @@ use gitql_ast::statement::WindowFunctionKind;
 use gitql_ast::statement::WindowOrderingClause;
 use gitql_ast::statement::WindowPartitioningClause;
 use gitql_ast::statement::WindowValue;
+use gitql_ast::types::integer::IntType;
 use gitql_core::environment::Environment;
 
 use crate::context::ParserContext;
 use crate::diagnostic::Diagnostic;
 use crate::parse_ordering::parse_order_by_statement;
+use crate::parse_window_frame::is_window_frame_unit;
+use crate::parse_window_frame::parse_window_frame;
+use crate::parser::calculate_safe_location;
 use crate::parser::consume_token_or_error;
+use crate::parser::expression_literal;
 use crate::parser::is_current_token;
 use crate::parser::is_current_token_with_condition;
 use crate::parser::parse_expression;
 use crate::parser::parse_member_access_expression;
 use crate::parser::parse_zero_or_more_values_with_comma_between;
+use crate::token::SourceLocation;
 use crate::token::Token;
 use crate::token::TokenKind;
 use crate::type_checker::check_function_call_arguments;
@@ -44,6 +52,17 @@ pub(crate) fn parse_function_call_expression(
         // Consume function name
         *position += 1;
 
+        // Check if this function is `GROUPING` that is resolved from the grouping set of each group
+        if function_name == "grouping" {
+            return parse_grouping_function_call(
+                context,
+                env,
+                tokens,
+                position,
+                function_name_location,
+            );
+        }
+
         // Check if this function is a Standard library functions
         if env.is_std_function(function_name) {
             let mut arguments = parse_zero_or_more_values_with_comma_between(
@@ -86,16 +105,90 @@ pub(crate) fn parse_function_call_expression(
             .as_boxed());
         }
 
-        // Check if this function is an Aggregation functions
-        if env.is_aggregation_function(function_name) {
+        // Check if this function is an user defined function
+        if env.is_user_function(function_name) {
             let mut arguments = parse_zero_or_more_values_with_comma_between(
                 context,
                 env,
                 tokens,
                 position,
-                "Aggregation function",
+                "User function",
             )?;
 
+            if let Some(signature) = env.user_function_signature(function_name) {
+                check_function_call_arguments(
+                    &mut arguments,
+                    &signature.parameters,
+                    function_name.to_string(),
+                    function_name_location,
+                )?;
+
+                return Ok(Box::new(CallExpr {
+                    function_name: function_name.to_string(),
+                    arguments,
+                    return_type: signature.return_type.clone(),
+                }));
+            }
+        }
+
+        // Check if this function is an Aggregation functions
+        if env.is_aggregation_function(function_name) {
+            let distinct = is_distinct_aggregation_arguments(tokens, position);
+            let mut arguments = if distinct {
+                parse_distinct_aggregation_arguments(context, env, tokens, position)?
+            } else {
+                parse_zero_or_more_values_with_comma_between(
+                    context,
+                    env,
+                    tokens,
+                    position,
+                    "Aggregation function",
+                )?
+            };
+
+            // Ordered-set function ordering values are passed to the function as the last arguments
+            let is_ordered_set_function = env.is_ordered_set_aggregation_function(function_name);
+            if is_ordered_set_function && distinct {
+                return Err(Diagnostic::error(
+                    "`DISTINCT` can't be used with ordered-set aggregation function",
+                )
+                .with_location(function_name_location)
+                .as_boxed());
+            }
+
+            let within_group = if is_current_token(tokens, position, TokenKind::Within) {
+                if !is_ordered_set_function {
+                    return Err(Diagnostic::error(&format!(
+                        "Function `{function_name}` is not an ordered-set aggregation function and can't be used with `WITHIN GROUP`",
+                    ))
+                    .with_location(tokens[*position].location)
+                    .as_boxed());
+                }
+
+                let order_by = parse_within_group_ordering(context, env, tokens, position)?;
+                arguments.extend(order_by.arguments.iter().cloned());
+                Some(order_by)
+            } else if is_ordered_set_function {
+                return Err(Diagnostic::error(&format!(
+                    "Ordered-set aggregation function `{function_name}` must be called with `WITHIN GROUP (ORDER BY ...)`",
+                ))
+                .add_help(&format!(
+                    "For example `{function_name}(...) WITHIN GROUP (ORDER BY value)`"
+                ))
+                .with_location(function_name_location)
+                .as_boxed());
+            } else {
+                None
+            };
+
+            let filter = if is_aggregation_filter_clause(tokens, position) {
+                Some(parse_aggregation_filter_clause(
+                    context, env, tokens, position,
+                )?)
+            } else {
+                None
+            };
+
             if let Some(signature) = env.aggregation_signature(function_name.as_str()) {
                 // Perform type checking and implicit casting if needed for function arguments
                 check_function_call_arguments(
@@ -128,6 +221,22 @@ pub(crate) fn parse_function_call_expression(
                     .as_boxed());
                 }
 
+                if is_used_as_window_function && within_group.is_some() {
+                    return Err(Diagnostic::error(
+                        "Ordered-set aggregation function can't be used as window function",
+                    )
+                    .with_location(function_name_location)
+                    .as_boxed());
+                }
+
+                if is_used_as_window_function && (distinct || filter.is_some()) {
+                    return Err(Diagnostic::error(
+                        "`DISTINCT` and `FILTER` can't be used with aggregation function as window function",
+                    )
+                    .with_location(function_name_location)
+                    .as_boxed());
+                }
+
                 let mut flag = SymbolFlag::AggregationReference;
                 if is_used_as_window_function {
                     // Consume `OVER` token
@@ -149,7 +258,13 @@ pub(crate) fn parse_function_call_expression(
 
                     flag = SymbolFlag::WindowReference;
                 } else {
-                    let function = AggregateValue::Function(function_name.to_string(), arguments);
+                    let function = AggregateValue::Function(AggregateFunction {
+                        function_name: function_name.to_string(),
+                        arguments,
+                        within_group,
+                        distinct,
+                        filter,
+                    });
                     context.aggregations.insert(column_name.clone(), function);
                 }
 
@@ -288,6 +403,216 @@ pub(crate) fn parse_function_call_expression(
     parse_member_access_expression(context, env, tokens, position)
 }
 
+/// Parse `GROUPING(a, b)` call, it's arguments must be columns in the `GROUP BY` and it's value
+/// is calculated for each group so it's registered as aggregation value
+fn parse_grouping_function_call(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    location: SourceLocation,
+) -> Result<Box<dyn Expr>, Box<Diagnostic>> {
+    let arguments =
+        parse_zero_or_more_values_with_comma_between(context, env, tokens, position, "GROUPING")?;
+
+    if arguments.is_empty() {
+        return Err(
+            Diagnostic::error("Function `GROUPING` expects at least one argument")
+                .with_location(location)
+                .as_boxed(),
+        );
+    }
+
+    if arguments.iter().any(|argument| {
+        argument
+            .as_any()
+            .downcast_ref::<SymbolExpr>()
+            .is_none_or(|symbol| symbol.flag != SymbolFlag::None)
+    }) {
+        return Err(
+            Diagnostic::error("Arguments of function `GROUPING` must be columns")
+                .add_help("Use the columns that are used in `GROUP BY`")
+                .with_location(location)
+                .as_boxed(),
+        );
+    }
+
+    let column_name = context.name_generator.generate_column_name();
+    context.hidden_selections.push(column_name.to_string());
+    env.define(column_name.to_string(), Box::new(IntType));
+
+    context
+        .aggregations
+        .insert(column_name.clone(), AggregateValue::Grouping(arguments));
+
+    Ok(Box::new(SymbolExpr {
+        value: column_name,
+        expr_type: Box::new(IntType),
+        flag: SymbolFlag::AggregationReference,
+    }))
+}
+
+/// Return true if the aggregation function arguments start with `DISTINCT` modifier `(DISTINCT a, b)`
+fn is_distinct_aggregation_arguments(tokens: &[Token], position: &usize) -> bool {
+    *position + 1 < tokens.len()
+        && tokens[*position].kind == TokenKind::LeftParen
+        && tokens[*position + 1].kind == TokenKind::Distinct
+}
+
+/// Parse aggregation function arguments with `DISTINCT` modifier `(DISTINCT a, b)`
+fn parse_distinct_aggregation_arguments(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Vec<Box<dyn Expr>>, Box<Diagnostic>> {
+    // Consume `(` and `DISTINCT` keyword
+    *position += 2;
+
+    if is_current_token(tokens, position, TokenKind::RightParen) {
+        return Err(
+            Diagnostic::error("Expect one or more values after `DISTINCT` keyword")
+                .with_location(calculate_safe_location(tokens, *position))
+                .as_boxed(),
+        );
+    }
+
+    let mut arguments: Vec<Box<dyn Expr>> = vec![];
+    loop {
+        let argument = parse_expression(context, env, tokens, position)?;
+        if let Some(argument_literal) = expression_literal(&argument) {
+            context.hidden_selections.push(argument_literal);
+        }
+
+        arguments.push(argument);
+
+        if is_current_token(tokens, position, TokenKind::Comma) {
+            // Consume Comma `,`
+            *position += 1;
+            continue;
+        }
+        break;
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` at the end of aggregation function arguments",
+    )?;
+
+    Ok(arguments)
+}
+
+/// Return true if the current tokens are `FILTER (WHERE`, `FILTER` is not a reserved keyword
+fn is_aggregation_filter_clause(tokens: &[Token], position: &usize) -> bool {
+    *position + 2 < tokens.len()
+        && matches!(&tokens[*position].kind, TokenKind::Symbol(name) if name == "filter")
+        && tokens[*position + 1].kind == TokenKind::LeftParen
+        && tokens[*position + 2].kind == TokenKind::Where
+}
+
+/// Parse `FILTER (WHERE condition)` of aggregation function
+fn parse_aggregation_filter_clause(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Box<dyn Expr>, Box<Diagnostic>> {
+    // Consume `FILTER`, `(` and `WHERE` keywords
+    *position += 3;
+
+    let aggregations_count_before = context.aggregations.len();
+    let window_functions_count_before = context.window_functions.len();
+
+    // Columns of the condition are not selected by default so they must be in the hidden selections
+    let condition_location = calculate_safe_location(tokens, *position);
+    context.inside_aggregation_filter = true;
+    let condition = parse_expression(context, env, tokens, position);
+    context.inside_aggregation_filter = false;
+    let condition = condition?;
+
+    if context.aggregations.len() != aggregations_count_before
+        || context.window_functions.len() != window_functions_count_before
+    {
+        return Err(Diagnostic::error(
+            "Aggregation and window functions can't be used in `FILTER` condition",
+        )
+        .with_location(condition_location)
+        .as_boxed());
+    }
+
+    if !condition.expr_type().is_bool() {
+        return Err(Diagnostic::error(&format!(
+            "Expect `FILTER` condition to be type Boolean but got {}",
+            condition.expr_type().literal()
+        ))
+        .with_location(condition_location)
+        .as_boxed());
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` after `FILTER` condition",
+    )?;
+
+    Ok(condition)
+}
+
+/// Parse `WITHIN GROUP (ORDER BY value [ASC | DESC])` of ordered-set aggregation function
+fn parse_within_group_ordering(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<OrderByStatement, Box<Diagnostic>> {
+    // Consume `WITHIN` keyword
+    *position += 1;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::Group,
+        "Expect `GROUP` keyword after `WITHIN`",
+    )?;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::LeftParen,
+        "Expect `(` after `WITHIN GROUP`",
+    )?;
+
+    if !is_current_token(tokens, position, TokenKind::Order) {
+        return Err(Diagnostic::error("Expect `ORDER BY` inside `WITHIN GROUP`")
+            .with_location(calculate_safe_location(tokens, *position))
+            .as_boxed());
+    }
+
+    let order_by = match parse_order_by_statement(context, env, tokens, position)? {
+        Statement::OrderBy(order_by) => order_by,
+        _ => unreachable!(),
+    };
+
+    // Ordering values are evaluated as arguments so their columns must be selected
+    for argument in &order_by.arguments {
+        if let Some(argument_literal) = expression_literal(argument) {
+            context.hidden_selections.push(argument_literal);
+        }
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` after `WITHIN GROUP` ordering",
+    )?;
+
+    Ok(order_by)
+}
+
 pub(crate) fn parse_over_window_definition(
     context: &mut ParserContext,
     env: &mut Environment,
@@ -304,6 +629,7 @@ pub(crate) fn parse_over_window_definition(
             name: Some(over_clause_name),
             partitioning_clause: None,
             ordering_clause: None,
+            frame: None,
         });
     }
 
@@ -319,10 +645,21 @@ pub(crate) fn parse_over_window_definition(
         name: None,
         partitioning_clause: None,
         ordering_clause: None,
+        frame: None,
     };
 
     context.inside_over_clauses = true;
     while !is_current_token(tokens, position, TokenKind::RightParen) {
+        // Window frame must be the last clause in the window definition
+        if window_definition.frame.is_some() {
+            context.inside_over_clauses = false;
+            return Err(Diagnostic::error(
+                "Window frame must be the last clause in the window definition",
+            )
+            .with_location(tokens[*position].location)
+            .as_boxed());
+        }
+
         if tokens[*position].kind == TokenKind::Partition {
             // Check if `PARTITION BY` is used more than one time
             if window_definition.partitioning_clause.is_some() {
@@ -379,9 +716,16 @@ pub(crate) fn parse_over_window_definition(
             continue;
         }
 
+        if is_window_frame_unit(tokens, position) {
+            let ordering_clause = &window_definition.ordering_clause;
+            let frame = parse_window_frame(context, env, tokens, position, ordering_clause)?;
+            window_definition.frame = Some(Box::new(frame));
+            continue;
+        }
+
         context.inside_over_clauses = false;
         return Err(Diagnostic::error(
-            "`OVER` clause can only support `ORDER BY` or `PARTITION BY`  clauses",
+            "`OVER` clause can only support `PARTITION BY`, `ORDER BY` or window frame clauses",
         )
         .with_location(tokens[*position].location)
         .as_boxed());
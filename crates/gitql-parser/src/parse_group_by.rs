@@ -0,0 +1,312 @@
+use gitql_ast::expression::Expr;
+use gitql_ast::expression::SymbolExpr;
+use gitql_ast::statement::GroupByStatement;
+use gitql_ast::statement::Statement;
+use gitql_core::environment::Environment;
+
+use crate::context::ParserContext;
+use crate::diagnostic::Diagnostic;
+use crate::parser::calculate_safe_location;
+use crate::parser::consume_token_or_error;
+use crate::parser::is_current_token;
+use crate::parser::parse_expression;
+use crate::token::Token;
+use crate::token::TokenKind;
+
+/// Maximum number of values in `CUBE`, because it creates grouping set for each subset of them
+const CUBE_MAX_VALUES_COUNT: usize = 12;
+
+/// Parse `GROUP BY` statement with one or more elements separated by comma, each element can be an expression,
+/// `ROLLUP (a, b)`, `CUBE (a, b)` or `GROUPING SETS ((a, b), (a), ())`, and the grouping sets of the
+/// statement are the cross product of the grouping sets of each element
+pub(crate) fn parse_group_by_statement(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Statement, Box<Diagnostic>> {
+    // Consume `Group` keyword
+    *position += 1;
+
+    // Consume `By` keyword
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::By,
+        "Expect keyword `BY` after keyword `group`",
+    )?;
+
+    // Parse one or more element
+    let mut values: Vec<Box<dyn Expr>> = vec![];
+    let mut grouping_sets: Vec<Vec<usize>> = vec![vec![]];
+    let mut has_only_expressions = true;
+    while *position < tokens.len() {
+        let element_sets = if is_current_token(tokens, position, TokenKind::Rollup) {
+            has_only_expressions = false;
+            parse_rollup_element(context, env, tokens, position, &mut values)?
+        } else if is_current_token(tokens, position, TokenKind::Cube) {
+            has_only_expressions = false;
+            parse_cube_element(context, env, tokens, position, &mut values)?
+        } else if is_grouping_sets_keywords(tokens, position) {
+            has_only_expressions = false;
+            parse_grouping_sets_element(context, env, tokens, position, &mut values)?
+        } else {
+            let expression = parse_expression(context, env, tokens, position)?;
+            vec![vec![register_group_by_value(&mut values, expression)]]
+        };
+
+        grouping_sets = cross_product_grouping_sets(&grouping_sets, &element_sets);
+
+        if is_current_token(tokens, position, TokenKind::Comma) {
+            // Consume Comma `,`
+            *position += 1;
+            continue;
+        }
+        break;
+    }
+
+    if is_current_token(tokens, position, TokenKind::With) {
+        let with_location = tokens[*position].location;
+
+        // Consume Comma `WITH``
+        *position += 1;
+
+        // Consume `Rollup` keyword
+        consume_token_or_error(
+            tokens,
+            position,
+            TokenKind::Rollup,
+            "Expect keyword `ROLLUP` after keyword `with`",
+        )?;
+
+        if !has_only_expressions {
+            return Err(Diagnostic::error(
+                "`WITH ROLLUP` can't be used with `ROLLUP`, `CUBE` or `GROUPING SETS`",
+            )
+            .add_help("Try to use `ROLLUP (...)` instead of `WITH ROLLUP`")
+            .with_location(with_location)
+            .as_boxed());
+        }
+
+        grouping_sets = rollup_grouping_sets(&(0..values.len()).collect::<Vec<usize>>());
+    }
+
+    context.has_group_by_statement = true;
+    Ok(Statement::GroupBy(GroupByStatement {
+        values,
+        grouping_sets,
+    }))
+}
+
+/// Parse `ROLLUP (a, b, c)` that has grouping sets `(a, b, c), (a, b), (a), ()`
+fn parse_rollup_element(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    values: &mut Vec<Box<dyn Expr>>,
+) -> Result<Vec<Vec<usize>>, Box<Diagnostic>> {
+    // Consume `ROLLUP` keyword
+    *position += 1;
+
+    let indices = parse_grouping_values_list(context, env, tokens, position, values, "ROLLUP")?;
+    Ok(rollup_grouping_sets(&indices))
+}
+
+/// Parse `CUBE (a, b)` that has grouping sets for each subset of the values `(a, b), (a), (b), ()`
+fn parse_cube_element(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    values: &mut Vec<Box<dyn Expr>>,
+) -> Result<Vec<Vec<usize>>, Box<Diagnostic>> {
+    let location = tokens[*position].location;
+
+    // Consume `CUBE` keyword
+    *position += 1;
+
+    let indices = parse_grouping_values_list(context, env, tokens, position, values, "CUBE")?;
+    if indices.len() > CUBE_MAX_VALUES_COUNT {
+        return Err(Diagnostic::error(&format!(
+            "`CUBE` can have at most {CUBE_MAX_VALUES_COUNT} values but got {}",
+            indices.len()
+        ))
+        .add_help("Try to use `GROUPING SETS` with only the needed sets")
+        .with_location(location)
+        .as_boxed());
+    }
+
+    // Order the subsets from the set with all values to the empty set
+    let values_count = indices.len();
+    let mut grouping_sets: Vec<Vec<usize>> = Vec::with_capacity(1 << values_count);
+    for mask in (0..(1usize << values_count)).rev() {
+        let grouping_set = indices
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| mask & (1 << (values_count - 1 - bit)) != 0)
+            .map(|(_, index)| *index)
+            .collect();
+        grouping_sets.push(grouping_set);
+    }
+
+    Ok(grouping_sets)
+}
+
+/// Parse `GROUPING SETS ((a, b), a, ())` where each set can be a list of values between parenthesis or one value
+fn parse_grouping_sets_element(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    values: &mut Vec<Box<dyn Expr>>,
+) -> Result<Vec<Vec<usize>>, Box<Diagnostic>> {
+    // Consume `GROUPING` and `SETS` keywords
+    *position += 2;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::LeftParen,
+        "Expect `(` after `GROUPING SETS`",
+    )?;
+
+    let mut grouping_sets: Vec<Vec<usize>> = vec![];
+    loop {
+        if is_current_token(tokens, position, TokenKind::LeftParen) {
+            // Parse empty set `()` or list of values `(a, b)`
+            if *position + 1 < tokens.len() && tokens[*position + 1].kind == TokenKind::RightParen {
+                *position += 2;
+                grouping_sets.push(vec![]);
+            } else {
+                let indices = parse_grouping_values_list(
+                    context,
+                    env,
+                    tokens,
+                    position,
+                    values,
+                    "GROUPING SETS",
+                )?;
+                grouping_sets.push(indices);
+            }
+        } else {
+            let expression = parse_expression(context, env, tokens, position)?;
+            grouping_sets.push(vec![register_group_by_value(values, expression)]);
+        }
+
+        if is_current_token(tokens, position, TokenKind::Comma) {
+            // Consume Comma `,`
+            *position += 1;
+            continue;
+        }
+        break;
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` after the grouping sets",
+    )?;
+
+    Ok(grouping_sets)
+}
+
+/// Parse one or more values between parenthesis `(a, b)` and return their indices in the group by values
+fn parse_grouping_values_list(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    values: &mut Vec<Box<dyn Expr>>,
+    element_name: &str,
+) -> Result<Vec<usize>, Box<Diagnostic>> {
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::LeftParen,
+        &format!("Expect `(` after `{element_name}`"),
+    )?;
+
+    if is_current_token(tokens, position, TokenKind::RightParen) {
+        return Err(Diagnostic::error(&format!(
+            "Expect one or more values between `(` and `)` in `{element_name}`"
+        ))
+        .with_location(calculate_safe_location(tokens, *position))
+        .as_boxed());
+    }
+
+    let mut indices: Vec<usize> = vec![];
+    loop {
+        let expression = parse_expression(context, env, tokens, position)?;
+        indices.push(register_group_by_value(values, expression));
+
+        if is_current_token(tokens, position, TokenKind::Comma) {
+            // Consume Comma `,`
+            *position += 1;
+            continue;
+        }
+        break;
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        &format!("Expect `)` after `{element_name}` values"),
+    )?;
+
+    Ok(indices)
+}
+
+/// Return true if the current tokens are `GROUPING SETS`, they are not reserved keywords
+/// because `GROUPING` is also a function name
+fn is_grouping_sets_keywords(tokens: &[Token], position: &usize) -> bool {
+    *position + 1 < tokens.len()
+        && matches!(&tokens[*position].kind, TokenKind::Symbol(name) if name == "grouping")
+        && matches!(&tokens[*position + 1].kind, TokenKind::Symbol(name) if name == "sets")
+}
+
+/// Add the value to the group by values if it's not already exists and return it's index,
+/// columns are compared by name so the same column in many grouping sets has one index
+fn register_group_by_value(values: &mut Vec<Box<dyn Expr>>, expression: Box<dyn Expr>) -> usize {
+    if let Some(symbol) = expression.as_any().downcast_ref::<SymbolExpr>() {
+        let existing_index = values.iter().position(|value| {
+            value
+                .as_any()
+                .downcast_ref::<SymbolExpr>()
+                .is_some_and(|other| other.value == symbol.value)
+        });
+
+        if let Some(index) = existing_index {
+            return index;
+        }
+    }
+
+    values.push(expression);
+    values.len() - 1
+}
+
+/// Return the grouping sets of `ROLLUP` from the set with all values to the empty set
+fn rollup_grouping_sets(indices: &[usize]) -> Vec<Vec<usize>> {
+    (0..=indices.len())
+        .rev()
+        .map(|len| indices[..len].to_vec())
+        .collect()
+}
+
+fn cross_product_grouping_sets(left: &[Vec<usize>], right: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut grouping_sets = Vec::with_capacity(left.len() * right.len());
+    for left_set in left {
+        for right_set in right {
+            let mut grouping_set = left_set.clone();
+            for index in right_set {
+                if !grouping_set.contains(index) {
+                    grouping_set.push(*index);
+                }
+            }
+            grouping_sets.push(grouping_set);
+        }
+    }
+    grouping_sets
+}
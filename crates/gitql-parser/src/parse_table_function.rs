@@ -0,0 +1,109 @@
+use gitql_ast::statement::TableFunctionCall;
+use gitql_core::environment::Environment;
+
+use crate::context::ParserContext;
+use crate::diagnostic::Diagnostic;
+use crate::parser::calculate_safe_location;
+use crate::parser::is_next_token;
+use crate::parser::parse_optional_table_alias;
+use crate::parser::parse_zero_or_more_values_with_comma_between;
+use crate::token::Token;
+use crate::token::TokenKind;
+use crate::type_checker::check_function_call_arguments;
+use crate::type_checker::resolve_dynamic_data_type;
+
+/// Return true if the current tokens are table function call like `generate_series(`
+pub(crate) fn is_table_function_call(
+    env: &Environment,
+    tokens: &[Token],
+    position: &usize,
+) -> bool {
+    *position < tokens.len()
+        && matches!(tokens[*position].kind, TokenKind::Symbol(_))
+        && env.is_table_function(&tokens[*position].to_string())
+        && is_next_token(tokens, position, TokenKind::LeftParen)
+}
+
+/// Parse table function call `generate_series(1, 10) [AS name]`, resolve the columns types
+/// from the function signature and return the table name, by default the table name is the function name
+pub(crate) fn parse_table_function(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<String, Box<Diagnostic>> {
+    let function_name = tokens[*position].to_string();
+    let location = tokens[*position].location;
+
+    // Consume function name
+    *position += 1;
+
+    let projections_count = context.projection_names.len();
+    let mut arguments = parse_zero_or_more_values_with_comma_between(
+        context,
+        env,
+        tokens,
+        position,
+        "Table function",
+    )?;
+
+    // The arguments are evaluated once before selecting the rows so they can't reference columns
+    if context.projection_names.len() != projections_count {
+        return Err(Diagnostic::error(&format!(
+            "Arguments of table function `{function_name}` can't reference columns"
+        ))
+        .add_help("Try to use constant values or global variables as arguments")
+        .with_location(location)
+        .as_boxed());
+    }
+
+    let Some(signature) = env.table_function_signature(&function_name).cloned() else {
+        return Err(Diagnostic::error(&format!(
+            "Can't find signature for table function with name {function_name}",
+        ))
+        .with_location(location)
+        .as_boxed());
+    };
+
+    check_function_call_arguments(
+        &mut arguments,
+        &signature.parameters,
+        function_name.to_string(),
+        location,
+    )?;
+
+    // Table function with the same name of schema table like `commits('v1.0..v2.0')` scope that table rows,
+    // so it can be used without alias
+    let alias = parse_optional_table_alias(tokens, position)?;
+    let is_scoped_schema_table = alias.is_none() && env.schema.contains_table(&function_name);
+    let table_name = alias.unwrap_or(function_name.clone());
+    if !is_scoped_schema_table && env.table_columns(&table_name).is_some() {
+        return Err(Diagnostic::error(&format!(
+            "There is already a table with name `{table_name}`"
+        ))
+        .add_help("Try to use a new unique name for the table function using `AS name`")
+        .with_location(calculate_safe_location(tokens, *position - 1))
+        .as_boxed());
+    }
+
+    let columns = signature
+        .columns
+        .iter()
+        .map(|column| {
+            let column_type =
+                resolve_dynamic_data_type(&signature.parameters, &arguments, &column.data_type);
+            (column.name.to_string(), column_type)
+        })
+        .collect();
+
+    env.derived_tables.insert(table_name.to_string(), columns);
+    context.table_functions.insert(
+        table_name.to_string(),
+        TableFunctionCall {
+            function_name,
+            arguments,
+        },
+    );
+
+    Ok(table_name)
+}
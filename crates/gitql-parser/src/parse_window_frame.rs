@@ -0,0 +1,247 @@
+use gitql_ast::statement::WindowFrame;
+use gitql_ast::statement::WindowFrameBound;
+use gitql_ast::statement::WindowFrameUnit;
+use gitql_ast::statement::WindowOrderingClause;
+use gitql_core::environment::Environment;
+
+use crate::context::ParserContext;
+use crate::diagnostic::Diagnostic;
+use crate::parser::calculate_safe_location;
+use crate::parser::consume_token_or_error;
+use crate::parser::is_current_token;
+use crate::parser::parse_expression;
+use crate::token::SourceLocation;
+use crate::token::Token;
+use crate::token::TokenKind;
+
+/// Return true if the current token is the start of window frame `ROWS`, `RANGE` or `GROUPS`
+pub(crate) fn is_window_frame_unit(tokens: &[Token], position: &usize) -> bool {
+    *position < tokens.len()
+        && matches!(
+            tokens[*position].kind,
+            TokenKind::Rows | TokenKind::Range | TokenKind::Groups
+        )
+}
+
+/// Parse window frame `{ROWS | RANGE | GROUPS} BETWEEN start AND end` or `{ROWS | RANGE | GROUPS} start`,
+/// when the end bound is not defined it will be the `CURRENT ROW`
+pub(crate) fn parse_window_frame(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    ordering_clause: &Option<WindowOrderingClause>,
+) -> Result<WindowFrame, Box<Diagnostic>> {
+    let unit_location = tokens[*position].location;
+    let unit = match tokens[*position].kind {
+        TokenKind::Rows => WindowFrameUnit::Rows,
+        TokenKind::Range => WindowFrameUnit::Range,
+        _ => WindowFrameUnit::Groups,
+    };
+
+    // Consume `ROWS`, `RANGE` or `GROUPS` keyword
+    *position += 1;
+
+    if unit != WindowFrameUnit::Rows && ordering_clause.is_none() {
+        return Err(Diagnostic::error(&format!(
+            "Window frame `{}` require `ORDER BY` in the window definition",
+            tokens[*position - 1]
+        ))
+        .add_help("Try to add `ORDER BY` before the window frame")
+        .with_location(unit_location)
+        .as_boxed());
+    }
+
+    let start_location = calculate_safe_location(tokens, *position);
+    let (start, end, end_location) = if is_current_token(tokens, position, TokenKind::Between) {
+        // Consume `BETWEEN` keyword
+        *position += 1;
+
+        let start = parse_window_frame_bound(context, env, tokens, position)?;
+        consume_token_or_error(
+            tokens,
+            position,
+            TokenKind::AndKeyword,
+            "Expect `AND` keyword between the window frame start and end",
+        )?;
+
+        let end_location = calculate_safe_location(tokens, *position);
+        let end = parse_window_frame_bound(context, env, tokens, position)?;
+        (start, end, end_location)
+    } else {
+        let start = parse_window_frame_bound(context, env, tokens, position)?;
+        (start, WindowFrameBound::CurrentRow, start_location)
+    };
+
+    if matches!(start, WindowFrameBound::UnboundedFollowing) {
+        return Err(
+            Diagnostic::error("Window frame start can't be `UNBOUNDED FOLLOWING`")
+                .with_location(start_location)
+                .as_boxed(),
+        );
+    }
+
+    if matches!(end, WindowFrameBound::UnboundedPreceding) {
+        return Err(
+            Diagnostic::error("Window frame end can't be `UNBOUNDED PRECEDING`")
+                .with_location(end_location)
+                .as_boxed(),
+        );
+    }
+
+    if window_frame_bound_order(&end) < window_frame_bound_order(&start) {
+        return Err(
+            Diagnostic::error("Window frame end can't be before the window frame start")
+                .add_help("For example use `BETWEEN 2 PRECEDING AND CURRENT ROW` or `BETWEEN CURRENT ROW AND 2 FOLLOWING`")
+                .with_location(end_location)
+                .as_boxed(),
+        );
+    }
+
+    type_check_window_frame_offset(&unit, &start, ordering_clause, start_location)?;
+    type_check_window_frame_offset(&unit, &end, ordering_clause, end_location)?;
+
+    Ok(WindowFrame { unit, start, end })
+}
+
+fn parse_window_frame_bound(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<WindowFrameBound, Box<Diagnostic>> {
+    // Parse `UNBOUNDED PRECEDING` or `UNBOUNDED FOLLOWING`
+    if is_current_token(tokens, position, TokenKind::Unbounded) {
+        // Consume `UNBOUNDED` keyword
+        *position += 1;
+
+        if is_current_token(tokens, position, TokenKind::Preceding) {
+            *position += 1;
+            return Ok(WindowFrameBound::UnboundedPreceding);
+        }
+
+        consume_token_or_error(
+            tokens,
+            position,
+            TokenKind::Following,
+            "Expect `PRECEDING` or `FOLLOWING` after `UNBOUNDED` keyword",
+        )?;
+        return Ok(WindowFrameBound::UnboundedFollowing);
+    }
+
+    // Parse `CURRENT ROW`
+    if is_current_token(tokens, position, TokenKind::Current) {
+        // Consume `CURRENT` keyword
+        *position += 1;
+
+        consume_token_or_error(
+            tokens,
+            position,
+            TokenKind::Row,
+            "Expect `ROW` keyword after `CURRENT` keyword",
+        )?;
+        return Ok(WindowFrameBound::CurrentRow);
+    }
+
+    // Parse `offset PRECEDING` or `offset FOLLOWING`
+    let location = calculate_safe_location(tokens, *position);
+    let projections_count = context.projection_names.len();
+    let offset = parse_expression(context, env, tokens, position)?;
+
+    // The offset is evaluated once for all rows so it can't reference columns
+    if context.projection_names.len() != projections_count {
+        return Err(
+            Diagnostic::error("Window frame offset can't reference columns")
+                .add_help("Try to use constant values or global variables as offset")
+                .with_location(location)
+                .as_boxed(),
+        );
+    }
+
+    if is_current_token(tokens, position, TokenKind::Preceding) {
+        *position += 1;
+        return Ok(WindowFrameBound::Preceding(offset));
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::Following,
+        "Expect `PRECEDING` or `FOLLOWING` after the window frame offset",
+    )?;
+    Ok(WindowFrameBound::Following(offset))
+}
+
+/// Return the order of the bound relative to the current row, used to check that the end is not before the start
+fn window_frame_bound_order(bound: &WindowFrameBound) -> u8 {
+    match bound {
+        WindowFrameBound::UnboundedPreceding => 0,
+        WindowFrameBound::Preceding(_) => 1,
+        WindowFrameBound::CurrentRow => 2,
+        WindowFrameBound::Following(_) => 3,
+        WindowFrameBound::UnboundedFollowing => 4,
+    }
+}
+
+fn type_check_window_frame_offset(
+    unit: &WindowFrameUnit,
+    bound: &WindowFrameBound,
+    ordering_clause: &Option<WindowOrderingClause>,
+    location: SourceLocation,
+) -> Result<(), Box<Diagnostic>> {
+    let offset = match bound {
+        WindowFrameBound::Preceding(offset) | WindowFrameBound::Following(offset) => offset,
+        _ => return Ok(()),
+    };
+
+    let offset_type = offset.expr_type();
+    if *unit != WindowFrameUnit::Range {
+        if offset_type.is_int() {
+            return Ok(());
+        }
+
+        return Err(Diagnostic::error(&format!(
+            "Window frame offset in `ROWS` or `GROUPS` must be `Int` but got `{}`",
+            offset_type.literal()
+        ))
+        .with_location(location)
+        .as_boxed());
+    }
+
+    // Range offset is distance from the ordering value, so it must be only one ordering value
+    let order_by = &ordering_clause.as_ref().unwrap().order_by;
+    if order_by.arguments.len() != 1 {
+        return Err(Diagnostic::error(
+            "Window frame `RANGE` with offset require exactly one `ORDER BY` value",
+        )
+        .with_location(location)
+        .as_boxed());
+    }
+
+    let ordering_type = order_by.arguments[0].expr_type();
+    let is_valid_offset = if ordering_type.is_number() {
+        offset_type.is_number()
+    } else if ordering_type.is_date() || ordering_type.is_date_time() {
+        offset_type.is_interval()
+    } else {
+        return Err(Diagnostic::error(&format!(
+            "Window frame `RANGE` with offset require ordering by number, `Date` or `DateTime` but got `{}`",
+            ordering_type.literal()
+        ))
+        .with_location(location)
+        .as_boxed());
+    };
+
+    if !is_valid_offset {
+        return Err(Diagnostic::error(&format!(
+            "Window frame `RANGE` offset type `{}` can't be used with ordering type `{}`",
+            offset_type.literal(),
+            ordering_type.literal()
+        ))
+        .add_help("Use number offset with number ordering, and `Interval` offset with `Date` or `DateTime` ordering")
+        .with_location(location)
+        .as_boxed());
+    }
+
+    Ok(())
+}
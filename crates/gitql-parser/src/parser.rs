@@ -12,11 +12,18 @@ use gitql_ast::operator::ArithmeticOperator;
 use gitql_ast::operator::BinaryBitwiseOperator;
 use gitql_ast::operator::BinaryLogicalOperator;
 use gitql_ast::operator::PrefixUnaryOperator;
+use gitql_ast::query::CommonTableExpression;
+use gitql_ast::query::CreateFunctionQuery;
+use gitql_ast::query::CreateViewQuery;
 use gitql_ast::query::DescribeQuery;
 use gitql_ast::query::DoQuery;
+use gitql_ast::query::DropViewQuery;
+use gitql_ast::query::ExplainQuery;
 use gitql_ast::query::GlobalVariableDeclQuery;
 use gitql_ast::query::Query;
 use gitql_ast::query::SelectQuery;
+use gitql_ast::query::SetOperationQuery;
+use gitql_ast::query::SetOperator;
 use gitql_ast::statement::*;
 use gitql_ast::types::any::AnyType;
 use gitql_ast::types::array::ArrayType;
@@ -26,19 +33,27 @@ use gitql_ast::types::row::RowType;
 use gitql_ast::types::undefined::UndefType;
 use gitql_ast::types::DataType;
 use gitql_core::environment::Environment;
+use gitql_core::environment::View;
+use gitql_core::signature::Signature;
 
 use crate::context::ParserContext;
 use crate::diagnostic::Diagnostic;
 use crate::name_similarity::find_closeest_string;
 use crate::parse_cast::parse_cast_call_expression;
 use crate::parse_comparisons::parse_comparison_expression;
+use crate::parse_file_table::is_file_table_call;
+use crate::parse_file_table::parse_file_table;
 use crate::parse_function_call::parse_function_call_expression;
 use crate::parse_function_call::parse_over_window_definition;
+use crate::parse_group_by::parse_group_by_statement;
 use crate::parse_interval::parse_interval_expression;
 use crate::parse_into::parse_into_statement;
 use crate::parse_ordering::parse_order_by_statement;
 use crate::parse_string_matchers::parse_like_expression;
 use crate::parse_string_matchers::parse_regex_expression;
+use crate::parse_table_function::is_table_function_call;
+use crate::parse_table_function::parse_table_function;
+use crate::parse_type::parse_type;
 use crate::token::SourceLocation;
 use crate::token::Token;
 use crate::token::TokenKind;
@@ -56,9 +71,13 @@ pub fn parse_gql(tokens: Vec<Token>, env: &mut Environment) -> Result<Vec<Query>
         let query = match &tokens[position].kind {
             TokenKind::Do => parse_do_query(env, &tokens, &mut position),
             TokenKind::Set => parse_set_query(env, &tokens, &mut position),
-            TokenKind::Select => parse_select_query(env, &tokens, &mut position),
+            TokenKind::Select => parse_set_operation_query(env, &tokens, &mut position),
+            TokenKind::With => parse_with_query(env, &tokens, &mut position),
             TokenKind::Describe => parse_describe_query(env, &tokens, &mut position),
             TokenKind::Show => parse_show_query(&tokens, &mut position),
+            TokenKind::Explain => parse_explain_query(env, &tokens, &mut position),
+            TokenKind::Create => parse_create_query(env, &tokens, &mut position),
+            TokenKind::Drop => parse_drop_query(env, &tokens, &mut position),
             _ => Err(un_expected_query_start_error(&tokens, &mut position)),
         }?;
 
@@ -115,6 +134,15 @@ fn parse_do_query(
         *position += 1;
     }
 
+    // Subqueries are evaluated only as part of the select query that use them
+    if !context.subqueries.is_empty() {
+        return Err(
+            Diagnostic::error("Subqueries can't be used in `DO` statement")
+                .with_location(calculate_safe_location(tokens, *position - 1))
+                .as_boxed(),
+        );
+    }
+
     Ok(Query::Do(DoQuery { exprs }))
 }
 
@@ -168,6 +196,15 @@ fn parse_set_query(
         );
     }
 
+    // Subqueries are evaluated only as part of the select query that use them
+    if !context.subqueries.is_empty() {
+        return Err(
+            Diagnostic::error("Subquery value can't be assigned to global variable")
+                .with_location(calculate_safe_location(tokens, *position - 1))
+                .as_boxed(),
+        );
+    }
+
     env.define_global(name.to_string(), value.expr_type());
 
     Ok(Query::GlobalVariableDecl(GlobalVariableDeclQuery {
@@ -194,18 +231,15 @@ fn parse_describe_query(
 
     // Make sure table name is valid
     let table_name = tokens[*position].to_string();
-    if !env
-        .schema
-        .tables_fields_names
-        .contains_key(table_name.as_str())
-    {
+    if !env.schema.contains_table(&table_name) && !env.is_view(&table_name) {
         let mut diagnostic =
             Diagnostic::error(&format!("Cannot find table with name `{table_name}`"))
                 .add_note("You can use the `SHOW TABLES` query to get list of current tables")
                 .add_note("Check the documentations to see available tables")
                 .with_location(calculate_safe_location(tokens, *position));
 
-        let canditates: Vec<&&str> = env.schema.tables_fields_names.keys().collect();
+        let tables_names = env.schema.tables_names();
+        let canditates: Vec<&&str> = tables_names.iter().collect();
         if let Some(closest_valid_name) = find_closeest_string(&table_name, &canditates) {
             let message = &format!("a table with a similar name exists: `{closest_valid_name}`");
             diagnostic = diagnostic.add_help(message);
@@ -233,14 +267,777 @@ fn parse_show_query(tokens: &[Token], position: &mut usize) -> Result<Query, Box
         );
     }
 
-    *position += 1;
-    Ok(Query::ShowTables)
+    *position += 1;
+    Ok(Query::ShowTables)
+}
+
+fn parse_explain_query(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Query, Box<Diagnostic>> {
+    // Consume `EXPLAIN` keyword
+    *position += 1;
+
+    // Consume optional `ANALYZE` keyword
+    let is_analyze = is_current_token(tokens, position, TokenKind::Analyze);
+    if is_analyze {
+        *position += 1;
+    }
+
+    let query_location = calculate_safe_location(tokens, *position);
+    let query = match tokens.get(*position).map(|token| &token.kind) {
+        Some(TokenKind::Select) => parse_set_operation_query(env, tokens, position)?,
+        Some(TokenKind::With) => parse_with_query(env, tokens, position)?,
+        _ => {
+            return Err(Diagnostic::error("Expect `SELECT` query after `EXPLAIN`")
+                .with_location(query_location)
+                .as_boxed());
+        }
+    };
+
+    let Query::Select(query) = query else {
+        return Err(
+            Diagnostic::error("`EXPLAIN` doesn't support queries with set operations")
+                .add_help("Try to explain each select query of the set operation")
+                .with_location(query_location)
+                .as_boxed(),
+        );
+    };
+
+    Ok(Query::Explain(ExplainQuery { query, is_analyze }))
+}
+
+fn parse_create_query(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Query, Box<Diagnostic>> {
+    // Consume `CREATE` keyword
+    *position += 1;
+
+    match tokens.get(*position).map(|token| &token.kind) {
+        Some(TokenKind::Function) => parse_create_function_query(env, tokens, position),
+        Some(TokenKind::View) => parse_create_view_query(env, tokens, position),
+        _ => Err(
+            Diagnostic::error("Expect `FUNCTION` or `VIEW` keyword after `CREATE` keyword")
+                .with_location(calculate_safe_location(tokens, *position))
+                .as_boxed(),
+        ),
+    }
+}
+
+fn parse_create_function_query(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Query, Box<Diagnostic>> {
+    // Consume `FUNCTION` keyword
+    *position += 1;
+
+    let name_token = consume_conditional_token_or_errors(
+        tokens,
+        position,
+        |token| matches!(token.kind, TokenKind::Symbol(_)),
+        "Expect function name after `CREATE FUNCTION` keywords",
+    )?;
+
+    let name = name_token.to_string();
+    let name_location = name_token.location;
+    if env.is_function(&name) {
+        return Err(
+            Diagnostic::error(&format!("Function with name `{name}` already exists"))
+                .add_help("Try to use a different name for the function")
+                .with_location(name_location)
+                .as_boxed(),
+        );
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::LeftParen,
+        "Expect `(` after function name",
+    )?;
+
+    let mut parameters_names: Vec<String> = vec![];
+    let mut parameters_types: Vec<Box<dyn DataType>> = vec![];
+    while !is_current_token(tokens, position, TokenKind::RightParen) {
+        let parameter_token = consume_conditional_token_or_errors(
+            tokens,
+            position,
+            |token| matches!(token.kind, TokenKind::Symbol(_)),
+            "Expect parameter name followed by it's type",
+        )?;
+
+        let parameter_name = parameter_token.to_string();
+        if parameters_names.contains(&parameter_name) {
+            return Err(Diagnostic::error(&format!(
+                "Function parameter `{parameter_name}` is defined more than once",
+            ))
+            .with_location(parameter_token.location)
+            .as_boxed());
+        }
+
+        let parameter_type = parse_type(env, tokens, position)?;
+        parameters_names.push(parameter_name);
+        parameters_types.push(parameter_type);
+
+        if !is_current_token(tokens, position, TokenKind::Comma) {
+            break;
+        }
+
+        // Consume `,`
+        *position += 1;
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` after function parameters",
+    )?;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::Returns,
+        "Expect `RETURNS` and type after function parameters",
+    )?;
+
+    let return_type = parse_type(env, tokens, position)?;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::As,
+        "Expect `AS` and expression after function return type",
+    )?;
+
+    // Parameters are defined in the current scope so the body can reference them as symbols
+    for (parameter_name, parameter_type) in parameters_names.iter().zip(parameters_types.iter()) {
+        env.define(parameter_name.to_string(), parameter_type.clone());
+    }
+
+    let body_location = calculate_safe_location(tokens, *position);
+    let mut context = ParserContext::default();
+    let mut body = parse_expression(&mut context, env, tokens, position)?;
+
+    if !context.aggregations.is_empty() || !context.window_functions.is_empty() {
+        return Err(Diagnostic::error(
+            "Aggregation and window functions can't be used in function body",
+        )
+        .with_location(body_location)
+        .as_boxed());
+    }
+
+    if !context.subqueries.is_empty() {
+        return Err(
+            Diagnostic::error("Subqueries can't be used in function body")
+                .with_location(body_location)
+                .as_boxed(),
+        );
+    }
+
+    // Function body can only reference it's parameters
+    for (symbol, location) in context
+        .projection_names
+        .iter()
+        .zip(context.projection_locations.iter())
+    {
+        if !parameters_names.contains(symbol) {
+            return Err(Diagnostic::error(&format!(
+                "Unresolved parameter name `{symbol}` in function body",
+            ))
+            .add_help("Function body can only reference it's parameters and global variables")
+            .with_location(*location)
+            .as_boxed());
+        }
+    }
+
+    let body_type = body.expr_type();
+    if !return_type.equals(&body_type) {
+        if !return_type.has_implicit_cast_from(&body) {
+            return Err(Diagnostic::error(&format!(
+                "Function body type `{}` don't match return type `{}`",
+                body_type.literal(),
+                return_type.literal()
+            ))
+            .with_location(body_location)
+            .as_boxed());
+        }
+
+        body = Box::new(CastExpr {
+            value: body,
+            result_type: return_type.clone(),
+        });
+    }
+
+    env.define_user_function(
+        name.to_string(),
+        Signature::new(parameters_types, return_type),
+    );
+
+    Ok(Query::CreateFunction(CreateFunctionQuery {
+        name,
+        parameters_names,
+        body,
+    }))
+}
+
+fn parse_create_view_query(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Query, Box<Diagnostic>> {
+    // Consume `VIEW` keyword
+    *position += 1;
+
+    let name_token = consume_conditional_token_or_errors(
+        tokens,
+        position,
+        |token| matches!(token.kind, TokenKind::Symbol(_)),
+        "Expect view name after `CREATE VIEW` keywords",
+    )?;
+
+    let name = name_token.to_string();
+    if env.table_columns(&name).is_some() {
+        return Err(
+            Diagnostic::error(&format!("There is already a table with name `{name}`"))
+                .add_help("Try to use a new unique name for the view")
+                .with_location(name_token.location)
+                .as_boxed(),
+        );
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::As,
+        "Expect `AS` keyword after view name",
+    )?;
+
+    if !is_current_token(tokens, position, TokenKind::Select) {
+        return Err(Diagnostic::error("Expect `SELECT` query as view query")
+            .with_location(calculate_safe_location(tokens, *position))
+            .as_boxed());
+    }
+
+    let (query, columns_names, columns_types) = parse_subquery(env, tokens, position)?;
+    let columns = columns_names.into_iter().zip(columns_types).collect();
+    env.define_view(name.to_string(), View { query, columns });
+
+    Ok(Query::CreateView(CreateViewQuery { name }))
+}
+
+fn parse_drop_query(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Query, Box<Diagnostic>> {
+    // Consume `DROP` keyword
+    *position += 1;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::View,
+        "Expect `VIEW` keyword after `DROP` keyword",
+    )?;
+
+    let name_token = consume_conditional_token_or_errors(
+        tokens,
+        position,
+        |token| matches!(token.kind, TokenKind::Symbol(_)),
+        "Expect view name after `DROP VIEW` keywords",
+    )?;
+
+    let name = name_token.to_string();
+    if env.views.remove(&name).is_none() {
+        return Err(
+            Diagnostic::error(&format!("Cannot find view with name `{name}`"))
+                .with_location(name_token.location)
+                .as_boxed(),
+        );
+    }
+
+    Ok(Query::DropView(DropViewQuery { name }))
+}
+
+fn parse_with_query(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Query, Box<Diagnostic>> {
+    // Consume `WITH` keyword
+    *position += 1;
+
+    // Consume optional `RECURSIVE` keyword
+    let is_recursive = is_current_token(tokens, position, TokenKind::Recursive);
+    if is_recursive {
+        *position += 1;
+    }
+
+    let mut common_tables = vec![];
+    loop {
+        let common_table = parse_common_table_expression(env, tokens, position, is_recursive)?;
+        common_tables.push(common_table);
+
+        if !is_current_token(tokens, position, TokenKind::Comma) {
+            break;
+        }
+
+        // Consume `,`
+        *position += 1;
+    }
+
+    if !is_current_token(tokens, position, TokenKind::Select) {
+        return Err(
+            Diagnostic::error("Expect `SELECT` query after common table expressions")
+                .with_location(calculate_safe_location(tokens, *position))
+                .as_boxed(),
+        );
+    }
+
+    let mut query = parse_set_operation_query(env, tokens, position)?;
+
+    // Common tables are evaluated before the first select query and visible to the others
+    let mut first_query = &mut query;
+    while let Query::SetOperation(set_operation) = first_query {
+        first_query = &mut set_operation.left;
+    }
+
+    if let Query::Select(select_query) = first_query {
+        select_query.common_tables = common_tables;
+    }
+
+    Ok(query)
+}
+
+fn parse_common_table_expression(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    is_recursive: bool,
+) -> Result<CommonTableExpression, Box<Diagnostic>> {
+    let location = calculate_safe_location(tokens, *position);
+    let name = consume_conditional_token_or_errors(
+        tokens,
+        position,
+        |token| matches!(token.kind, TokenKind::Symbol(_)),
+        "Expect common table name after `WITH` keyword or `,`",
+    )?
+    .to_string();
+
+    if env.table_columns(&name).is_some() {
+        return Err(
+            Diagnostic::error(&format!("There is already a table with name `{name}`"))
+                .add_help("Try to use a new unique name for the common table")
+                .with_location(location)
+                .as_boxed(),
+        );
+    }
+
+    // Parse optional list of columns names
+    let mut explicit_columns_names: Option<Vec<String>> = None;
+    if is_current_token(tokens, position, TokenKind::LeftParen) {
+        // Consume `(`
+        *position += 1;
+
+        let mut names = vec![];
+        loop {
+            let column_name = consume_conditional_token_or_errors(
+                tokens,
+                position,
+                |token| matches!(token.kind, TokenKind::Symbol(_)),
+                "Expect column name in the common table columns list",
+            )?;
+            names.push(column_name.to_string());
+
+            if !is_current_token(tokens, position, TokenKind::Comma) {
+                break;
+            }
+
+            // Consume `,`
+            *position += 1;
+        }
+
+        consume_token_or_error(
+            tokens,
+            position,
+            TokenKind::RightParen,
+            "Expect `)` at the end of common table columns list",
+        )?;
+
+        explicit_columns_names = Some(names);
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::As,
+        "Expect `AS` keyword after common table name",
+    )?;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::LeftParen,
+        "Expect `(` before the common table query",
+    )?;
+
+    if !is_current_token(tokens, position, TokenKind::Select) {
+        return Err(
+            Diagnostic::error("Expect `SELECT` query as common table query")
+                .with_location(calculate_safe_location(tokens, *position))
+                .as_boxed(),
+        );
+    }
+
+    let (query, mut columns_names, columns_types) = parse_subquery(env, tokens, position)?;
+
+    if let Some(names) = explicit_columns_names {
+        if names.len() != columns_names.len() {
+            return Err(Diagnostic::error(&format!(
+                "Common table `{name}` has {} columns names but it's query select {} columns",
+                names.len(),
+                columns_names.len()
+            ))
+            .with_location(location)
+            .as_boxed());
+        }
+        columns_names = names;
+    }
+
+    let columns: Vec<(String, Box<dyn DataType>)> =
+        columns_names.iter().cloned().zip(columns_types).collect();
+
+    // Parse the optional recursive part `UNION [ALL] SELECT ...`
+    let mut recursive_query: Option<SelectQuery> = None;
+    let mut is_union_all = false;
+    if is_current_token(tokens, position, TokenKind::Union) {
+        if !is_recursive {
+            return Err(Diagnostic::error(
+                "`UNION` inside common table query can be used only with `WITH RECURSIVE`",
+            )
+            .with_location(calculate_safe_location(tokens, *position))
+            .as_boxed());
+        }
+
+        // Consume `UNION` keyword
+        *position += 1;
+
+        // Consume optional `ALL` keyword
+        is_union_all = is_current_token(tokens, position, TokenKind::All);
+        if is_union_all {
+            *position += 1;
+        }
+
+        if !is_current_token(tokens, position, TokenKind::Select) {
+            return Err(Diagnostic::error("Expect `SELECT` query after `UNION`")
+                .with_location(calculate_safe_location(tokens, *position))
+                .as_boxed());
+        }
+
+        // The recursive query can reference the common table by it's name
+        let recursive_location = calculate_safe_location(tokens, *position);
+        env.derived_tables.insert(name.to_string(), columns.clone());
+        let (query, _, recursive_types) = parse_subquery(env, tokens, position)?;
+        env.derived_tables.remove(&name);
+
+        if recursive_types.len() != columns.len() {
+            return Err(Diagnostic::error(&format!(
+                "Recursive query of `{name}` must select {} columns but got {}",
+                columns.len(),
+                recursive_types.len()
+            ))
+            .with_location(recursive_location)
+            .as_boxed());
+        }
+
+        for ((column_name, column_type), recursive_type) in columns.iter().zip(&recursive_types) {
+            if !column_type.equals(recursive_type) {
+                return Err(Diagnostic::error(&format!(
+                    "Column `{column_name}` has type `{}` but the recursive query select `{}`",
+                    column_type.literal(),
+                    recursive_type.literal()
+                ))
+                .with_location(recursive_location)
+                .as_boxed());
+            }
+        }
+
+        recursive_query = Some(query);
+    }
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` at the end of common table query",
+    )?;
+
+    env.derived_tables.insert(name.to_string(), columns);
+
+    Ok(CommonTableExpression {
+        name,
+        columns_names,
+        query,
+        recursive_query,
+        is_union_all,
+    })
+}
+
+/// Parse select query or set operations between select queries,
+/// `INTERSECT` has higher precedence than `UNION` and `EXCEPT`
+fn parse_set_operation_query(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Query, Box<Diagnostic>> {
+    let (mut query, columns_names, columns_types) =
+        parse_intersect_operation(env, tokens, position, false)?;
+    while is_current_token(tokens, position, TokenKind::Union)
+        || is_current_token(tokens, position, TokenKind::Except)
+    {
+        let location = calculate_safe_location(tokens, *position);
+        let operator = if tokens[*position].kind == TokenKind::Union {
+            SetOperator::Union
+        } else {
+            SetOperator::Except
+        };
+
+        // Consume `UNION` or `EXCEPT` keyword
+        *position += 1;
+
+        let is_all = parse_set_operation_all_option(tokens, position);
+        let (right, _, right_types) = parse_intersect_operation(env, tokens, position, true)?;
+        check_set_operation_operands(&query, &right, &columns_types, &right_types, location)?;
+
+        query = Query::SetOperation(SetOperationQuery {
+            left: Box::new(query),
+            right: Box::new(right),
+            operator,
+            is_all,
+            order_by: None,
+            offset: None,
+            limit: None,
+        });
+    }
+
+    if let Query::SetOperation(set_operation) = &mut query {
+        parse_set_operation_result_clauses(
+            env,
+            tokens,
+            position,
+            set_operation,
+            columns_names,
+            columns_types,
+        )?;
+    }
+
+    Ok(query)
+}
+
+#[allow(clippy::type_complexity)]
+fn parse_intersect_operation(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    is_right_operand: bool,
+) -> Result<(Query, Vec<String>, Vec<Box<dyn DataType>>), Box<Diagnostic>> {
+    let (mut query, columns_names, columns_types) =
+        parse_set_operation_operand(env, tokens, position, is_right_operand)?;
+    while is_current_token(tokens, position, TokenKind::Intersect) {
+        let location = calculate_safe_location(tokens, *position);
+
+        // Consume `INTERSECT` keyword
+        *position += 1;
+
+        let is_all = parse_set_operation_all_option(tokens, position);
+        let (right, _, right_types) = parse_set_operation_operand(env, tokens, position, true)?;
+        check_set_operation_operands(&query, &right, &columns_types, &right_types, location)?;
+
+        query = Query::SetOperation(SetOperationQuery {
+            left: Box::new(query),
+            right: Box::new(right),
+            operator: SetOperator::Intersect,
+            is_all,
+            order_by: None,
+            offset: None,
+            limit: None,
+        });
+    }
+
+    Ok((query, columns_names, columns_types))
+}
+
+/// Parse the select query operand of set operation, the right operands stop before
+/// `ORDER BY`, `OFFSET` and `LIMIT` because they are applied on the combined rows
+#[allow(clippy::type_complexity)]
+fn parse_set_operation_operand(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    is_right_operand: bool,
+) -> Result<(Query, Vec<String>, Vec<Box<dyn DataType>>), Box<Diagnostic>> {
+    if !is_current_token(tokens, position, TokenKind::Select) {
+        return Err(
+            Diagnostic::error("Expect `SELECT` query after set operator")
+                .with_location(calculate_safe_location(tokens, *position))
+                .as_boxed(),
+        );
+    }
+
+    let (query, columns_names, columns_types) =
+        parse_scoped_select_query(env, tokens, position, is_right_operand)?;
+    Ok((Query::Select(query), columns_names, columns_types))
+}
+
+/// Parse `ORDER BY`, `OFFSET` and `LIMIT` after the last query of the set operation,
+/// they can only use the columns of the combined rows
+fn parse_set_operation_result_clauses(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    set_operation: &mut SetOperationQuery,
+    columns_names: Vec<String>,
+    columns_types: Vec<Box<dyn DataType>>,
+) -> Result<(), Box<Diagnostic>> {
+    let outer_scopes = std::mem::take(&mut env.scopes);
+    for (column_name, column_type) in columns_names.iter().zip(columns_types) {
+        env.define(column_name.to_string(), column_type);
+    }
+
+    let mut context = ParserContext {
+        selected_fields: columns_names,
+        has_select_statement: true,
+        ..Default::default()
+    };
+
+    let result = parse_set_operation_clauses(&mut context, env, tokens, position, set_operation);
+    env.scopes = outer_scopes;
+    result
+}
+
+fn parse_set_operation_clauses(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    set_operation: &mut SetOperationQuery,
+) -> Result<(), Box<Diagnostic>> {
+    while *position < tokens.len() {
+        match tokens[*position].kind {
+            TokenKind::Order if set_operation.order_by.is_none() => {
+                let location = tokens[*position].location;
+                let Statement::OrderBy(order_by) =
+                    parse_order_by_statement(context, env, tokens, position)?
+                else {
+                    unreachable!()
+                };
+
+                if !context.aggregations.is_empty() || !context.window_functions.is_empty() {
+                    return Err(Diagnostic::error(
+                        "`ORDER BY` of set operation can't use aggregation or window functions",
+                    )
+                    .with_location(location)
+                    .as_boxed());
+                }
+
+                set_operation.order_by = Some(order_by);
+            }
+            TokenKind::Offset if set_operation.offset.is_none() => {
+                let Statement::Offset(offset) =
+                    parse_offset_statement(context, env, tokens, position)?
+                else {
+                    unreachable!()
+                };
+                set_operation.offset = Some(offset);
+            }
+            TokenKind::Limit if set_operation.limit.is_none() => {
+                let Statement::Limit(limit) = parse_limit_statement(tokens, position)? else {
+                    unreachable!()
+                };
+                set_operation.limit = Some(limit);
+            }
+            _ => break,
+        }
+    }
+    Ok(())
+}
+
+fn parse_set_operation_all_option(tokens: &[Token], position: &mut usize) -> bool {
+    if is_current_token(tokens, position, TokenKind::All) {
+        // Consume `ALL` keyword
+        *position += 1;
+        return true;
+    }
+    false
+}
+
+fn check_set_operation_operands(
+    left: &Query,
+    right: &Query,
+    left_types: &[Box<dyn DataType>],
+    right_types: &[Box<dyn DataType>],
+    location: SourceLocation,
+) -> Result<(), Box<Diagnostic>> {
+    for operand in [left, right] {
+        if let Query::Select(select_query) = operand {
+            if select_query.statements.contains_key("into") {
+                return Err(Diagnostic::error(
+                    "`INTO` statement can't be used with set operations",
+                )
+                .with_location(location)
+                .as_boxed());
+            }
+
+            if ["order", "offset", "limit"]
+                .iter()
+                .any(|name| select_query.statements.contains_key(name))
+            {
+                return Err(Diagnostic::error(
+                    "`ORDER BY`, `OFFSET` and `LIMIT` must be used after the last query of set operations",
+                )
+                .add_help("They are applied on the combined rows of the set operation")
+                .with_location(location)
+                .as_boxed());
+            }
+        }
+    }
+
+    if left_types.len() != right_types.len() {
+        return Err(Diagnostic::error(&format!(
+            "Set operation queries must select the same number of columns but got {} and {}",
+            left_types.len(),
+            right_types.len()
+        ))
+        .with_location(location)
+        .as_boxed());
+    }
+
+    for (index, (left_type, right_type)) in left_types.iter().zip(right_types).enumerate() {
+        if !left_type.equals(right_type) {
+            return Err(Diagnostic::error(&format!(
+                "Set operation column {} has type `{}` in the left query but `{}` in the right query",
+                index + 1,
+                left_type.literal(),
+                right_type.literal()
+            ))
+            .with_location(location)
+            .as_boxed());
+        }
+    }
+
+    Ok(())
 }
 
 fn parse_select_query(
     env: &mut Environment,
     tokens: &[Token],
     position: &mut usize,
+    is_set_operation_operand: bool,
 ) -> Result<Query, Box<Diagnostic>> {
     let len = tokens.len();
 
@@ -318,6 +1115,10 @@ fn parse_select_query(
                 let statement = parse_having_statement(&mut context, env, tokens, position)?;
                 statements.insert("having", statement);
             }
+            // Set operation applies them on the combined rows after the last operand
+            TokenKind::Order | TokenKind::Offset | TokenKind::Limit if is_set_operation_operand => {
+                break;
+            }
             TokenKind::Limit => {
                 if statements.contains_key("limit") {
                     return Err(Diagnostic::error("You already used `LIMIT` statement")
@@ -427,6 +1228,36 @@ fn parse_select_query(
         }
     }
 
+    // Make sure the arguments of `GROUPING` are grouped columns
+    for aggregation in context.aggregations.values() {
+        if let AggregateValue::Grouping(arguments) = aggregation {
+            let Some(Statement::GroupBy(group_by)) = statements.get("group") else {
+                return Err(Diagnostic::error(
+                    "Function `GROUPING` can only be used in a query that has `GROUP BY` statement",
+                )
+                .as_boxed());
+            };
+
+            for argument in arguments {
+                let argument = argument.as_any().downcast_ref::<SymbolExpr>().unwrap();
+                let is_grouped_column = group_by.values.iter().any(|value| {
+                    value
+                        .as_any()
+                        .downcast_ref::<SymbolExpr>()
+                        .is_some_and(|value| value.value == argument.value)
+                });
+
+                if !is_grouped_column {
+                    return Err(Diagnostic::error(&format!(
+                        "Argument `{}` of function `GROUPING` must be a column in `GROUP BY`",
+                        argument.value
+                    ))
+                    .as_boxed());
+                }
+            }
+        }
+    }
+
     // If any aggregation function is used, add Aggregation Functions Node to the GitQL Query
     if !context.aggregations.is_empty() {
         let aggregation_functions = AggregationsStatement {
@@ -493,6 +1324,8 @@ fn parse_select_query(
         has_group_by_statement: context.has_group_by_statement,
         hidden_selections: hidden_selection_per_table,
         alias_table: context.name_alias_table,
+        subqueries: context.subqueries,
+        common_tables: vec![],
     }))
 }
 
@@ -509,7 +1342,7 @@ fn classify_hidden_selection(
     for hidden_selection in hidden_selections {
         let mut is_resolved = false;
         for table in tables {
-            let table_columns = env.schema.tables_fields_names.get(table.as_str()).unwrap();
+            let table_columns = env.table_columns(table).unwrap();
             if table_columns.contains(&hidden_selection.as_str()) {
                 let hidden_selection_for_table = table_hidden_selections.get_mut(table).unwrap();
                 if !hidden_selection_for_table.contains(hidden_selection) {
@@ -553,6 +1386,26 @@ fn parse_select_statement(
     // Parse `DISTINCT` or `DISTINCT ON(...)`
     let distinct = parse_select_distinct_option(context, tokens, position)?;
 
+    // Parse optional `FROM` with one or more tables and joins before the selections,
+    // so the columns of derived tables are resolved while parsing the selected expressions
+    let mut joins: Vec<Join> = vec![];
+    let mut tables_to_select_from: Vec<String> = vec![];
+    let selections_position = *position;
+    let from_position = find_from_keyword_position(tokens, *position);
+    if let Some(from_position) = from_position {
+        *position = from_position;
+        parse_from_option(
+            context,
+            env,
+            &mut tables_to_select_from,
+            &mut joins,
+            tokens,
+            position,
+        )?;
+    }
+    let end_of_from_position = *position;
+    *position = selections_position;
+
     // Parse `*` or `expressions`
     let mut fields_names: Vec<String> = vec![];
     let mut selected_expr_titles: Vec<String> = vec![];
@@ -572,17 +1425,10 @@ fn parse_select_statement(
     )?;
     context.inside_selections = false;
 
-    // Parse optional `FROM` with one or more tables and joins
-    let mut joins: Vec<Join> = vec![];
-    let mut tables_to_select_from: Vec<String> = vec![];
-    parse_from_option(
-        context,
-        env,
-        &mut tables_to_select_from,
-        &mut joins,
-        tokens,
-        position,
-    )?;
+    // Skip the already parsed `FROM` only if the selections ended right before it
+    if from_position == Some(*position) {
+        *position = end_of_from_position;
+    }
 
     // Make sure Aggregated functions are used with tables only
     if tables_to_select_from.is_empty() && !context.aggregations.is_empty() {
@@ -624,13 +1470,28 @@ fn parse_select_statement(
     }
 
     // Type check all selected fields has type registered in type table
-    let table_selections = type_check_and_classify_selected_fields(
+    let mut table_selections = type_check_and_classify_selected_fields(
         env,
         &tables_to_select_from,
         &fields_names,
         calculate_safe_location(tokens, *position),
     )?;
 
+    // Attach the query of each derived table, the source of each file table and the call of each table function
+    for table_selection in table_selections.iter_mut() {
+        if let Some(query) = context.derived_tables.remove(&table_selection.table_name) {
+            table_selection.derived_query = Some(Box::new(query));
+        }
+
+        if let Some(source) = context.file_tables.remove(&table_selection.table_name) {
+            table_selection.file_source = Some(source);
+        }
+
+        if let Some(call) = context.table_functions.remove(&table_selection.table_name) {
+            table_selection.table_function = Some(call);
+        }
+    }
+
     Ok(Statement::Select(SelectStatement {
         table_selections,
         joins,
@@ -770,10 +1631,8 @@ fn parse_select_all_or_expressions(
             .to_string();
 
             // TODO [#120, #121]: Remove this check
-            if env
-                .schema
-                .tables_fields_types
-                .contains_key(alias_name.as_str())
+            if resolve_selected_table_column_type(env, &context.selected_tables, &alias_name)
+                .is_some()
             {
                 return Err(Diagnostic::error("Can't use column name as Alias")
                     .add_note("Until supporting `table.column` you should use different alias name")
@@ -840,27 +1699,33 @@ fn parse_from_option(
         // Consume `From` keyword
         *position += 1;
 
-        // Parse and consume Symbol as Table name
-        let table_name = consume_conditional_token_or_errors(
-            tokens,
-            position,
-            |token| matches!(token.kind, TokenKind::Symbol(_)),
-            "Expect `Table` value after `FROM` keyword",
-        )?
-        .to_string();
-
-        if !env
-            .schema
-            .tables_fields_names
-            .contains_key(table_name.as_str())
-        {
+        // Parse derived table, file table, table function or consume Symbol as Table name
+        let table_expression = parse_table_expression(context, env, tokens, position)?;
+        let is_table_expression = table_expression.is_some();
+        let table_name = match table_expression {
+            Some(table_name) => table_name,
+            None => consume_conditional_token_or_errors(
+                tokens,
+                position,
+                |token| matches!(token.kind, TokenKind::Symbol(_)),
+                "Expect `Table` value after `FROM` keyword",
+            )?
+            .to_string(),
+        };
+
+        if !is_table_expression && env.is_view(&table_name) {
+            register_view_as_derived_table(context, env, &table_name);
+        }
+
+        if env.table_columns(&table_name).is_none() {
             let mut diagnostic =
                 Diagnostic::error(&format!("Cannot find table with name `{table_name}`"))
                     .add_note("You can use the `SHOW TABLES` query to get list of current tables")
                     .add_note("Check the documentations to see available tables")
                     .with_location(calculate_safe_location(tokens, *position));
 
-            let canditates: Vec<&&str> = env.schema.tables_fields_names.keys().collect();
+            let tables_names = env.schema.tables_names();
+            let canditates: Vec<&&str> = tables_names.iter().collect();
             if let Some(closest_valid_name) = find_closeest_string(&table_name, &canditates) {
                 let message =
                     &format!("a table with a similar name exists: `{closest_valid_name}`");
@@ -921,15 +1786,20 @@ fn parse_from_option(
             let join_location = tokens[*position].location;
             *position += 1;
 
-            if *position >= tokens.len() || !matches!(tokens[*position].kind, TokenKind::Symbol(_))
+            let is_derived_table = is_current_token(tokens, position, TokenKind::LeftParen);
+            if !is_derived_table
+                && (*position >= tokens.len()
+                    || !matches!(tokens[*position].kind, TokenKind::Symbol(_)))
             {
                 return Err(Diagnostic::error("Expect table name after `JOIN` keyword")
                     .with_location(calculate_safe_location(tokens, *position))
                     .as_boxed());
             }
 
-            let other_table = &tokens[*position];
-            let other_table_name = &other_table.to_string();
+            let table_expression = parse_table_expression(context, env, tokens, position)?;
+            let is_table_expression = table_expression.is_some();
+            let other_table_name =
+                &table_expression.unwrap_or_else(|| tokens[*position].to_string());
 
             // Make sure the RIGHT and LEFT tables names are not the same
             if number_previous_of_joins == 0 && table_name.eq(other_table_name) {
@@ -940,19 +1810,26 @@ fn parse_from_option(
                 .as_boxed());
             }
 
+            if !is_table_expression && env.is_view(other_table_name) {
+                register_view_as_derived_table(context, env, other_table_name);
+            }
+
             tables_to_select_from.push(other_table_name.to_string());
             context.selected_tables.push(other_table_name.to_string());
             register_current_table_fields_types(env, other_table_name)?;
-
-            // Consume Other table name
-            *position += 1;
+            if !is_table_expression {
+                // Consume Other table name
+                *position += 1;
+            }
 
             // Parse the `ON` predicate
             let mut predicate: Option<Box<dyn Expr>> = None;
             if is_current_token(tokens, position, TokenKind::On) {
                 // Consume `ON` keyword
                 *position += 1;
+                context.inside_join_predicate = true;
                 predicate = Some(parse_expression(context, env, tokens, position)?);
+                context.inside_join_predicate = false;
             }
 
             // Make sure user set predicate condition for LEFT or RIGHT JOIN
@@ -982,6 +1859,125 @@ fn parse_from_option(
     Ok(())
 }
 
+/// Parse derived table, file table or table function and return it's name,
+/// or return None if the current token is a table or view name
+fn parse_table_expression(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Option<String>, Box<Diagnostic>> {
+    if is_current_token(tokens, position, TokenKind::LeftParen) {
+        return parse_derived_table(context, env, tokens, position).map(Some);
+    }
+
+    if is_file_table_call(tokens, position) {
+        return parse_file_table(context, env, tokens, position).map(Some);
+    }
+
+    if is_table_function_call(env, tokens, position) {
+        return parse_table_function(context, env, tokens, position).map(Some);
+    }
+
+    Ok(None)
+}
+
+fn parse_derived_table(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<String, Box<Diagnostic>> {
+    let location = calculate_safe_location(tokens, *position);
+
+    // Consume `(` token
+    *position += 1;
+
+    if !is_current_token(tokens, position, TokenKind::Select) {
+        return Err(
+            Diagnostic::error("Expect `SELECT` query after `(` as derived table")
+                .with_location(calculate_safe_location(tokens, *position))
+                .as_boxed(),
+        );
+    }
+
+    let (query, columns_names, columns_types) = parse_subquery(env, tokens, position)?;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` at the end of derived table query",
+    )?;
+
+    // Consume optional `AS` keyword before the derived table name
+    if is_current_token(tokens, position, TokenKind::As) {
+        *position += 1;
+    }
+
+    let table_name = consume_conditional_token_or_errors(
+        tokens,
+        position,
+        |token| matches!(token.kind, TokenKind::Symbol(_)),
+        "Expect derived table name after the subquery, for example `FROM (SELECT ...) AS name`",
+    )?
+    .to_string();
+
+    if env.table_columns(&table_name).is_some() {
+        return Err(Diagnostic::error(&format!(
+            "There is already a table with name `{table_name}`"
+        ))
+        .add_help("Try to use a new unique name for the derived table")
+        .with_location(location)
+        .as_boxed());
+    }
+
+    let columns = columns_names.into_iter().zip(columns_types).collect();
+    env.derived_tables.insert(table_name.to_string(), columns);
+    context.derived_tables.insert(table_name.to_string(), query);
+    Ok(table_name)
+}
+
+/// Parse optional alias name of table expression with or without `AS` keyword
+pub(crate) fn parse_optional_table_alias(
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<Option<String>, Box<Diagnostic>> {
+    if is_current_token(tokens, position, TokenKind::As) {
+        // Consume `AS` keyword
+        *position += 1;
+        let alias = consume_conditional_token_or_errors(
+            tokens,
+            position,
+            |token| matches!(token.kind, TokenKind::Symbol(_)),
+            "Expect table name after `AS` keyword",
+        )?;
+        return Ok(Some(alias.to_string()));
+    }
+
+    if *position < tokens.len() && matches!(tokens[*position].kind, TokenKind::Symbol(_)) {
+        // Consume alias name
+        *position += 1;
+        return Ok(Some(tokens[*position - 1].to_string()));
+    }
+
+    Ok(None)
+}
+
+/// Select from view like a derived table with the view name and query
+fn register_view_as_derived_table(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    view_name: &str,
+) {
+    if let Some(view) = env.views.get(view_name) {
+        let query = view.query.clone();
+        let columns = view.columns.clone();
+        env.derived_tables.insert(view_name.to_string(), columns);
+        context.derived_tables.insert(view_name.to_string(), query);
+    }
+}
+
 fn parse_where_statement(
     context: &mut ParserContext,
     env: &mut Environment,
@@ -1038,58 +2034,6 @@ fn parse_where_statement(
     Ok(Statement::Where(WhereStatement { condition }))
 }
 
-fn parse_group_by_statement(
-    context: &mut ParserContext,
-    env: &mut Environment,
-    tokens: &[Token],
-    position: &mut usize,
-) -> Result<Statement, Box<Diagnostic>> {
-    // Consume `Group` keyword
-    *position += 1;
-
-    // Consume `By` keyword
-    consume_token_or_error(
-        tokens,
-        position,
-        TokenKind::By,
-        "Expect keyword `BY` after keyword `group`",
-    )?;
-
-    // Parse one or more expression
-    let mut values: Vec<Box<dyn Expr>> = vec![];
-    while *position < tokens.len() {
-        values.push(parse_expression(context, env, tokens, position)?);
-        if is_current_token(tokens, position, TokenKind::Comma) {
-            // Consume Comma `,`
-            *position += 1;
-            continue;
-        }
-        break;
-    }
-
-    let mut has_with_rollup = false;
-    if is_current_token(tokens, position, TokenKind::With) {
-        // Consume Comma `WITH``
-        *position += 1;
-
-        // Consume `Rollup` keyword
-        consume_token_or_error(
-            tokens,
-            position,
-            TokenKind::Rollup,
-            "Expect keyword `ROLLUP` after keyword `with`",
-        )?;
-
-        has_with_rollup = true;
-    }
-
-    context.has_group_by_statement = true;
-    Ok(Statement::GroupBy(GroupByStatement {
-        values,
-        has_with_roll_up: has_with_rollup,
-    }))
-}
-
 fn parse_having_statement(
     context: &mut ParserContext,
     env: &mut Environment,
@@ -1467,6 +2411,28 @@ fn parse_in_expression(
                 .as_boxed());
         }
 
+        // Parse `IN (SELECT ...)` as list of values selected by subquery
+        if is_next_token(tokens, position, TokenKind::Select) {
+            let subquery =
+                parse_subquery_expression(context, env, tokens, position, SubqueryKind::List)?;
+
+            let values_type = subquery.expr_type();
+            if !values_type.is_any() && !expression.expr_type().equals(&values_type) {
+                return Err(Diagnostic::error(
+                    "Argument and Values of In Expression must have the same type",
+                )
+                .with_location(in_location)
+                .as_boxed());
+            }
+
+            return Ok(Box::new(InExpr {
+                argument: expression,
+                values: vec![subquery],
+                values_type,
+                has_not_keyword,
+            }));
+        }
+
         let values =
             parse_zero_or_more_values_with_comma_between(context, env, tokens, position, "IN")?;
 
@@ -3307,6 +4273,9 @@ fn parse_primary_expression(
         TokenKind::Symbol(_) => parse_symbol_expression(context, env, tokens, position),
         TokenKind::Array => parse_array_value_expression(context, env, tokens, position),
         TokenKind::LeftBracket => parse_array_value_expression(context, env, tokens, position),
+        TokenKind::LeftParen if is_next_token(tokens, position, TokenKind::Select) => {
+            parse_subquery_expression(context, env, tokens, position, SubqueryKind::Scalar)
+        }
         TokenKind::LeftParen => {
             parse_column_or_row_expression(context, env, tokens, position, false)
         }
@@ -3366,16 +4335,20 @@ fn parse_symbol_expression(
 
         // If user perform member access with Composite type, composite type name should be in hidden selection
         // For example `SELECT (commit).author_name`, commit should be in hidden selection
-        if let Some(symbol_type) = env.schema.tables_fields_types.get(&value.as_str()) {
+        if let Some(symbol_type) =
+            resolve_selected_table_column_type(env, &context.selected_tables, &value)
+        {
             if symbol_type.is_composite() && !context.hidden_selections.contains(&value) {
                 context.hidden_selections.push(value.to_string());
             }
         }
     }
 
-    // In case of using un selected column name inside OVER(....) clauses, mark it as hidden selection for now
-    if context.inside_over_clauses
-        && env.schema.tables_fields_types.contains_key(&value.as_str())
+    // In case of using un selected column name inside OVER(....), JOIN ON or aggregation FILTER clauses, mark it as hidden selection for now
+    if (context.inside_over_clauses
+        || context.inside_join_predicate
+        || context.inside_aggregation_filter)
+        && resolve_selected_table_column_type(env, &context.selected_tables, &value).is_some()
         && !context.hidden_selections.contains(&value)
     {
         context.hidden_selections.push(value.to_string());
@@ -3549,6 +4522,102 @@ fn parse_column_or_row_expression(
     }
 }
 
+fn parse_subquery_expression(
+    context: &mut ParserContext,
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    kind: SubqueryKind,
+) -> Result<Box<dyn Expr>, Box<Diagnostic>> {
+    let location = calculate_safe_location(tokens, *position);
+    let name = context.name_generator.generate_subquery_name(*position);
+
+    // Consume `(` token
+    *position += 1;
+
+    let (query, columns_names, mut columns_types) = parse_subquery(env, tokens, position)?;
+
+    consume_token_or_error(
+        tokens,
+        position,
+        TokenKind::RightParen,
+        "Expect `)` at the end of subquery",
+    )?;
+
+    if columns_names.len() != 1 {
+        return Err(Diagnostic::error(&format!(
+            "Subquery used as expression must select one column but got {}",
+            columns_names.len()
+        ))
+        .with_location(location)
+        .as_boxed());
+    }
+
+    context.subqueries.insert(name.to_string(), query);
+
+    Ok(Box::new(SubqueryExpr {
+        name,
+        kind,
+        result_type: columns_types.remove(0),
+    }))
+}
+
+/// Parse uncorrelated select query in it's own scope, and return it with the names and types of the selected columns
+#[allow(clippy::type_complexity)]
+fn parse_subquery(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+) -> Result<(SelectQuery, Vec<String>, Vec<Box<dyn DataType>>), Box<Diagnostic>> {
+    let location = calculate_safe_location(tokens, *position);
+    let (query, columns_names, columns_types) =
+        parse_scoped_select_query(env, tokens, position, false)?;
+    if query.statements.contains_key("into") {
+        return Err(Diagnostic::error("Subquery can't have `INTO` statement")
+            .with_location(location)
+            .as_boxed());
+    }
+
+    Ok((query, columns_names, columns_types))
+}
+
+/// Parse select query in it's own scope, and return it with the names and types of the selected columns
+#[allow(clippy::type_complexity)]
+fn parse_scoped_select_query(
+    env: &mut Environment,
+    tokens: &[Token],
+    position: &mut usize,
+    is_set_operation_operand: bool,
+) -> Result<(SelectQuery, Vec<String>, Vec<Box<dyn DataType>>), Box<Diagnostic>> {
+    // The query can't see the columns of the outer query, but can see it's derived tables
+    let outer_scopes = std::mem::take(&mut env.scopes);
+    let outer_derived_tables = env.derived_tables.clone();
+
+    let result = parse_select_query(env, tokens, position, is_set_operation_operand).map(|query| {
+        let Query::Select(query) = query else {
+            unreachable!()
+        };
+
+        let mut columns_names = vec![];
+        let mut columns_types = vec![];
+        if let Some(Statement::Select(select_statement)) = query.statements.get("select") {
+            for table_selection in &select_statement.table_selections {
+                for column_name in &table_selection.columns_names {
+                    let title = query.alias_table.get(column_name).unwrap_or(column_name);
+                    columns_names.push(title.to_string());
+                    columns_types.push(resolve_symbol_type_or_undefine(env, column_name));
+                }
+            }
+        }
+
+        (query, columns_names, columns_types)
+    });
+
+    env.scopes = outer_scopes;
+    env.derived_tables = outer_derived_tables;
+    result
+}
+
 fn parse_case_expression(
     context: &mut ParserContext,
     env: &mut Environment,
@@ -3741,7 +4810,9 @@ fn un_expected_query_start_error(tokens: &[Token], position: &mut usize) -> Box<
     // Query starts with invalid keyword
     if *position == 0 {
         return Diagnostic::error("Unexpected query start")
-            .add_help("Expect query to start with `SELECT`, `DO`, `SET` or `DESCRIBE` keyword")
+            .add_help(
+                "Expect query to start with `SELECT`, `DO`, `SET`, `DESCRIBE`, `EXPLAIN`, `CREATE` or `DROP` keyword",
+            )
             .with_location(location)
             .as_boxed();
     }
@@ -3858,7 +4929,7 @@ fn un_expected_content_after_correct_query(
 
 #[inline(always)]
 #[allow(clippy::borrowed_box)]
-fn expression_literal(expression: &Box<dyn Expr>) -> Option<String> {
+pub(crate) fn expression_literal(expression: &Box<dyn Expr>) -> Option<String> {
     if let Some(symbol) = expression.as_any().downcast_ref::<SymbolExpr>() {
         return Some(symbol.value.to_string());
     }
@@ -3871,8 +4942,6 @@ fn resolve_symbol_type_or_undefine(env: &mut Environment, name: &String) -> Box<
         symbol_type.clone()
     } else if let Some(symbol_type) = env.globals_types.get(name) {
         symbol_type.clone()
-    } else if let Some(symbol_type) = env.schema.tables_fields_types.get(name.as_str()) {
-        symbol_type.clone()
     } else {
         Box::new(UndefType)
     }
@@ -3883,26 +4952,48 @@ fn register_current_table_fields_types(
     env: &mut Environment,
     table_name: &str,
 ) -> Result<(), Box<Diagnostic>> {
-    let table_fields_names = &env.schema.tables_fields_names[table_name].clone();
-    for field_name in table_fields_names {
-        if env.schema.tables_fields_types.contains_key(field_name) {
-            let field_type = env.schema.tables_fields_types[field_name].clone();
-            env.define(field_name.to_string(), field_type);
-            continue;
+    if let Some(columns) = env.derived_tables.get(table_name) {
+        for (column_name, column_type) in columns.clone() {
+            env.define(column_name, column_type);
         }
+        return Ok(());
+    }
 
-        return Err(Diagnostic::error(
-            &format!(
-                "Column name {field_name} in table {table_name} has no type registered in the schema",
-            )
-            .to_string(),
-        )
+    let Some(table) = env.schema.table(table_name) else {
+        return Err(Diagnostic::error(&format!(
+            "Table {table_name} has no definition registered in the schema",
+        ))
         .as_boxed());
+    };
+
+    for column in table.columns.clone() {
+        env.define(column.name, column.data_type);
     }
 
     Ok(())
 }
 
+/// Resolve the type of column name from the schema definitions or the derived tables of the selected tables
+#[inline(always)]
+fn resolve_selected_table_column_type(
+    env: &Environment,
+    selected_tables: &[String],
+    column_name: &str,
+) -> Option<Box<dyn DataType>> {
+    selected_tables.iter().find_map(|table_name| {
+        if let Some(column) = env.schema.column(table_name, column_name) {
+            return Some(column.data_type.clone());
+        }
+
+        env.derived_tables.get(table_name).and_then(|columns| {
+            columns
+                .iter()
+                .find(|(name, _)| name == column_name)
+                .map(|(_, data_type)| data_type.clone())
+        })
+    })
+}
+
 #[inline(always)]
 fn select_all_table_fields(
     env: &mut Environment,
@@ -3912,7 +5003,7 @@ fn select_all_table_fields(
 ) {
     let mut tables_columns: Vec<&str> = vec![];
     for table in table_name {
-        let columns = env.schema.tables_fields_names.get(table.as_str()).unwrap();
+        let columns = env.table_columns(table).unwrap();
         for column in columns {
             tables_columns.push(column);
         }
@@ -4068,6 +5159,39 @@ fn is_factor_operator(tokens: &[Token], position: &usize) -> bool {
 }
 
 #[inline(always)]
+/// Find the position of the `FROM` keyword of the current select statement, skipping the nested queries
+fn find_from_keyword_position(tokens: &[Token], position: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(position) {
+        match token.kind {
+            TokenKind::LeftParen => depth += 1,
+            TokenKind::RightParen if depth == 0 => return None,
+            TokenKind::RightParen => depth -= 1,
+            TokenKind::From if depth == 0 => return Some(index),
+            // `GROUP` keyword of ordered-set function `WITHIN GROUP (...)` is not a statement
+            TokenKind::Group if index > 0 && tokens[index - 1].kind == TokenKind::Within => {}
+            TokenKind::Select
+            | TokenKind::Where
+            | TokenKind::Group
+            | TokenKind::Having
+            | TokenKind::Order
+            | TokenKind::Limit
+            | TokenKind::Offset
+            | TokenKind::Qualify
+            | TokenKind::Window
+            | TokenKind::Into
+            | TokenKind::Union
+            | TokenKind::Semicolon
+                if depth == 0 =>
+            {
+                return None
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 fn is_join_or_join_type_token(tokens: &[Token], position: &usize) -> bool {
     *position < tokens.len()
         && matches!(
@@ -23,6 +23,13 @@ pub enum TokenKind {
     Glob,
     Describe,
     Show,
+    Explain,
+    Analyze,
+    Create,
+    Function,
+    Returns,
+    View,
+    Drop,
     RegExp,
     Array,
     Cast,
@@ -53,7 +60,10 @@ pub enum TokenKind {
     Not,
     As,
     With,
+    Recursive,
     Rollup,
+    Cube,
+    Within,
     OrKeyword,
     AndKeyword,
     XorKeyword,
@@ -64,9 +74,19 @@ pub enum TokenKind {
     Window,
     Over,
     Partition,
+    Rows,
+    Range,
+    Groups,
+    Unbounded,
+    Preceding,
+    Following,
+    Current,
     First,
     Last,
     Interval,
+    Union,
+    Intersect,
+    Except,
 
     // Values
     Row,
@@ -149,6 +169,13 @@ pub static GITQL_RESERVED_KEYWORDS: phf::Map<&'static str, TokenKind> = phf_map!
     "glob" => TokenKind::Glob,
     "describe" => TokenKind::Describe,
     "show" => TokenKind::Show,
+    "explain" => TokenKind::Explain,
+    "analyze" => TokenKind::Analyze,
+    "create" => TokenKind::Create,
+    "function" => TokenKind::Function,
+    "returns" => TokenKind::Returns,
+    "view" => TokenKind::View,
+    "drop" => TokenKind::Drop,
     "regexp" => TokenKind::RegExp,
 
     "cast" => TokenKind::Cast,
@@ -179,6 +206,16 @@ pub static GITQL_RESERVED_KEYWORDS: phf::Map<&'static str, TokenKind> = phf_map!
     "having" => TokenKind::Having,
     "with" => TokenKind::With,
     "rollup" => TokenKind::Rollup,
+    "cube" => TokenKind::Cube,
+    "within" => TokenKind::Within,
+
+    // Common table expressions
+    "recursive" => TokenKind::Recursive,
+
+    // Set operations
+    "union" => TokenKind::Union,
+    "intersect" => TokenKind::Intersect,
+    "except" => TokenKind::Except,
 
     // Between kind
     "symmetric" => TokenKind::Symmetric,
@@ -229,6 +266,15 @@ pub static GITQL_RESERVED_KEYWORDS: phf::Map<&'static str, TokenKind> = phf_map!
     "window" => TokenKind::Window,
     "over" => TokenKind::Over,
     "partition" => TokenKind::Partition,
+
+    // Window frame
+    "rows" => TokenKind::Rows,
+    "range" => TokenKind::Range,
+    "groups" => TokenKind::Groups,
+    "unbounded" => TokenKind::Unbounded,
+    "preceding" => TokenKind::Preceding,
+    "following" => TokenKind::Following,
+    "current" => TokenKind::Current,
 };
 
 impl Display for TokenKind {
@@ -252,6 +298,13 @@ impl Display for TokenKind {
             TokenKind::Glob => "GLOB",
             TokenKind::Describe => "DESCRIBE",
             TokenKind::Show => "SHOW",
+            TokenKind::Explain => "EXPLAIN",
+            TokenKind::Analyze => "ANALYZE",
+            TokenKind::Create => "CREATE",
+            TokenKind::Function => "FUNCTION",
+            TokenKind::Returns => "RETURNS",
+            TokenKind::View => "VIEW",
+            TokenKind::Drop => "DROP",
             TokenKind::RegExp => "REGEXP",
             TokenKind::Array => "ARRAY",
             TokenKind::Cast => "CAST",
@@ -282,7 +335,10 @@ impl Display for TokenKind {
             TokenKind::Not => "NOT",
             TokenKind::As => "AS",
             TokenKind::With => "WITH",
+            TokenKind::Recursive => "RECURSIVE",
             TokenKind::Rollup => "ROLLUP",
+            TokenKind::Cube => "CUBE",
+            TokenKind::Within => "WITHIN",
             TokenKind::OrKeyword => "OR",
             TokenKind::AndKeyword => "AND",
             TokenKind::XorKeyword => "XOE",
@@ -293,10 +349,20 @@ impl Display for TokenKind {
             TokenKind::Window => "WINDOW",
             TokenKind::Over => "OVER",
             TokenKind::Partition => "PARTITION",
+            TokenKind::Rows => "ROWS",
+            TokenKind::Range => "RANGE",
+            TokenKind::Groups => "GROUPS",
+            TokenKind::Unbounded => "UNBOUNDED",
+            TokenKind::Preceding => "PRECEDING",
+            TokenKind::Following => "FOLLOWING",
+            TokenKind::Current => "CURRENT",
             TokenKind::Nulls => "NULLS",
             TokenKind::First => "FIRST",
             TokenKind::Last => "LAST",
             TokenKind::Interval => "INTERVAL",
+            TokenKind::Union => "UNION",
+            TokenKind::Intersect => "INTERSECT",
+            TokenKind::Except => "EXCEPT",
 
             // Values
             TokenKind::Row => "Row",
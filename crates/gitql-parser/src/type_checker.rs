@@ -5,6 +5,7 @@ use gitql_ast::expression::Expr;
 use gitql_ast::statement::TableSelection;
 use gitql_ast::types::any::AnyType;
 use gitql_ast::types::dynamic::DynamicType;
+use gitql_ast::types::optional::OptionType;
 use gitql_ast::types::varargs::VarargsType;
 use gitql_ast::types::DataType;
 use gitql_core::environment::Environment;
@@ -233,6 +234,9 @@ pub fn type_check_and_classify_selected_fields(
         table_selections.push(TableSelection {
             table_name: table.to_string(),
             columns_names: vec![],
+            derived_query: None,
+            file_source: None,
+            table_function: None,
         });
         table_index.insert(table.to_string(), index);
     }
@@ -240,7 +244,7 @@ pub fn type_check_and_classify_selected_fields(
     for selected_column in selected_columns {
         let mut is_column_resolved = false;
         for table in selected_tables {
-            let table_columns = env.schema.tables_fields_names.get(table.as_str()).unwrap();
+            let table_columns = env.table_columns(table).unwrap();
 
             // Check if this column name exists in current table
             if table_columns.contains(&selected_column.as_str()) {
@@ -263,6 +267,9 @@ pub fn type_check_and_classify_selected_fields(
                                 .unwrap_or(&"".to_string())
                                 .to_string(),
                             columns_names: vec![selected_column.to_string()],
+                            derived_query: None,
+                            file_source: None,
+                            table_function: None,
                         });
                     } else {
                         table_selections[0]
@@ -296,7 +303,7 @@ pub fn type_check_projection_symbols(
     for (index, selected_column) in projection_names.iter().enumerate() {
         let mut is_column_resolved = false;
         for table in selected_tables {
-            let table_columns = env.schema.tables_fields_names.get(table.as_str()).unwrap();
+            let table_columns = env.table_columns(table).unwrap();
             if table_columns.contains(&selected_column.as_str()) {
                 is_column_resolved = true;
                 break;
@@ -354,5 +361,15 @@ pub fn resolve_dynamic_data_type(
         }
     }
 
+    // Resolve Optional Dynamic to Optional <TYPE> recursively
+    if let Some(optional) = data_type.as_any().downcast_ref::<OptionType>() {
+        if let Some(base) = &optional.base {
+            if base.as_any().downcast_ref::<DynamicType>().is_some() {
+                let base = resolve_dynamic_data_type(parameters, arguments, base);
+                return Box::new(OptionType::new(Some(base)));
+            }
+        }
+    }
+
     data_type.clone()
 }
@@ -9,16 +9,17 @@ use gitql_ast::types::datetime::DateTimeType;
 use gitql_ast::types::dynamic::DynamicType;
 use gitql_ast::types::float::FloatType;
 use gitql_ast::types::integer::IntType;
-use gitql_ast::types::null::NullType;
 use gitql_ast::types::optional::OptionType;
 use gitql_ast::types::text::TextType;
 use gitql_ast::types::time::TimeType;
 use gitql_ast::types::varargs::VarargsType;
 use gitql_ast::types::variant::VariantType;
+use gitql_ast::types::DataType;
 use gitql_core::signature::AggregationFunction;
 use gitql_core::signature::Signature;
 use gitql_core::values::array::ArrayValue;
 use gitql_core::values::boolean::BoolValue;
+use gitql_core::values::float::FloatValue;
 use gitql_core::values::integer::IntValue;
 use gitql_core::values::null::NullValue;
 use gitql_core::values::text::TextValue;
@@ -43,6 +44,26 @@ pub fn aggregation_functions() -> &'static HashMap<&'static str, AggregationFunc
         map.insert("bit_or", aggregation_bit_or);
         map.insert("bit_xor", aggregation_bit_xor);
         map.insert("array_agg", aggregation_array_agg);
+        map.insert("median", aggregation_median);
+        map.insert("stddev_samp", aggregation_stddev_samp);
+        map.insert("stddev_pop", aggregation_stddev_pop);
+        map.insert("var_samp", aggregation_var_samp);
+        map.insert("var_pop", aggregation_var_pop);
+        map.insert("corr", aggregation_corr);
+        map.insert("regr_slope", aggregation_regr_slope);
+        map
+    })
+}
+
+/// Ordered-set aggregation functions that are called with `WITHIN GROUP (ORDER BY value)`,
+/// the ordering value is passed as the last argument and the group rows are sorted by it
+pub fn ordered_set_aggregation_functions() -> &'static HashMap<&'static str, AggregationFunction> {
+    static HASHMAP: OnceLock<HashMap<&'static str, AggregationFunction>> = OnceLock::new();
+    HASHMAP.get_or_init(|| {
+        let mut map: HashMap<&'static str, AggregationFunction> = HashMap::new();
+        map.insert("percentile_cont", aggregation_percentile_cont);
+        map.insert("percentile_disc", aggregation_percentile_disc);
+        map.insert("mode", aggregation_mode);
         map
     })
 }
@@ -161,10 +182,99 @@ pub fn aggregation_function_signatures() -> HashMap<&'static str, Signature> {
             }),
         },
     );
+    map.insert(
+        "median",
+        Signature {
+            parameters: vec![number_type()],
+            return_type: Box::new(FloatType),
+        },
+    );
+    map.insert(
+        "stddev_samp",
+        Signature {
+            parameters: vec![number_type()],
+            return_type: Box::new(FloatType),
+        },
+    );
+    map.insert(
+        "stddev_pop",
+        Signature {
+            parameters: vec![number_type()],
+            return_type: Box::new(FloatType),
+        },
+    );
+    map.insert(
+        "var_samp",
+        Signature {
+            parameters: vec![number_type()],
+            return_type: Box::new(FloatType),
+        },
+    );
+    map.insert(
+        "var_pop",
+        Signature {
+            parameters: vec![number_type()],
+            return_type: Box::new(FloatType),
+        },
+    );
+    map.insert(
+        "corr",
+        Signature {
+            parameters: vec![number_type(), number_type()],
+            return_type: Box::new(FloatType),
+        },
+    );
+    map.insert(
+        "regr_slope",
+        Signature {
+            parameters: vec![number_type(), number_type()],
+            return_type: Box::new(FloatType),
+        },
+    );
     map
 }
 
+pub fn ordered_set_aggregation_function_signatures() -> HashMap<&'static str, Signature> {
+    let mut map: HashMap<&'static str, Signature> = HashMap::new();
+    map.insert(
+        "percentile_cont",
+        Signature {
+            parameters: vec![Box::new(FloatType), number_type()],
+            return_type: Box::new(FloatType),
+        },
+    );
+    map.insert(
+        "percentile_disc",
+        Signature {
+            parameters: vec![Box::new(FloatType), Box::new(AnyType)],
+            return_type: Box::new(DynamicType {
+                function: |elements| elements[1].clone(),
+            }),
+        },
+    );
+    map.insert(
+        "mode",
+        Signature {
+            parameters: vec![Box::new(AnyType)],
+            return_type: Box::new(DynamicType {
+                function: first_element_type,
+            }),
+        },
+    );
+    map
+}
+
+fn number_type() -> Box<dyn DataType> {
+    Box::new(VariantType {
+        variants: vec![Box::new(IntType), Box::new(FloatType)],
+    })
+}
+
 pub fn aggregation_max(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    if group_values.is_empty() {
+        return Box::new(NullValue);
+    }
+
     let mut max_value = &group_values[0][0];
     for row_values in group_values {
         let single_value = &row_values[0];
@@ -176,6 +286,10 @@ pub fn aggregation_max(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
 }
 
 pub fn aggregation_min(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    if group_values.is_empty() {
+        return Box::new(NullValue);
+    }
+
     let mut min_value = &group_values[0][0];
     for row_values in group_values {
         let single_value = &row_values[0];
@@ -188,15 +302,26 @@ pub fn aggregation_min(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
 
 pub fn aggregation_sum(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
     let mut sum: i64 = 0;
+    let mut has_non_null = false;
     for row_values in group_values {
         if let Some(int_value) = row_values[0].as_any().downcast_ref::<IntValue>() {
             sum += int_value.value;
+            has_non_null = true;
         }
     }
-    Box::new(IntValue { value: sum })
+
+    if has_non_null {
+        Box::new(IntValue { value: sum })
+    } else {
+        Box::new(NullValue)
+    }
 }
 
 pub fn aggregation_average(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    if group_values.is_empty() {
+        return Box::new(NullValue);
+    }
+
     let mut sum: i64 = 0;
     for row_values in group_values {
         if let Some(int_value) = row_values[0].as_any().downcast_ref::<IntValue>() {
@@ -214,6 +339,10 @@ pub fn aggregation_count(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value>
 }
 
 pub fn aggregation_group_concat(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    if group_values.is_empty() {
+        return Box::new(NullValue);
+    }
+
     let mut string_values: Vec<String> = vec![];
     for row_values in group_values {
         for value in row_values {
@@ -226,25 +355,39 @@ pub fn aggregation_group_concat(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn
 }
 
 pub fn aggregation_bool_and(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    let mut has_non_null = false;
     for row_values in group_values {
         if let Some(bool_value) = row_values[0].as_any().downcast_ref::<BoolValue>() {
             if !bool_value.value {
                 return Box::new(BoolValue { value: false });
             }
+            has_non_null = true;
         }
     }
-    Box::new(BoolValue { value: true })
+
+    if has_non_null {
+        Box::new(BoolValue { value: true })
+    } else {
+        Box::new(NullValue)
+    }
 }
 
 pub fn aggregation_bool_or(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    let mut has_non_null = false;
     for row_values in group_values {
         if let Some(bool_value) = row_values[0].as_any().downcast_ref::<BoolValue>() {
             if bool_value.value {
                 return Box::new(BoolValue { value: true });
             }
+            has_non_null = true;
         }
     }
-    Box::new(BoolValue { value: false })
+
+    if has_non_null {
+        Box::new(BoolValue { value: false })
+    } else {
+        Box::new(NullValue)
+    }
 }
 
 pub fn aggregation_bit_and(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
@@ -311,19 +454,392 @@ pub fn aggregation_bit_xor(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Valu
 }
 
 pub fn aggregation_array_agg(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    if group_values.is_empty() {
+        return Box::new(NullValue);
+    }
+
     let mut array: Vec<Box<dyn Value>> = vec![];
     for row_values in group_values {
         array.push(row_values[0].clone());
     }
 
-    let element_type = if array.is_empty() {
-        Box::new(NullType)
-    } else {
-        array[0].data_type()
-    };
-
+    let element_type = array[0].data_type();
     Box::new(ArrayValue {
         values: array,
         base_type: element_type,
     })
 }
+
+pub fn aggregation_median(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    let mut values = numbers_values(group_values, 0);
+    values.sort_by(f64::total_cmp);
+    match continuous_percentile(&values, 0.5) {
+        Some(median) => Box::new(FloatValue::new(median)),
+        None => Box::new(NullValue),
+    }
+}
+
+pub fn aggregation_stddev_samp(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    match variance(&numbers_values(group_values, 0), 1) {
+        Some(variance) => Box::new(FloatValue::new(variance.sqrt())),
+        None => Box::new(NullValue),
+    }
+}
+
+pub fn aggregation_stddev_pop(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    match variance(&numbers_values(group_values, 0), 0) {
+        Some(variance) => Box::new(FloatValue::new(variance.sqrt())),
+        None => Box::new(NullValue),
+    }
+}
+
+pub fn aggregation_var_samp(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    match variance(&numbers_values(group_values, 0), 1) {
+        Some(variance) => Box::new(FloatValue::new(variance)),
+        None => Box::new(NullValue),
+    }
+}
+
+pub fn aggregation_var_pop(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    match variance(&numbers_values(group_values, 0), 0) {
+        Some(variance) => Box::new(FloatValue::new(variance)),
+        None => Box::new(NullValue),
+    }
+}
+
+/// Correlation coefficient of the (Y, X) pairs that has no NULL values
+pub fn aggregation_corr(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    let (sum_xx, sum_yy, sum_xy) = match pairs_sums_of_squares(group_values) {
+        Some(sums) => sums,
+        None => return Box::new(NullValue),
+    };
+
+    let denominator = (sum_xx * sum_yy).sqrt();
+    if denominator == 0.0 {
+        return Box::new(NullValue);
+    }
+
+    Box::new(FloatValue::new(sum_xy / denominator))
+}
+
+/// Slope of the least-squares line of the (Y, X) pairs that has no NULL values
+pub fn aggregation_regr_slope(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    let (sum_xx, _, sum_xy) = match pairs_sums_of_squares(group_values) {
+        Some(sums) => sums,
+        None => return Box::new(NullValue),
+    };
+
+    if sum_xx == 0.0 {
+        return Box::new(NullValue);
+    }
+
+    Box::new(FloatValue::new(sum_xy / sum_xx))
+}
+
+/// Interpolated value at the fraction of the ordered values, the fraction is the first argument
+pub fn aggregation_percentile_cont(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    let fraction = match percentile_fraction(group_values) {
+        Some(fraction) => fraction,
+        None => return Box::new(NullValue),
+    };
+
+    let values = numbers_values(group_values, 1);
+    match continuous_percentile(&values, fraction) {
+        Some(percentile) => Box::new(FloatValue::new(percentile)),
+        None => Box::new(NullValue),
+    }
+}
+
+/// First ordered value that its position is greater than or equal the fraction, the fraction is the first argument
+pub fn aggregation_percentile_disc(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    let fraction = match percentile_fraction(group_values) {
+        Some(fraction) => fraction,
+        None => return Box::new(NullValue),
+    };
+
+    let values: Vec<&Box<dyn Value>> = group_values
+        .iter()
+        .map(|row_values| &row_values[1])
+        .filter(|value| !value.is_null())
+        .collect();
+
+    if values.is_empty() {
+        return Box::new(NullValue);
+    }
+
+    let position = (fraction * values.len() as f64).ceil() as usize;
+    values[position.saturating_sub(1)].clone()
+}
+
+/// Most frequent ordered value, if many values has the same frequency the first one in order is returned
+pub fn aggregation_mode(group_values: &[Vec<Box<dyn Value>>]) -> Box<dyn Value> {
+    let mut mode_value: Option<&Box<dyn Value>> = None;
+    let mut mode_count = 0;
+
+    let mut current_value: Option<&Box<dyn Value>> = None;
+    let mut current_count = 0;
+
+    for row_values in group_values {
+        let value = &row_values[0];
+        if value.is_null() {
+            continue;
+        }
+
+        // Equal values are adjacent because the values are ordered
+        match current_value {
+            Some(current) if current.equals(value) => current_count += 1,
+            _ => {
+                current_value = Some(value);
+                current_count = 1;
+            }
+        }
+
+        if current_count > mode_count {
+            mode_value = current_value;
+            mode_count = current_count;
+        }
+    }
+
+    match mode_value {
+        Some(value) => value.clone(),
+        None => Box::new(NullValue),
+    }
+}
+
+/// Return the non NULL numbers values of the argument in all rows
+fn numbers_values(group_values: &[Vec<Box<dyn Value>>], argument_index: usize) -> Vec<f64> {
+    group_values
+        .iter()
+        .filter_map(|row_values| number_value(row_values[argument_index].as_ref()))
+        .collect()
+}
+
+fn number_value(value: &dyn Value) -> Option<f64> {
+    if let Some(int_value) = value.as_any().downcast_ref::<IntValue>() {
+        return Some(int_value.value as f64);
+    }
+    value
+        .as_any()
+        .downcast_ref::<FloatValue>()
+        .map(|float_value| float_value.value)
+}
+
+/// Return the percentile fraction from the first argument, or None if it's not between 0 and 1
+fn percentile_fraction(group_values: &[Vec<Box<dyn Value>>]) -> Option<f64> {
+    let fraction = number_value(group_values.first()?[0].as_ref())?;
+    (0.0..=1.0).contains(&fraction).then_some(fraction)
+}
+
+/// Linear interpolation between the two nearest values of the fraction in sorted values
+fn continuous_percentile(sorted_values: &[f64], fraction: f64) -> Option<f64> {
+    if sorted_values.is_empty() {
+        return None;
+    }
+
+    let position = fraction * (sorted_values.len() - 1) as f64;
+    let lower = sorted_values[position.floor() as usize];
+    let upper = sorted_values[position.ceil() as usize];
+    Some(lower + (upper - lower) * (position - position.floor()))
+}
+
+/// Variance of the values where delta degrees of freedom is 0 for population and 1 for sample,
+/// or None if there are no enough values
+fn variance(values: &[f64], delta_degrees_of_freedom: usize) -> Option<f64> {
+    if values.len() <= delta_degrees_of_freedom {
+        return None;
+    }
+
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let sum_of_squares: f64 = values.iter().map(|value| (value - mean).powi(2)).sum();
+    Some(sum_of_squares / (values.len() - delta_degrees_of_freedom) as f64)
+}
+
+/// Return the sums of squares of X, Y and products of X and Y deviations from their means
+/// for the (Y, X) pairs that has no NULL values, or None if there are no pairs
+fn pairs_sums_of_squares(group_values: &[Vec<Box<dyn Value>>]) -> Option<(f64, f64, f64)> {
+    let pairs: Vec<(f64, f64)> = group_values
+        .iter()
+        .filter_map(|row_values| {
+            let y = number_value(row_values[0].as_ref())?;
+            let x = number_value(row_values[1].as_ref())?;
+            Some((x, y))
+        })
+        .collect();
+
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let count = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / count;
+
+    let mut sum_xx = 0.0;
+    let mut sum_yy = 0.0;
+    let mut sum_xy = 0.0;
+    for (x, y) in pairs {
+        sum_xx += (x - mean_x).powi(2);
+        sum_yy += (y - mean_y).powi(2);
+        sum_xy += (x - mean_x) * (y - mean_y);
+    }
+
+    Some((sum_xx, sum_yy, sum_xy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group_values(values: Vec<Box<dyn Value>>) -> Vec<Vec<Box<dyn Value>>> {
+        values.into_iter().map(|value| vec![value]).collect()
+    }
+
+    #[test]
+    fn aggregations_of_empty_group_are_null() {
+        let empty_group: Vec<Vec<Box<dyn Value>>> = vec![];
+        for function in [
+            aggregation_max,
+            aggregation_min,
+            aggregation_sum,
+            aggregation_average,
+            aggregation_group_concat,
+            aggregation_bool_and,
+            aggregation_bool_or,
+            aggregation_bit_and,
+            aggregation_bit_or,
+            aggregation_bit_xor,
+            aggregation_array_agg,
+        ] {
+            assert!(function(&empty_group).is_null());
+        }
+
+        assert_eq!(aggregation_count(&empty_group).as_int(), Some(0));
+    }
+
+    #[test]
+    fn sum_ignores_null_values() {
+        let values = group_values(vec![
+            Box::new(IntValue::new(2)),
+            Box::new(NullValue),
+            Box::new(IntValue::new(3)),
+        ]);
+        assert_eq!(aggregation_sum(&values).as_int(), Some(5));
+
+        let values = group_values(vec![Box::new(NullValue)]);
+        assert!(aggregation_sum(&values).is_null());
+    }
+
+    #[test]
+    fn bool_aggregations_ignore_null_values() {
+        let values = group_values(vec![Box::new(BoolValue::new(true)), Box::new(NullValue)]);
+        assert_eq!(aggregation_bool_and(&values).as_bool(), Some(true));
+        assert_eq!(aggregation_bool_or(&values).as_bool(), Some(true));
+
+        let values = group_values(vec![Box::new(BoolValue::new(false)), Box::new(NullValue)]);
+        assert_eq!(aggregation_bool_and(&values).as_bool(), Some(false));
+        assert_eq!(aggregation_bool_or(&values).as_bool(), Some(false));
+
+        let values = group_values(vec![Box::new(NullValue)]);
+        assert!(aggregation_bool_and(&values).is_null());
+        assert!(aggregation_bool_or(&values).is_null());
+    }
+
+    fn int_values(values: &[i64]) -> Vec<Vec<Box<dyn Value>>> {
+        values
+            .iter()
+            .map(|value| vec![Box::new(IntValue::new(*value)) as Box<dyn Value>])
+            .collect()
+    }
+
+    /// Rows of (Y, X) pairs
+    fn pairs_values(pairs: &[(i64, i64)]) -> Vec<Vec<Box<dyn Value>>> {
+        pairs
+            .iter()
+            .map(|(y, x)| {
+                vec![
+                    Box::new(IntValue::new(*y)) as Box<dyn Value>,
+                    Box::new(IntValue::new(*x)),
+                ]
+            })
+            .collect()
+    }
+
+    /// Rows of the percentile fraction and the ordered value
+    fn ordered_set_values(fraction: f64, values: &[i64]) -> Vec<Vec<Box<dyn Value>>> {
+        values
+            .iter()
+            .map(|value| {
+                vec![
+                    Box::new(FloatValue::new(fraction)) as Box<dyn Value>,
+                    Box::new(IntValue::new(*value)),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn variance_and_standard_deviation() {
+        let values = int_values(&[2, 4, 4, 4, 5, 5, 7, 9]);
+        assert_eq!(aggregation_var_pop(&values).as_float(), Some(4.0));
+        assert_eq!(aggregation_stddev_pop(&values).as_float(), Some(2.0));
+        assert_eq!(aggregation_var_samp(&values).as_float(), Some(32.0 / 7.0));
+        assert_eq!(
+            aggregation_stddev_samp(&values).as_float(),
+            Some((32.0_f64 / 7.0).sqrt())
+        );
+
+        let single_value = int_values(&[3]);
+        assert_eq!(aggregation_var_pop(&single_value).as_float(), Some(0.0));
+        assert!(aggregation_var_samp(&single_value).is_null());
+        assert!(aggregation_stddev_samp(&single_value).is_null());
+    }
+
+    #[test]
+    fn median_ignores_null_values() {
+        let mut values = int_values(&[9, 1, 4, 5]);
+        values.push(vec![Box::new(NullValue)]);
+        assert_eq!(aggregation_median(&values).as_float(), Some(4.5));
+
+        let values = int_values(&[3, 1, 2]);
+        assert_eq!(aggregation_median(&values).as_float(), Some(2.0));
+    }
+
+    #[test]
+    fn correlation_and_regression_slope() {
+        let values = pairs_values(&[(2, 1), (4, 2), (6, 3)]);
+        assert_eq!(aggregation_corr(&values).as_float(), Some(1.0));
+        assert_eq!(aggregation_regr_slope(&values).as_float(), Some(2.0));
+
+        let values = pairs_values(&[(3, 1), (2, 2), (1, 3)]);
+        assert_eq!(aggregation_corr(&values).as_float(), Some(-1.0));
+        assert_eq!(aggregation_regr_slope(&values).as_float(), Some(-1.0));
+
+        // Constant X has no slope or correlation
+        let values = pairs_values(&[(1, 5), (2, 5)]);
+        assert!(aggregation_corr(&values).is_null());
+        assert!(aggregation_regr_slope(&values).is_null());
+    }
+
+    #[test]
+    fn percentiles_of_ordered_values() {
+        let values = ordered_set_values(0.25, &[1, 2, 3, 4]);
+        assert_eq!(aggregation_percentile_cont(&values).as_float(), Some(1.75));
+
+        let values = ordered_set_values(0.5, &[1, 2, 3, 4]);
+        assert_eq!(aggregation_percentile_disc(&values).as_int(), Some(2));
+
+        let values = ordered_set_values(1.5, &[1, 2, 3, 4]);
+        assert!(aggregation_percentile_cont(&values).is_null());
+        assert!(aggregation_percentile_disc(&values).is_null());
+    }
+
+    #[test]
+    fn mode_of_ordered_values() {
+        let values = int_values(&[1, 2, 2, 3]);
+        assert_eq!(aggregation_mode(&values).as_int(), Some(2));
+
+        // First value in order wins when frequencies are equal
+        let values = int_values(&[1, 1, 3, 3]);
+        assert_eq!(aggregation_mode(&values).as_int(), Some(1));
+    }
+}
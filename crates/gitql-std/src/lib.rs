@@ -7,5 +7,6 @@ pub mod number;
 pub mod range;
 pub mod regex;
 pub mod standard;
+pub mod table;
 pub mod text;
 pub mod window;
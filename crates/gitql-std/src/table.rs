@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use gitql_ast::types::any::AnyType;
+use gitql_ast::types::array::ArrayType;
+use gitql_ast::types::dynamic::DynamicType;
+use gitql_ast::types::integer::IntType;
+use gitql_ast::types::optional::OptionType;
+use gitql_core::object::Row;
+use gitql_core::schema::ColumnDefinition;
+use gitql_core::signature::TableFunction;
+use gitql_core::signature::TableSignature;
+use gitql_core::values::integer::IntValue;
+use gitql_core::values::Value;
+
+use crate::meta_types::array_element_type;
+use crate::meta_types::first_element_type;
+
+pub fn table_functions() -> &'static HashMap<&'static str, TableFunction> {
+    static HASHMAP: OnceLock<HashMap<&'static str, TableFunction>> = OnceLock::new();
+    HASHMAP.get_or_init(|| {
+        let mut map: HashMap<&'static str, TableFunction> = HashMap::new();
+        map.insert("generate_series", table_generate_series);
+        map.insert("unnest", table_unnest);
+        map
+    })
+}
+
+pub fn table_function_signatures() -> HashMap<&'static str, TableSignature> {
+    let mut map: HashMap<&'static str, TableSignature> = HashMap::new();
+    map.insert(
+        "generate_series",
+        TableSignature::new(
+            vec![
+                Box::new(IntType),
+                Box::new(IntType),
+                Box::new(OptionType::new(Some(Box::new(IntType)))),
+            ],
+            vec![ColumnDefinition::new("value", Box::new(IntType))],
+        ),
+    );
+
+    map.insert(
+        "unnest",
+        TableSignature::new(
+            vec![Box::new(ArrayType::new(Box::new(AnyType)))],
+            vec![ColumnDefinition::new(
+                "value",
+                Box::new(DynamicType {
+                    function: |elements| array_element_type(first_element_type(elements)),
+                }),
+            )
+            .nullable()],
+        ),
+    );
+    map
+}
+
+pub fn table_generate_series(inputs: &[Box<dyn Value>]) -> Result<Vec<Row>, String> {
+    let start = inputs[0].as_int().unwrap();
+    let stop = inputs[1].as_int().unwrap();
+    let step = inputs.get(2).and_then(|step| step.as_int()).unwrap_or(1);
+    if step == 0 {
+        return Err("generate_series step can't be zero".to_string());
+    }
+
+    let mut rows: Vec<Row> = vec![];
+    let mut value = start;
+    while (step > 0 && value <= stop) || (step < 0 && value >= stop) {
+        rows.push(Row {
+            values: vec![Box::new(IntValue::new(value))],
+        });
+
+        match value.checked_add(step) {
+            Some(next_value) => value = next_value,
+            None => break,
+        }
+    }
+
+    Ok(rows)
+}
+
+pub fn table_unnest(inputs: &[Box<dyn Value>]) -> Result<Vec<Row>, String> {
+    let elements = inputs[0].as_array().unwrap_or_default();
+    Ok(elements
+        .into_iter()
+        .map(|element| Row {
+            values: vec![element],
+        })
+        .collect())
+}
@@ -3,9 +3,12 @@ use std::sync::OnceLock;
 
 use gitql_ast::types::any::AnyType;
 use gitql_ast::types::dynamic::DynamicType;
+use gitql_ast::types::float::FloatType;
 use gitql_ast::types::integer::IntType;
+use gitql_ast::types::optional::OptionType;
 use gitql_core::signature::Signature;
 use gitql_core::signature::WindowFunction;
+use gitql_core::values::float::FloatValue;
 use gitql_core::values::integer::IntValue;
 use gitql_core::values::null::NullValue;
 use gitql_core::values::Value;
@@ -20,6 +23,13 @@ pub fn window_functions() -> &'static HashMap<&'static str, WindowFunction> {
         map.insert("nth_value", window_nth_value);
         map.insert("last_value", window_last_value);
         map.insert("row_number", window_row_number);
+        map.insert("rank", window_rank);
+        map.insert("dense_rank", window_dense_rank);
+        map.insert("percent_rank", window_percent_rank);
+        map.insert("cume_dist", window_cume_dist);
+        map.insert("ntile", window_ntile);
+        map.insert("lag", window_lag);
+        map.insert("lead", window_lead);
         map
     })
 }
@@ -63,10 +73,82 @@ pub fn window_function_signatures() -> HashMap<&'static str, Signature> {
             return_type: Box::new(IntType),
         },
     );
+
+    map.insert(
+        "rank",
+        Signature {
+            parameters: vec![],
+            return_type: Box::new(IntType),
+        },
+    );
+
+    map.insert(
+        "dense_rank",
+        Signature {
+            parameters: vec![],
+            return_type: Box::new(IntType),
+        },
+    );
+
+    map.insert(
+        "percent_rank",
+        Signature {
+            parameters: vec![],
+            return_type: Box::new(FloatType),
+        },
+    );
+
+    map.insert(
+        "cume_dist",
+        Signature {
+            parameters: vec![],
+            return_type: Box::new(FloatType),
+        },
+    );
+
+    map.insert(
+        "ntile",
+        Signature {
+            parameters: vec![Box::new(IntType)],
+            return_type: Box::new(IntType),
+        },
+    );
+
+    map.insert(
+        "lag",
+        Signature {
+            parameters: vec![
+                Box::new(AnyType),
+                Box::new(OptionType::new(Some(Box::new(IntType)))),
+                Box::new(OptionType::new(Some(Box::new(DynamicType {
+                    function: first_element_type,
+                })))),
+            ],
+            return_type: Box::new(DynamicType {
+                function: first_element_type,
+            }),
+        },
+    );
+
+    map.insert(
+        "lead",
+        Signature {
+            parameters: vec![
+                Box::new(AnyType),
+                Box::new(OptionType::new(Some(Box::new(IntType)))),
+                Box::new(OptionType::new(Some(Box::new(DynamicType {
+                    function: first_element_type,
+                })))),
+            ],
+            return_type: Box::new(DynamicType {
+                function: first_element_type,
+            }),
+        },
+    );
     map
 }
 
-pub fn window_first_value(frame: &[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>> {
+pub fn window_first_value(frame: &[Vec<Box<dyn Value>>], _peers: &[usize]) -> Vec<Box<dyn Value>> {
     let frame_len = frame.len();
     let first_value = &frame[0][0];
     let mut values = Vec::with_capacity(frame_len);
@@ -76,7 +158,7 @@ pub fn window_first_value(frame: &[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>>
     values
 }
 
-pub fn window_nth_value(frame: &[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>> {
+pub fn window_nth_value(frame: &[Vec<Box<dyn Value>>], _peers: &[usize]) -> Vec<Box<dyn Value>> {
     let frame_len = frame.len();
     let index = frame[0][1].as_int().unwrap();
 
@@ -92,7 +174,7 @@ pub fn window_nth_value(frame: &[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>> {
     values
 }
 
-pub fn window_last_value(frame: &[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>> {
+pub fn window_last_value(frame: &[Vec<Box<dyn Value>>], _peers: &[usize]) -> Vec<Box<dyn Value>> {
     let frame_len = frame.len();
     let last_value = &frame[frame_len - 1][0];
     let mut values = Vec::with_capacity(frame_len);
@@ -102,7 +184,7 @@ pub fn window_last_value(frame: &[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>> {
     values
 }
 
-pub fn window_row_number(frame: &[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>> {
+pub fn window_row_number(frame: &[Vec<Box<dyn Value>>], _peers: &[usize]) -> Vec<Box<dyn Value>> {
     let frame_len = frame.len();
     let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(frame_len);
     for i in 0..frame_len {
@@ -111,3 +193,122 @@ pub fn window_row_number(frame: &[Vec<Box<dyn Value>>]) -> Vec<Box<dyn Value>> {
     }
     values
 }
+
+pub fn window_rank(frame: &[Vec<Box<dyn Value>>], peers: &[usize]) -> Vec<Box<dyn Value>> {
+    let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(frame.len());
+    let mut rank = 1;
+    for index in 0..frame.len() {
+        if index > 0 && peers[index] != peers[index - 1] {
+            rank = index as i64 + 1;
+        }
+        values.push(Box::new(IntValue { value: rank }));
+    }
+    values
+}
+
+pub fn window_dense_rank(frame: &[Vec<Box<dyn Value>>], peers: &[usize]) -> Vec<Box<dyn Value>> {
+    let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(frame.len());
+    let mut rank = 1;
+    for index in 0..frame.len() {
+        if index > 0 && peers[index] != peers[index - 1] {
+            rank += 1;
+        }
+        values.push(Box::new(IntValue { value: rank }));
+    }
+    values
+}
+
+pub fn window_percent_rank(frame: &[Vec<Box<dyn Value>>], peers: &[usize]) -> Vec<Box<dyn Value>> {
+    let frame_len = frame.len();
+    let ranks = window_rank(frame, peers);
+    let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(frame_len);
+    for rank in ranks {
+        let percent_rank = if frame_len > 1 {
+            (rank.as_int().unwrap() - 1) as f64 / (frame_len - 1) as f64
+        } else {
+            0.0
+        };
+        values.push(Box::new(FloatValue::new(percent_rank)));
+    }
+    values
+}
+
+pub fn window_cume_dist(frame: &[Vec<Box<dyn Value>>], peers: &[usize]) -> Vec<Box<dyn Value>> {
+    let frame_len = frame.len();
+    let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(frame_len);
+    let mut peer_group_end = 0;
+    for index in 0..frame_len {
+        // Number of rows before or peer with the current row
+        if index >= peer_group_end {
+            peer_group_end = index + 1;
+            while peer_group_end < frame_len && peers[peer_group_end] == peers[index] {
+                peer_group_end += 1;
+            }
+        }
+
+        let cume_dist = peer_group_end as f64 / frame_len as f64;
+        values.push(Box::new(FloatValue::new(cume_dist)));
+    }
+    values
+}
+
+pub fn window_ntile(frame: &[Vec<Box<dyn Value>>], _peers: &[usize]) -> Vec<Box<dyn Value>> {
+    let frame_len = frame.len();
+    let buckets_count = frame[0][0].as_int().unwrap_or(0);
+    let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(frame_len);
+    if buckets_count <= 0 {
+        for _ in 0..frame_len {
+            values.push(Box::new(NullValue));
+        }
+        return values;
+    }
+
+    // Distribute the rows so the first buckets has one more row than the others if they can't be equal
+    let buckets_count = buckets_count as usize;
+    let bucket_size = frame_len / buckets_count;
+    let large_buckets_count = frame_len % buckets_count;
+    let large_buckets_rows = large_buckets_count * (bucket_size + 1);
+    for index in 0..frame_len {
+        let bucket = if index < large_buckets_rows {
+            index / (bucket_size + 1)
+        } else {
+            large_buckets_count + (index - large_buckets_rows) / bucket_size
+        };
+        values.push(Box::new(IntValue {
+            value: bucket as i64 + 1,
+        }));
+    }
+    values
+}
+
+pub fn window_lag(frame: &[Vec<Box<dyn Value>>], _peers: &[usize]) -> Vec<Box<dyn Value>> {
+    window_offset_value(frame, -1)
+}
+
+pub fn window_lead(frame: &[Vec<Box<dyn Value>>], _peers: &[usize]) -> Vec<Box<dyn Value>> {
+    window_offset_value(frame, 1)
+}
+
+/// Return the value of the row before or after each row by offset, or the default value
+/// if there is no row at this offset, the default offset is 1 and the default value is NULL
+fn window_offset_value(frame: &[Vec<Box<dyn Value>>], direction: i64) -> Vec<Box<dyn Value>> {
+    let frame_len = frame.len() as i64;
+    let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(frame.len());
+    for (index, row) in frame.iter().enumerate() {
+        let offset = row.get(1).and_then(|offset| offset.as_int()).unwrap_or(1);
+        let target_index = offset
+            .checked_mul(direction)
+            .and_then(|offset| offset.checked_add(index as i64));
+
+        match target_index {
+            Some(target_index) if target_index >= 0 && target_index < frame_len => {
+                values.push(frame[target_index as usize][0].clone());
+            }
+            _ => match row.get(2) {
+                Some(default_value) => values.push(default_value.clone()),
+                None => values.push(Box::new(NullValue)),
+            },
+        }
+    }
+    values
+}
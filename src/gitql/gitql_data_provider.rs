@@ -1,5 +1,7 @@
-use std::convert::Infallible;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 
+use gitql_ast::operator::ComparisonOperator;
 use gitql_core::object::Row;
 use gitql_core::values::Value;
 use gitql_core::values::boolean::BoolValue;
@@ -7,55 +9,262 @@ use gitql_core::values::datetime::DateTimeValue;
 use gitql_core::values::integer::IntValue;
 use gitql_core::values::null::NullValue;
 use gitql_core::values::text::TextValue;
+use gitql_engine::data_provider::ColumnCondition;
 use gitql_engine::data_provider::DataProvider;
+use gitql_engine::data_provider::ProviderHints;
+use gitql_engine::data_provider::RowsIterator;
 
-use gix::diff::blob::pipeline::Mode;
+use gix::bstr::ByteSlice;
 use gix::refs::Category;
-
+use gix::revision::plumbing::Spec;
+use gix::revision::walk::Sorting;
+use gix::traverse::commit::simple::CommitTimeOrder;
+
+use super::gitql_diffs::CommitDiff;
+use super::gitql_diffs::DiffCommit;
+use super::gitql_diffs::MAX_DIFFS_BATCH_SIZE;
+use super::gitql_diffs::calculate_cached_commits_diffs;
+use super::gitql_diffs::diffs_threads_count;
+use super::gitql_diffs_cache::DiffsCache;
 use super::values::diff_changes::DiffChange;
 use super::values::diff_changes::DiffChangesValue;
 
 pub struct GitQLDataProvider {
     repos: Vec<gix::Repository>,
+    is_diffs_cache_enabled: bool,
 }
 
 impl GitQLDataProvider {
     #[must_use]
     pub fn new(repos: Vec<gix::Repository>) -> Self {
-        Self { repos }
+        Self {
+            repos,
+            is_diffs_cache_enabled: false,
+        }
+    }
+
+    /// Load and store the commits diffs in the persistent cache of each repository
+    #[must_use]
+    pub fn with_diffs_cache(mut self, is_enabled: bool) -> Self {
+        self.is_diffs_cache_enabled = is_enabled;
+        self
     }
 }
 
 impl DataProvider for GitQLDataProvider {
     fn provide(&self, table: &str, selected_columns: &[String]) -> Result<Vec<Row>, String> {
+        self.provide_with_hints(table, selected_columns, &ProviderHints::default())
+    }
+
+    fn provide_with_hints(
+        &self,
+        table: &str,
+        selected_columns: &[String],
+        hints: &ProviderHints,
+    ) -> Result<Vec<Row>, String> {
+        // Values are not `Send`, so each repository thread selects its rows as plain cells
+        // and the rows values are built on the calling thread
+        let is_diffs_cache_enabled = self.is_diffs_cache_enabled;
+        let conditions_cells = conditions_cells(hints);
+        let limit = hints.limit;
+        let repos_cells: Vec<Result<Vec<Vec<ProvidedCell>>, String>> =
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = self
+                    .repos
+                    .iter()
+                    .map(|repository| {
+                        let repository = repository.clone().into_sync();
+                        let conditions_cells = conditions_cells.clone();
+                        scope.spawn(move || {
+                            let repo = repository.to_thread_local();
+                            let hints = ProviderHints {
+                                conditions: conditions_from_cells(conditions_cells),
+                                limit,
+                            };
+                            let rows = select_gql_objects(
+                                &repo,
+                                table,
+                                selected_columns,
+                                &hints,
+                                is_diffs_cache_enabled,
+                            )?;
+
+                            let mut cells_rows = vec![];
+                            for row in rows {
+                                let row = row?;
+                                let mut cells = Vec::with_capacity(row.values.len());
+                                for value in &row.values {
+                                    cells.push(ProvidedCell::from_value(value.as_ref())?);
+                                }
+                                cells_rows.push(cells);
+                            }
+                            Ok(cells_rows)
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|_| Err("Repository thread panicked".to_string()))
+                    })
+                    .collect()
+            });
+
         let mut rows: Vec<Row> = vec![];
+        for repo_cells in repos_cells {
+            for cells in repo_cells? {
+                let values = cells.into_iter().map(ProvidedCell::into_value).collect();
+                rows.push(Row { values });
+            }
+        }
 
+        Ok(rows)
+    }
+
+    fn provide_iter<'a>(
+        &'a self,
+        table: &str,
+        selected_columns: &'a [String],
+        hints: &'a ProviderHints,
+    ) -> Result<RowsIterator<'a>, String> {
+        // Rows of multiple repositories are selected in parallel, the limit hint still
+        // stops each repository walk early
+        if self.repos.len() > 1 {
+            let rows = self.provide_with_hints(table, selected_columns, hints)?;
+            return Ok(Box::new(rows.into_iter().map(Ok)));
+        }
+
+        let mut repos_rows: Vec<RowsIterator<'a>> = Vec::with_capacity(self.repos.len());
         for repository in &self.repos {
-            let mut repo_rows =
-                select_gql_objects(repository, table.to_string(), selected_columns)?;
-            rows.append(&mut repo_rows);
+            let repo_rows = select_gql_objects(
+                repository,
+                table,
+                selected_columns,
+                hints,
+                self.is_diffs_cache_enabled,
+            )?;
+            repos_rows.push(repo_rows);
         }
 
-        Ok(rows)
+        Ok(Box::new(repos_rows.into_iter().flatten()))
     }
 }
 
-fn select_gql_objects(
-    repo: &gix::Repository,
-    table: String,
-    selected_columns: &[String],
-) -> Result<Vec<Row>, String> {
-    match table.as_str() {
-        "refs" => select_references(repo, selected_columns),
-        "commits" => select_commits(repo, selected_columns),
-        "branches" => select_branches(repo, selected_columns),
-        "diffs" => select_diffs(repo, selected_columns),
-        "diffs_changes" => select_diffs_changes(repo, selected_columns),
-        "tags" => select_tags(repo, selected_columns),
-        _ => Ok(vec![Row { values: vec![] }]),
+/// Thread safe representation of the values selected by the provider
+#[derive(Clone)]
+enum ProvidedCell {
+    Text(String),
+    Int(i64),
+    Bool(bool),
+    DateTime(i64),
+    DiffChanges(Vec<DiffChange>),
+    Null,
+}
+
+impl ProvidedCell {
+    fn from_value(value: &dyn Value) -> Result<Self, String> {
+        let any = value.as_any();
+        if let Some(text) = any.downcast_ref::<TextValue>() {
+            return Ok(ProvidedCell::Text(text.value.to_string()));
+        }
+        if let Some(integer) = any.downcast_ref::<IntValue>() {
+            return Ok(ProvidedCell::Int(integer.value));
+        }
+        if let Some(boolean) = any.downcast_ref::<BoolValue>() {
+            return Ok(ProvidedCell::Bool(boolean.value));
+        }
+        if let Some(datetime) = any.downcast_ref::<DateTimeValue>() {
+            return Ok(ProvidedCell::DateTime(datetime.value));
+        }
+        if let Some(diff_changes) = any.downcast_ref::<DiffChangesValue>() {
+            return Ok(ProvidedCell::DiffChanges(diff_changes.changes.clone()));
+        }
+        if any.downcast_ref::<NullValue>().is_some() {
+            return Ok(ProvidedCell::Null);
+        }
+        Err(format!("Unsupported provided value `{}`", value.literal()))
+    }
+
+    fn into_value(self) -> Box<dyn Value> {
+        match self {
+            ProvidedCell::Text(value) => Box::new(TextValue::new(value)),
+            ProvidedCell::Int(value) => Box::new(IntValue::new(value)),
+            ProvidedCell::Bool(value) => Box::new(BoolValue::new(value)),
+            ProvidedCell::DateTime(value) => Box::new(DateTimeValue::new(value)),
+            ProvidedCell::DiffChanges(changes) => Box::new(DiffChangesValue::new(changes)),
+            ProvidedCell::Null => Box::new(NullValue),
+        }
     }
 }
 
+type ConditionCells = Vec<(String, ComparisonOperator, ProvidedCell)>;
+
+/// Convert the hints conditions to cells, conditions on unsupported values are dropped and left for the engine
+fn conditions_cells(hints: &ProviderHints) -> ConditionCells {
+    hints
+        .conditions
+        .iter()
+        .filter_map(|condition| {
+            let cell = ProvidedCell::from_value(condition.value.as_ref()).ok()?;
+            Some((
+                condition.column.to_string(),
+                condition.operator.clone(),
+                cell,
+            ))
+        })
+        .collect()
+}
+
+fn conditions_from_cells(cells: ConditionCells) -> Vec<ColumnCondition> {
+    cells
+        .into_iter()
+        .map(|(column, operator, cell)| ColumnCondition {
+            column,
+            operator,
+            value: cell.into_value(),
+        })
+        .collect()
+}
+
+/// Select the rows of the table, commits tables rows are selected lazily while walking the commits
+fn select_gql_objects<'a>(
+    repo: &'a gix::Repository,
+    table: &str,
+    selected_columns: &'a [String],
+    hints: &'a ProviderHints,
+    is_diffs_cache_enabled: bool,
+) -> Result<RowsIterator<'a>, String> {
+    let rows = match table {
+        "refs" => select_references(repo, selected_columns)?,
+        "commits" => return select_commits(repo, None, selected_columns, hints),
+        "branches" => select_branches(repo, selected_columns)?,
+        "diffs" => {
+            return select_diffs(repo, None, selected_columns, hints, is_diffs_cache_enabled);
+        }
+        "diffs_changes" => {
+            return select_diffs_changes(
+                repo,
+                None,
+                selected_columns,
+                hints,
+                is_diffs_cache_enabled,
+            );
+        }
+        "tags" => select_tags(repo, selected_columns)?,
+        "blame" => select_blame(repo, selected_columns, hints)?,
+        "files" => {
+            let head_tree = repo.head_tree().map_err(|error| error.to_string())?;
+            select_tree_files(repo, head_tree, selected_columns)?
+        }
+        _ => vec![Row { values: vec![] }],
+    };
+
+    Ok(Box::new(rows.into_iter().map(Ok)))
+}
+
 fn select_references(
     repo: &gix::Repository,
     selected_columns: &[String],
@@ -109,18 +318,21 @@ fn select_references(
     Ok(rows)
 }
 
-fn select_commits(repo: &gix::Repository, selected_columns: &[String]) -> Result<Vec<Row>, String> {
-    let head_id = repo.head_id();
-    if let Err(error) = head_id {
-        return Err(error.to_string());
-    }
-
+/// Select the commits reachable from the revision or from HEAD if there is no revision
+pub(crate) fn select_commits<'a>(
+    repo: &'a gix::Repository,
+    revision: Option<&str>,
+    selected_columns: &'a [String],
+    hints: &'a ProviderHints,
+) -> Result<RowsIterator<'a>, String> {
     let repo_path = repo.path().to_str().unwrap();
-    let walker = head_id.unwrap().ancestors().all().unwrap();
-    let mut rows: Vec<Row> = vec![];
+    let walker = commits_walker(repo, revision, hints)?;
 
-    for commit_info in walker {
-        let commit_info = commit_info.unwrap();
+    let rows = walker.filter_map(move |commit_info| {
+        let commit_info = match commit_info {
+            Ok(commit_info) => commit_info,
+            Err(error) => return Some(Err(error.to_string())),
+        };
         let commit = repo.find_object(commit_info.id).unwrap().into_commit();
         let commit = commit.decode().unwrap();
 
@@ -191,6 +403,14 @@ fn select_commits(repo: &gix::Repository, selected_columns: &[String]) -> Result
                 continue;
             }
 
+            if column_name == "parent_id" {
+                match commit.parents.first() {
+                    Some(parent_id) => values.push(Box::new(TextValue::new(parent_id.to_string()))),
+                    None => values.push(Box::new(NullValue)),
+                }
+                continue;
+            }
+
             if column_name == "repo" {
                 values.push(Box::new(TextValue::new(repo_path.to_string())));
                 continue;
@@ -199,11 +419,14 @@ fn select_commits(repo: &gix::Repository, selected_columns: &[String]) -> Result
             values.push(Box::new(NullValue));
         }
 
-        let row = Row { values };
-        rows.push(row);
-    }
+        if !hints.is_satisfied_by(selected_columns, &values) {
+            return None;
+        }
 
-    Ok(rows)
+        Some(Ok(Row { values }))
+    });
+
+    Ok(limit_rows(rows, hints))
 }
 
 fn select_branches(
@@ -296,114 +519,414 @@ fn select_branches(
     Ok(rows)
 }
 
-fn select_diffs(repo: &gix::Repository, selected_columns: &[String]) -> Result<Vec<Row>, String> {
-    let repo = {
-        let mut repo = repo.clone();
-        repo.object_cache_size_if_unset(4 * 1024 * 1024);
-        repo
-    };
+/// Diffs columns that need the diff between the commit and it's parent to be calculated
+const DIFFS_CALCULATED_COLUMNS: [&str; 4] =
+    ["insertions", "removals", "files_changed", "diff_changes"];
+
+/// Select the diffs of the commits reachable from the revision or from HEAD if there is no revision
+pub(crate) fn select_diffs<'a>(
+    repo: &'a gix::Repository,
+    revision: Option<&str>,
+    selected_columns: &'a [String],
+    hints: &'a ProviderHints,
+    is_diffs_cache_enabled: bool,
+) -> Result<RowsIterator<'a>, String> {
+    let repo_path = repo.path().to_str().unwrap();
+    let walker = commits_walker(repo, revision, hints)?;
+    let mut commits_info = walker.filter_map(Result::ok);
+
+    let should_calculate_diffs = selected_columns
+        .iter()
+        .any(|col| DIFFS_CALCULATED_COLUMNS.contains(&col.as_str()));
+
+    // Files content is needed only for the diff changes column
+    let with_content = selected_columns.iter().any(|col| col == "diff_changes");
+
+    let thread_safe_repo = repo.clone().into_sync();
+    let mut diffs_cache = is_diffs_cache_enabled.then(|| DiffsCache::open(repo));
+    let mut batch_size = diffs_threads_count();
+    let mut batch_rows: VecDeque<Row> = VecDeque::new();
+
+    let rows = std::iter::from_fn(move || {
+        while batch_rows.is_empty() {
+            let mut batch_values: Vec<Vec<Box<dyn Value>>> = Vec::with_capacity(batch_size);
+            let mut batch_commits: Vec<DiffCommit> = Vec::with_capacity(batch_size);
+
+            for commit_info in commits_info.by_ref() {
+                let commit = commit_info.id().object().unwrap().into_commit();
+                let commit_ref = commit.decode().unwrap();
+                let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+
+                for column_name in selected_columns {
+                    if column_name == "commit_id" {
+                        values.push(Box::new(TextValue::new(commit_info.id.to_string())));
+                        continue;
+                    }
 
-    let mut rewrite_cache = repo
-        .diff_resource_cache(Mode::ToGit, Default::default())
-        .unwrap();
+                    if column_name == "author_name" {
+                        if let Ok(commit_author) = commit.author() {
+                            values.push(Box::new(TextValue::new(commit_author.name.to_string())));
+                            continue;
+                        }
+                        values.push(Box::new(TextValue::empty()));
+                        continue;
+                    }
 
-    let mut diff_cache = rewrite_cache.clone();
+                    if column_name == "author_email" {
+                        if let Ok(commit_author) = commit.author() {
+                            values.push(Box::new(TextValue::new(commit_author.email.to_string())));
+                            continue;
+                        }
+                        values.push(Box::new(TextValue::empty()));
+                        continue;
+                    }
 
-    let should_calculate_diffs = selected_columns.iter().any(|col| {
-        col == "insertions" || col == "removals" || col == "files_changed" || col == "diff_changes"
-    });
+                    if column_name == "datetime" {
+                        let time_stamp = commit_info
+                            .commit_time
+                            .unwrap_or_else(|| commit_ref.time().unwrap().seconds);
+                        values.push(Box::new(DateTimeValue::new(time_stamp)));
+                        continue;
+                    }
 
-    let repo_path = repo.path().to_str().unwrap();
-    let walker = repo.head_id().unwrap().ancestors().all().unwrap();
-    let commits_info = walker.filter_map(Result::ok);
+                    if column_name == "repo" {
+                        values.push(Box::new(TextValue::new(repo_path.to_string())));
+                        continue;
+                    }
 
-    let mut rows: Vec<Row> = vec![];
+                    // Diff columns values are replaced after calculating the diff
+                    values.push(Box::new(NullValue));
+                }
 
-    for commit_info in commits_info.into_iter() {
-        let commit = commit_info.id().object().unwrap().into_commit();
-        let commit_ref = commit.decode().unwrap();
-        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+                // Skip the commit before calculating the diff if the other columns don't satisfy the conditions
+                let is_commit_satisfied =
+                    hints.is_satisfied_by_columns(selected_columns, &values, |column| {
+                        !DIFFS_CALCULATED_COLUMNS.contains(&column)
+                    });
 
-        // Calculate the diff between two commits take time, and  should calculated once per commit
-        let (mut insertions, mut removals, mut files_changed) = (0, 0, 0);
-        let mut diff_changes: Vec<DiffChange> = vec![];
+                if !is_commit_satisfied {
+                    continue;
+                }
 
-        if should_calculate_diffs
-            && let Some(parent) = commit_info
-                .parent_ids()
-                .next()
-                .map(|id| id.object().unwrap().into_commit().tree().unwrap())
-        {
-            let current = commit.tree().unwrap();
-            rewrite_cache.clear_resource_cache_keep_allocation();
-            diff_cache.clear_resource_cache_keep_allocation();
-
-            if let Ok(mut changes) = current.changes() {
-                let _ = changes.for_each_to_obtain_tree_with_cache(
-                    &parent,
-                    &mut rewrite_cache,
-                    |change| {
-                        files_changed += usize::from(change.entry_mode().is_no_tree());
-                        let diff_change =
-                            DiffChange::new_with_content(&change, &mut diff_cache, &repo);
-                        insertions += diff_change.insertions;
-                        removals += diff_change.removals;
-                        diff_changes.push(diff_change);
-                        Ok::<_, Infallible>(std::ops::ControlFlow::Continue(()))
-                    },
-                );
+                batch_commits.push(DiffCommit {
+                    id: commit_info.id,
+                    parent_id: commit_info.parent_ids().next().map(|id| id.detach()),
+                });
+
+                batch_values.push(values);
+                if batch_values.len() >= batch_size {
+                    break;
+                }
             }
-        }
 
-        for column_name in selected_columns {
-            if column_name == "commit_id" {
-                values.push(Box::new(TextValue::new(commit_info.id.to_string())));
-                continue;
+            if batch_values.is_empty() {
+                return None;
             }
 
-            if column_name == "author_name" {
-                if let Ok(commit_author) = commit.author() {
-                    values.push(Box::new(TextValue::new(commit_author.name.to_string())));
-                    continue;
+            // Calculate the diff between two commits take time, so the batch diffs are calculated in parallel,
+            // and the next batch is bigger so queries with small limit don't calculate unused diffs
+            let diffs = if should_calculate_diffs {
+                calculate_cached_commits_diffs(
+                    &thread_safe_repo,
+                    &batch_commits,
+                    with_content,
+                    diffs_cache.as_mut(),
+                )
+            } else {
+                batch_commits
+                    .iter()
+                    .map(|_| CommitDiff::default())
+                    .collect()
+            };
+
+            batch_size = (batch_size * 2).min(MAX_DIFFS_BATCH_SIZE);
+
+            for (mut values, diff) in batch_values.into_iter().zip(diffs) {
+                let mut diff_changes = Some(diff.changes);
+                for (index, column_name) in selected_columns.iter().enumerate() {
+                    if column_name == "insertions" {
+                        values[index] = Box::new(IntValue::new(diff.insertions as i64));
+                        continue;
+                    }
+
+                    if column_name == "removals" {
+                        values[index] = Box::new(IntValue::new(diff.removals as i64));
+                        continue;
+                    }
+
+                    if column_name == "files_changed" {
+                        values[index] = Box::new(IntValue::new(diff.files_changed as i64));
+                        continue;
+                    }
+
+                    if column_name == "diff_changes" {
+                        let changes = diff_changes.take().unwrap_or_default();
+                        values[index] = Box::new(DiffChangesValue::new(changes));
+                        continue;
+                    }
+                }
+
+                if hints.is_satisfied_by(selected_columns, &values) {
+                    batch_rows.push_back(Row { values });
                 }
-                values.push(Box::new(TextValue::empty()));
-                continue;
             }
+        }
 
-            if column_name == "author_email" {
-                if let Ok(commit_author) = commit.author() {
-                    values.push(Box::new(TextValue::new(commit_author.email.to_string())));
+        batch_rows.pop_front().map(Ok)
+    });
+
+    Ok(limit_rows(rows, hints))
+}
+
+/// Diffs changes columns that depend on the changed file
+const DIFFS_CHANGES_FILE_COLUMNS: [&str; 4] = ["insertions", "removals", "mode", "path"];
+
+/// Select the changed files of the commits reachable from the revision or from HEAD if there is no revision
+pub(crate) fn select_diffs_changes<'a>(
+    repo: &'a gix::Repository,
+    revision: Option<&str>,
+    selected_columns: &'a [String],
+    hints: &'a ProviderHints,
+    is_diffs_cache_enabled: bool,
+) -> Result<RowsIterator<'a>, String> {
+    let repo_path = repo.path().to_str().unwrap();
+    let walker = commits_walker(repo, revision, hints)?;
+    let mut commits_info = walker.filter_map(Result::ok);
+
+    let thread_safe_repo = repo.clone().into_sync();
+    let mut diffs_cache = is_diffs_cache_enabled.then(|| DiffsCache::open(repo));
+    let mut batch_size = diffs_threads_count();
+    let mut batch_rows: VecDeque<Row> = VecDeque::new();
+
+    let selected_columns_len = selected_columns.len();
+    let rows = std::iter::from_fn(move || {
+        while batch_rows.is_empty() {
+            let mut batch_values: Vec<Vec<Box<dyn Value>>> = Vec::with_capacity(batch_size);
+            let mut batch_commits: Vec<DiffCommit> = Vec::with_capacity(batch_size);
+
+            for commit_info in commits_info.by_ref() {
+                let commit = commit_info.id().object().unwrap().into_commit();
+                let commit_ref = commit.decode().unwrap();
+
+                let mut commit_values: Vec<Box<dyn Value>> =
+                    Vec::with_capacity(selected_columns_len);
+                for column_name in selected_columns {
+                    if column_name == "commit_id" {
+                        commit_values.push(Box::new(TextValue::new(commit_info.id.to_string())));
+                        continue;
+                    }
+
+                    if column_name == "datetime" {
+                        let time_stamp = commit_info
+                            .commit_time
+                            .unwrap_or_else(|| commit_ref.time().unwrap().seconds);
+                        commit_values.push(Box::new(DateTimeValue::new(time_stamp)));
+                        continue;
+                    }
+
+                    if column_name == "repo" {
+                        commit_values.push(Box::new(TextValue::new(repo_path.to_string())));
+                        continue;
+                    }
+
+                    // File columns values are replaced for each changed file
+                    commit_values.push(Box::new(NullValue));
+                }
+
+                // Skip the commit before calculating the changes if the commit columns don't satisfy the conditions
+                let is_commit_satisfied =
+                    hints.is_satisfied_by_columns(selected_columns, &commit_values, |column| {
+                        !DIFFS_CHANGES_FILE_COLUMNS.contains(&column)
+                    });
+
+                if !is_commit_satisfied {
                     continue;
                 }
-                values.push(Box::new(TextValue::empty()));
-                continue;
-            }
 
-            if column_name == "datetime" {
-                let time_stamp = commit_info
-                    .commit_time
-                    .unwrap_or_else(|| commit_ref.time().unwrap().seconds);
-                values.push(Box::new(DateTimeValue::new(time_stamp)));
-                continue;
-            }
+                batch_commits.push(DiffCommit {
+                    id: commit_info.id,
+                    parent_id: commit_info.parent_ids().next().map(|id| id.detach()),
+                });
 
-            if column_name == "insertions" {
-                values.push(Box::new(IntValue::new(insertions as i64)));
-                continue;
+                batch_values.push(commit_values);
+                if batch_values.len() >= batch_size {
+                    break;
+                }
             }
 
-            if column_name == "removals" {
-                values.push(Box::new(IntValue::new(removals as i64)));
-                continue;
+            if batch_values.is_empty() {
+                return None;
             }
 
-            if column_name == "files_changed" {
-                values.push(Box::new(IntValue::new(files_changed as i64)));
-                continue;
+            // Calculate the changes of the batch commits in parallel, and the next batch is bigger
+            let diffs = calculate_cached_commits_diffs(
+                &thread_safe_repo,
+                &batch_commits,
+                false,
+                diffs_cache.as_mut(),
+            );
+            batch_size = (batch_size * 2).min(MAX_DIFFS_BATCH_SIZE);
+
+            for (commit_values, diff) in batch_values.into_iter().zip(diffs) {
+                for diff_change in diff.changes {
+                    let mut values = commit_values.clone();
+                    for (index, column_name) in selected_columns.iter().enumerate() {
+                        if column_name == "insertions" {
+                            values[index] = Box::new(IntValue::new(diff_change.insertions as i64));
+                            continue;
+                        }
+
+                        if column_name == "removals" {
+                            values[index] = Box::new(IntValue::new(diff_change.removals as i64));
+                            continue;
+                        }
+
+                        if column_name == "mode" {
+                            let mode = diff_change.kind.mode().to_string();
+                            values[index] = Box::new(TextValue::new(mode));
+                            continue;
+                        }
+
+                        if column_name == "path" {
+                            let path = diff_change.location.to_string();
+                            values[index] = Box::new(TextValue::new(path));
+                            continue;
+                        }
+                    }
+
+                    if hints.is_satisfied_by(selected_columns, &values) {
+                        batch_rows.push_back(Row { values });
+                    }
+                }
             }
+        }
 
-            if column_name == "diff_changes" {
-                values.push(Box::new(DiffChangesValue::new(diff_changes.to_owned())));
+        batch_rows.pop_front().map(Ok)
+    });
+
+    Ok(limit_rows(rows, hints))
+}
+
+/// Take only the limit of the hints from rows that already satisfy the hints conditions
+fn limit_rows<'a>(
+    rows: impl Iterator<Item = Result<Row, String>> + 'a,
+    hints: &ProviderHints,
+) -> RowsIterator<'a> {
+    match hints.limit {
+        Some(limit) => Box::new(rows.take(limit)),
+        None => Box::new(rows),
+    }
+}
+
+/// Walk the commits reachable from the revision or starting from HEAD if there is no revision,
+/// if there is a minimum `datetime` in the conditions the commits older than it are not visited like `git log --since`
+fn commits_walker<'repo>(
+    repo: &'repo gix::Repository,
+    revision: Option<&str>,
+    hints: &ProviderHints,
+) -> Result<gix::revision::Walk<'repo>, String> {
+    let mut platform = match revision {
+        Some(revision) => revision_walk_platform(repo, revision)?,
+        None => repo
+            .head_id()
+            .map_err(|error| error.to_string())?
+            .ancestors(),
+    };
+
+    let min_datetime = hints
+        .conditions
+        .iter()
+        .filter(|condition| condition.column == "datetime")
+        .filter(|condition| {
+            matches!(
+                condition.operator,
+                ComparisonOperator::Greater
+                    | ComparisonOperator::GreaterEqual
+                    | ComparisonOperator::Equal
+            )
+        })
+        .filter_map(|condition| condition.value.as_any().downcast_ref::<DateTimeValue>())
+        .map(|datetime| datetime.value)
+        .max();
+
+    if let Some(seconds) = min_datetime {
+        platform = platform.sorting(Sorting::ByCommitTimeCutoff {
+            order: CommitTimeOrder::NewestFirst,
+            seconds,
+        });
+    }
+
+    platform.all().map_err(|error| error.to_string())
+}
+
+/// Create walk platform for the revision like `git log <revision>`, it can be a single revision such as
+/// `main` or `v1.0`, a range such as `v1.0..v2.0` or symmetric difference such as `main...feature`
+fn revision_walk_platform<'repo>(
+    repo: &'repo gix::Repository,
+    revision: &str,
+) -> Result<gix::revision::walk::Platform<'repo>, String> {
+    let spec = repo
+        .rev_parse(revision)
+        .map_err(|error| format!("Invalid revision `{revision}`, {error}"))?;
+
+    let platform = match spec.detach() {
+        Spec::Include(id) => repo.rev_walk([peel_to_commit_id(repo, id)?]),
+        Spec::Range { from, to } => repo
+            .rev_walk([peel_to_commit_id(repo, to)?])
+            .with_hidden([peel_to_commit_id(repo, from)?]),
+        Spec::Merge { theirs, ours } => {
+            let theirs = peel_to_commit_id(repo, theirs)?;
+            let ours = peel_to_commit_id(repo, ours)?;
+            let merge_base = repo
+                .merge_base(theirs, ours)
+                .map_err(|error| format!("Invalid revision `{revision}`, {error}"))?;
+            repo.rev_walk([theirs, ours])
+                .with_hidden([merge_base.detach()])
+        }
+        _ => {
+            return Err(format!(
+                "Unsupported revision `{revision}`, expect revision, `from..to` or `from...to` range"
+            ));
+        }
+    };
+
+    Ok(platform)
+}
+
+/// Return the id of the commit that the object point to, for example the commit of annotated tag
+fn peel_to_commit_id(repo: &gix::Repository, id: gix::ObjectId) -> Result<gix::ObjectId, String> {
+    let object = repo.find_object(id).map_err(|error| error.to_string())?;
+    let commit = object.peel_to_commit().map_err(|error| error.to_string())?;
+    Ok(commit.id)
+}
+
+/// Return the tree of the revision, for example the tree of branch, tag or commit
+pub(crate) fn revision_tree<'repo>(
+    repo: &'repo gix::Repository,
+    revision: &str,
+) -> Result<gix::Tree<'repo>, String> {
+    let id = repo
+        .rev_parse_single(revision)
+        .map_err(|error| format!("Invalid revision `{revision}`, {error}"))?;
+    let object = id.object().map_err(|error| error.to_string())?;
+    object.peel_to_tree().map_err(|error| error.to_string())
+}
+
+fn select_tags(repo: &gix::Repository, selected_columns: &[String]) -> Result<Vec<Row>, String> {
+    let platform = repo.references().unwrap();
+    let tag_names = platform.tags().unwrap();
+    let repo_path = repo.path().to_str().unwrap();
+    let mut rows: Vec<Row> = vec![];
+    for tag_ref in tag_names.flatten() {
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+
+        for column_name in selected_columns {
+            if column_name == "name" {
+                let tag_name = tag_ref
+                    .name()
+                    .category_and_short_name()
+                    .map_or_else(String::default, |(_, short_name)| short_name.to_string());
+                values.push(Box::new(TextValue::new(tag_name)));
                 continue;
             }
 
@@ -422,100 +945,130 @@ fn select_diffs(repo: &gix::Repository, selected_columns: &[String]) -> Result<V
     Ok(rows)
 }
 
-fn select_diffs_changes(
+/// Select the blame lines of the HEAD files, files that don't satisfy the `path` conditions are not blamed
+fn select_blame(
     repo: &gix::Repository,
     selected_columns: &[String],
+    hints: &ProviderHints,
 ) -> Result<Vec<Row>, String> {
-    let repo = {
-        let mut repo = repo.clone();
-        repo.object_cache_size_if_unset(4 * 1024 * 1024);
-        repo
-    };
+    let head_id = repo.head_id();
+    if let Err(error) = head_id {
+        return Err(error.to_string());
+    }
+
+    let head_id = head_id.unwrap().detach();
+    let head_tree = repo.head_tree();
+    if let Err(error) = head_tree {
+        return Err(error.to_string());
+    }
+
+    let files = head_tree.unwrap().traverse().breadthfirst.files();
+    if let Err(error) = files {
+        return Err(error.to_string());
+    }
 
-    let mut rewrite_cache = repo
-        .diff_resource_cache(Mode::ToGit, Default::default())
-        .unwrap();
+    let should_find_authors = selected_columns
+        .iter()
+        .any(|col| col == "author_name" || col == "author_email" || col == "datetime");
 
-    let mut diff_cache = rewrite_cache.clone();
+    // Many lines are blamed on the same commits, so the author info is decoded once per commit
+    let mut authors: HashMap<gix::ObjectId, (String, String, i64)> = HashMap::new();
 
     let repo_path = repo.path().to_str().unwrap();
-    let walker = repo.head_id().unwrap().ancestors().all().unwrap();
-    let commits_info = walker.filter_map(Result::ok);
-
+    let path_column = ["path".to_string()];
     let mut rows: Vec<Row> = vec![];
-    let selected_columns_len = selected_columns.len();
-    for commit_info in commits_info.into_iter() {
-        let commit = commit_info.id().object().unwrap().into_commit();
-        let commit_ref = commit.decode().unwrap();
-
-        if let Some(parent) = commit_info
-            .parent_ids()
-            .next()
-            .map(|id| id.object().unwrap().into_commit().tree().unwrap())
-        {
-            let current = commit.tree().unwrap();
-            rewrite_cache.clear_resource_cache_keep_allocation();
-            diff_cache.clear_resource_cache_keep_allocation();
-
-            if let Ok(mut changes) = current.changes() {
-                let _ = changes.for_each_to_obtain_tree_with_cache(
-                    &parent,
-                    &mut rewrite_cache,
-                    |change| {
-                        let diff_change = DiffChange::new_without_content(&change, &mut diff_cache);
-
-                        let mut values: Vec<Box<dyn Value>> =
-                            Vec::with_capacity(selected_columns_len);
-                        for column_name in selected_columns {
-                            if column_name == "commit_id" {
-                                values.push(Box::new(TextValue::new(commit_info.id.to_string())));
-                                continue;
-                            }
 
-                            if column_name == "insertions" {
-                                values.push(Box::new(IntValue::new(diff_change.insertions as i64)));
-                                continue;
-                            }
+    for entry in files.unwrap() {
+        if !entry.mode.is_blob() {
+            continue;
+        }
 
-                            if column_name == "removals" {
-                                values.push(Box::new(IntValue::new(diff_change.removals as i64)));
-                                continue;
-                            }
+        let path = entry.filepath.to_string();
+        let path_value: Box<dyn Value> = Box::new(TextValue::new(path.to_owned()));
+        if !hints.is_satisfied_by(&path_column, &[path_value]) {
+            continue;
+        }
 
-                            if column_name == "mode" {
-                                let mode = diff_change.kind.mode().to_string();
-                                values.push(Box::new(TextValue::new(mode)));
-                                continue;
-                            }
+        let blame_result = repo.blame_file(entry.filepath.as_ref(), head_id, Default::default());
+        if let Err(error) = blame_result {
+            return Err(error.to_string());
+        }
 
-                            if column_name == "path" {
-                                let path = diff_change.location.to_string();
-                                values.push(Box::new(TextValue::new(path)));
-                                continue;
-                            }
+        let outcome = blame_result.unwrap();
+        for (blame_entry, lines) in outcome.entries_with_lines() {
+            let commit_id = blame_entry.commit_id;
+            if should_find_authors && !authors.contains_key(&commit_id) {
+                let commit = repo
+                    .find_object(commit_id)
+                    .map_err(|error| error.to_string())?
+                    .try_into_commit()
+                    .map_err(|error| error.to_string())?;
+                let author = match commit.author() {
+                    Ok(author) => (
+                        author.name.to_string(),
+                        author.email.to_string(),
+                        author.time().map_or(0, |time| time.seconds),
+                    ),
+                    Err(_) => (String::default(), String::default(), 0),
+                };
+                authors.insert(commit_id, author);
+            }
 
-                            if column_name == "datetime" {
-                                let time_stamp = commit_info
-                                    .commit_time
-                                    .unwrap_or_else(|| commit_ref.time().unwrap().seconds);
-                                values.push(Box::new(DateTimeValue::new(time_stamp)));
-                                continue;
-                            }
+            let first_line_number = blame_entry.start_in_blamed_file as i64 + 1;
+            for (line_index, line) in lines.into_iter().enumerate() {
+                let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+                for column_name in selected_columns {
+                    if column_name == "path" {
+                        values.push(Box::new(TextValue::new(path.to_owned())));
+                        continue;
+                    }
 
-                            if column_name == "repo" {
-                                values.push(Box::new(TextValue::new(repo_path.to_string())));
-                                continue;
-                            }
+                    if column_name == "line_number" {
+                        let line_number = first_line_number + line_index as i64;
+                        values.push(Box::new(IntValue::new(line_number)));
+                        continue;
+                    }
 
-                            values.push(Box::new(NullValue));
-                        }
+                    if column_name == "commit_id" {
+                        values.push(Box::new(TextValue::new(commit_id.to_string())));
+                        continue;
+                    }
+
+                    if column_name == "author_name" {
+                        let (author_name, _, _) = &authors[&commit_id];
+                        values.push(Box::new(TextValue::new(author_name.to_owned())));
+                        continue;
+                    }
+
+                    if column_name == "author_email" {
+                        let (_, author_email, _) = &authors[&commit_id];
+                        values.push(Box::new(TextValue::new(author_email.to_owned())));
+                        continue;
+                    }
+
+                    if column_name == "datetime" {
+                        let (_, _, time_stamp) = authors[&commit_id];
+                        values.push(Box::new(DateTimeValue::new(time_stamp)));
+                        continue;
+                    }
+
+                    if column_name == "line_content" {
+                        let content = line.to_string();
+                        let content = content.trim_end_matches(['\n', '\r']);
+                        values.push(Box::new(TextValue::new(content.to_string())));
+                        continue;
+                    }
+
+                    if column_name == "repo" {
+                        values.push(Box::new(TextValue::new(repo_path.to_string())));
+                        continue;
+                    }
 
-                        let row = Row { values };
-                        rows.push(row);
+                    values.push(Box::new(NullValue));
+                }
 
-                        Ok::<_, Infallible>(std::ops::ControlFlow::Continue(()))
-                    },
-                );
+                let row = Row { values };
+                rows.push(row);
             }
         }
     }
@@ -523,21 +1076,89 @@ fn select_diffs_changes(
     Ok(rows)
 }
 
-fn select_tags(repo: &gix::Repository, selected_columns: &[String]) -> Result<Vec<Row>, String> {
-    let platform = repo.references().unwrap();
-    let tag_names = platform.tags().unwrap();
+/// Select the files of the tree, for example the HEAD tree
+pub(crate) fn select_tree_files(
+    repo: &gix::Repository,
+    tree: gix::Tree<'_>,
+    selected_columns: &[String],
+) -> Result<Vec<Row>, String> {
+    let files = tree.traverse().breadthfirst.files();
+    if let Err(error) = files {
+        return Err(error.to_string());
+    }
+
+    let should_read_content = selected_columns
+        .iter()
+        .any(|col| col == "size" || col == "is_binary" || col == "line_count");
+
     let repo_path = repo.path().to_str().unwrap();
     let mut rows: Vec<Row> = vec![];
-    for tag_ref in tag_names.flatten() {
-        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
 
+    for entry in files.unwrap() {
+        if entry.mode.is_tree() {
+            continue;
+        }
+
+        // Submodules entries point to commits in other repositories so they have no content here
+        let (mut size, mut is_binary, mut line_count) = (0, false, 0);
+        if should_read_content
+            && !entry.mode.is_commit()
+            && let Ok(blob) = repo.find_blob(entry.oid)
+        {
+            size = blob.data.len();
+            // Same heuristic as git, a file is binary if it has a NUL byte in the first 8000 bytes
+            is_binary = blob.data.iter().take(8000).any(|byte| *byte == 0);
+            // Binary files have no lines, so their line count is zero instead of counting new line bytes
+            if !is_binary {
+                line_count = blob.data.lines().count();
+            }
+        }
+
+        let path = entry.filepath.to_string();
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
         for column_name in selected_columns {
+            if column_name == "path" {
+                values.push(Box::new(TextValue::new(path.to_owned())));
+                continue;
+            }
+
             if column_name == "name" {
-                let tag_name = tag_ref
-                    .name()
-                    .category_and_short_name()
-                    .map_or_else(String::default, |(_, short_name)| short_name.to_string());
-                values.push(Box::new(TextValue::new(tag_name)));
+                let name = path.rsplit('/').next().unwrap_or_default();
+                values.push(Box::new(TextValue::new(name.to_string())));
+                continue;
+            }
+
+            if column_name == "extension" {
+                let extension = std::path::Path::new(&path)
+                    .extension()
+                    .map_or_else(String::default, |ext| ext.to_string_lossy().to_string());
+                values.push(Box::new(TextValue::new(extension)));
+                continue;
+            }
+
+            if column_name == "size" {
+                values.push(Box::new(IntValue::new(size as i64)));
+                continue;
+            }
+
+            if column_name == "mode" {
+                let mode = format!("{:06o}", entry.mode.value());
+                values.push(Box::new(TextValue::new(mode)));
+                continue;
+            }
+
+            if column_name == "blob_id" {
+                values.push(Box::new(TextValue::new(entry.oid.to_string())));
+                continue;
+            }
+
+            if column_name == "is_binary" {
+                values.push(Box::new(BoolValue::new(is_binary)));
+                continue;
+            }
+
+            if column_name == "line_count" {
+                values.push(Box::new(IntValue::new(line_count as i64)));
                 continue;
             }
 
@@ -555,3 +1176,189 @@ fn select_tags(repo: &gix::Repository, selected_columns: &[String]) -> Result<Ve
 
     Ok(rows)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gitql::test_utils::TestRepository;
+
+    const DAY: i64 = 24 * 60 * 60;
+    const FIRST_COMMIT_TIME: i64 = 1_700_000_000;
+
+    fn commits_repository(name: &str) -> TestRepository {
+        let repository = TestRepository::new(name);
+        for index in 0..5 {
+            let timestamp = FIRST_COMMIT_TIME + index * DAY;
+            let content = format!("version {index}");
+            repository.commit(
+                "file.txt",
+                content.as_bytes(),
+                &format!("commit {index}"),
+                timestamp,
+            );
+        }
+        repository
+    }
+
+    fn since_hints(timestamp: i64) -> ProviderHints {
+        ProviderHints {
+            conditions: vec![ColumnCondition {
+                column: "datetime".to_string(),
+                operator: ComparisonOperator::GreaterEqual,
+                value: Box::new(DateTimeValue::new(timestamp)),
+            }],
+            limit: None,
+        }
+    }
+
+    /// Select the commits titles with the walk hints and keep the rows satisfying the filter hints
+    fn commits_titles(
+        repo: &gix::Repository,
+        walk_hints: &ProviderHints,
+        filter_hints: &ProviderHints,
+    ) -> Vec<String> {
+        let selected_columns = vec!["title".to_string(), "datetime".to_string()];
+        select_commits(repo, None, &selected_columns, walk_hints)
+            .unwrap()
+            .map(Result::unwrap)
+            .filter(|row| filter_hints.is_satisfied_by(&selected_columns, &row.values))
+            .map(|row| row.values[0].literal())
+            .collect()
+    }
+
+    #[test]
+    fn commits_walk_stops_at_datetime_lower_bound() {
+        let repository = commits_repository("walk-cutoff");
+        let repo = repository.open();
+
+        let hints = since_hints(FIRST_COMMIT_TIME + 3 * DAY);
+        let all_commits = commits_walker(&repo, None, &ProviderHints::default()).unwrap();
+        let since_commits = commits_walker(&repo, None, &hints).unwrap();
+        assert_eq!(all_commits.count(), 5);
+        assert_eq!(since_commits.count(), 2);
+    }
+
+    #[test]
+    fn commits_walk_cutoff_keeps_matching_rows() {
+        let repository = commits_repository("walk-rows");
+        let repo = repository.open();
+
+        let hints = since_hints(FIRST_COMMIT_TIME + 2 * DAY);
+        let walked_rows = commits_titles(&repo, &hints, &hints);
+        let filtered_rows = commits_titles(&repo, &ProviderHints::default(), &hints);
+
+        assert_eq!(walked_rows, vec!["commit 4", "commit 3", "commit 2"]);
+        assert_eq!(walked_rows, filtered_rows);
+    }
+
+    fn text_condition(column: &str, value: &str) -> ProviderHints {
+        ProviderHints {
+            conditions: vec![ColumnCondition {
+                column: column.to_string(),
+                operator: ComparisonOperator::Equal,
+                value: Box::new(TextValue::new(value.to_string())),
+            }],
+            limit: None,
+        }
+    }
+
+    fn rows_literals(rows: Vec<Row>) -> Vec<Vec<String>> {
+        rows.into_iter()
+            .map(|row| row.values.iter().map(|value| value.literal()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn blame_rows_have_line_authorship() {
+        let repository = TestRepository::new("blame-rows");
+        repository.commit("notes.txt", b"first\n", "add first", FIRST_COMMIT_TIME);
+        let repo = repository.open();
+        let first_id = repo.head_id().unwrap().to_string();
+
+        repository.commit(
+            "notes.txt",
+            b"first\nsecond\n",
+            "add second",
+            FIRST_COMMIT_TIME + DAY,
+        );
+        let repo = repository.open();
+        let second_id = repo.head_id().unwrap().to_string();
+
+        let selected_columns: Vec<String> = ["path", "line_number", "commit_id", "line_content"]
+            .map(String::from)
+            .to_vec();
+        let rows = select_blame(&repo, &selected_columns, &ProviderHints::default()).unwrap();
+
+        assert_eq!(
+            rows_literals(rows),
+            vec![
+                vec!["notes.txt", "1", &first_id, "first"],
+                vec!["notes.txt", "2", &second_id, "second"],
+            ]
+        );
+    }
+
+    #[test]
+    fn blame_applies_path_hint_before_blaming() {
+        let repository = TestRepository::new("blame-path");
+        repository.commit("a.txt", b"a\n", "add a", FIRST_COMMIT_TIME);
+        repository.commit("b.txt", b"b\n", "add b", FIRST_COMMIT_TIME + DAY);
+        let repo = repository.open();
+
+        let selected_columns: Vec<String> = ["path", "line_content"].map(String::from).to_vec();
+        let hints = text_condition("path", "b.txt");
+        let rows = select_blame(&repo, &selected_columns, &hints).unwrap();
+
+        assert_eq!(rows_literals(rows), vec![vec!["b.txt", "b"]]);
+    }
+
+    #[test]
+    fn files_rows_describe_head_tree() {
+        let repository = TestRepository::new("files-rows");
+        repository.commit(
+            "src/main.rs",
+            b"fn main() {}\n",
+            "add main",
+            FIRST_COMMIT_TIME,
+        );
+        let repo = repository.open();
+
+        let selected_columns: Vec<String> = [
+            "path",
+            "name",
+            "extension",
+            "size",
+            "is_binary",
+            "line_count",
+        ]
+        .map(String::from)
+        .to_vec();
+        let head_tree = repo.head_tree().unwrap();
+        let rows = select_tree_files(&repo, head_tree, &selected_columns).unwrap();
+
+        assert_eq!(
+            rows_literals(rows),
+            vec![vec!["src/main.rs", "main.rs", "rs", "13", "false", "1"]]
+        );
+    }
+
+    #[test]
+    fn files_line_count_is_zero_for_binary_files() {
+        let repository = TestRepository::new("files-binary");
+        repository.commit(
+            "image.png",
+            b"\x89PNG\n\x00\x01\n\x02\n",
+            "add image",
+            FIRST_COMMIT_TIME,
+        );
+        let repo = repository.open();
+
+        let selected_columns: Vec<String> = ["path", "is_binary", "line_count"]
+            .map(String::from)
+            .to_vec();
+        let head_tree = repo.head_tree().unwrap();
+        let rows = select_tree_files(&repo, head_tree, &selected_columns).unwrap();
+
+        assert_eq!(rows_literals(rows), vec![vec!["image.png", "true", "0"]]);
+    }
+}
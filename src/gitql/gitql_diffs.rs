@@ -0,0 +1,166 @@
+use std::convert::Infallible;
+use std::ops::ControlFlow;
+
+use gix::ObjectId;
+use gix::diff::blob::pipeline::Mode;
+
+use super::gitql_diffs_cache::DiffsCache;
+use super::values::diff_changes::DiffChange;
+
+/// Maximum number of commits that their diffs are calculated together
+pub(crate) const MAX_DIFFS_BATCH_SIZE: usize = 512;
+
+/// Commit that it's diff should be calculated with it's first parent if exists
+#[derive(Clone)]
+pub(crate) struct DiffCommit {
+    pub id: ObjectId,
+    pub parent_id: Option<ObjectId>,
+}
+
+/// The diff between a commit and it's first parent
+#[derive(Clone, Default)]
+pub(crate) struct CommitDiff {
+    pub insertions: u32,
+    pub removals: u32,
+    pub files_changed: usize,
+    pub changes: Vec<DiffChange>,
+}
+
+/// Return the number of threads used to calculate the diffs
+pub(crate) fn diffs_threads_count() -> usize {
+    std::thread::available_parallelism().map_or(1, usize::from)
+}
+
+/// Calculate the diffs of the commits in parallel, each thread has it's own repository handle
+/// and caches, the result diffs are in the same order of the commits
+pub(crate) fn calculate_commits_diffs(
+    repo: &gix::ThreadSafeRepository,
+    commits: &[DiffCommit],
+    with_content: bool,
+) -> Vec<CommitDiff> {
+    if commits.is_empty() {
+        return vec![];
+    }
+
+    let threads_count = diffs_threads_count().min(commits.len());
+    let chunk_size = commits.len().div_ceil(threads_count);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = commits
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut repo = repo.to_thread_local();
+                    repo.object_cache_size_if_unset(4 * 1024 * 1024);
+
+                    let mut rewrite_cache = repo
+                        .diff_resource_cache(Mode::ToGit, Default::default())
+                        .unwrap();
+
+                    let mut diff_cache = rewrite_cache.clone();
+
+                    chunk
+                        .iter()
+                        .map(|commit| {
+                            calculate_commit_diff(
+                                &repo,
+                                commit,
+                                &mut rewrite_cache,
+                                &mut diff_cache,
+                                with_content,
+                            )
+                        })
+                        .collect::<Vec<CommitDiff>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// Calculate the diffs of the commits that are not stored in the cache and store them,
+/// the cache store the diffs without the files content so it's not used if the content is needed
+pub(crate) fn calculate_cached_commits_diffs(
+    repo: &gix::ThreadSafeRepository,
+    commits: &[DiffCommit],
+    with_content: bool,
+    cache: Option<&mut DiffsCache>,
+) -> Vec<CommitDiff> {
+    let Some(cache) = cache.filter(|_| !with_content) else {
+        return calculate_commits_diffs(repo, commits, with_content);
+    };
+
+    let missing_commits: Vec<DiffCommit> = commits
+        .iter()
+        .filter(|commit| cache.get(&commit.id).is_none())
+        .cloned()
+        .collect();
+
+    let mut missing_diffs = calculate_commits_diffs(repo, &missing_commits, false).into_iter();
+    let mut new_diffs: Vec<(ObjectId, CommitDiff)> = Vec::with_capacity(missing_commits.len());
+    let diffs = commits
+        .iter()
+        .map(|commit| match cache.get(&commit.id) {
+            Some(diff) => diff.clone(),
+            None => {
+                let diff = missing_diffs.next().unwrap_or_default();
+                new_diffs.push((commit.id, diff.clone()));
+                diff
+            }
+        })
+        .collect();
+
+    cache.insert(new_diffs);
+    diffs
+}
+
+fn calculate_commit_diff(
+    repo: &gix::Repository,
+    commit: &DiffCommit,
+    rewrite_cache: &mut gix::diff::blob::Platform,
+    diff_cache: &mut gix::diff::blob::Platform,
+    with_content: bool,
+) -> CommitDiff {
+    let mut diff = CommitDiff::default();
+    let Some(parent_id) = commit.parent_id else {
+        return diff;
+    };
+
+    let current = repo
+        .find_object(commit.id)
+        .unwrap()
+        .into_commit()
+        .tree()
+        .unwrap();
+
+    let parent = repo
+        .find_object(parent_id)
+        .unwrap()
+        .into_commit()
+        .tree()
+        .unwrap();
+
+    rewrite_cache.clear_resource_cache_keep_allocation();
+    diff_cache.clear_resource_cache_keep_allocation();
+
+    if let Ok(mut changes) = current.changes() {
+        let _ = changes.for_each_to_obtain_tree_with_cache(&parent, rewrite_cache, |change| {
+            diff.files_changed += usize::from(change.entry_mode().is_no_tree());
+            let diff_change = if with_content {
+                DiffChange::new_with_content(&change, diff_cache, repo)
+            } else {
+                DiffChange::new_without_content(&change, diff_cache)
+            };
+            diff.insertions += diff_change.insertions;
+            diff.removals += diff_change.removals;
+            diff.changes.push(diff_change);
+            Ok::<_, Infallible>(ControlFlow::Continue(()))
+        });
+    }
+
+    diff
+}
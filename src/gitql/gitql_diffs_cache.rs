@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use gix::ObjectId;
+
+use super::gitql_diffs::CommitDiff;
+use super::values::diff_changes::DiffChange;
+use super::values::diff_changes::DiffChangeKind;
+
+const DIFFS_CACHE_DIRECTORY: &str = "gitql-cache";
+const DIFFS_CACHE_FILE: &str = "diffs";
+const DIFFS_CACHE_LOCK_EXTENSION: &str = "lock";
+const DIFFS_CACHE_TEMPORARY_EXTENSION: &str = "tmp";
+const DIFFS_CACHE_STALE_LOCK_DURATION: Duration = Duration::from_secs(60);
+
+/// Persistent cache of the commits diffs stored in the `.git/gitql-cache` directory,
+/// the diff between commit and it's first parent never change so it's calculated once per commit
+///
+/// Each commit is stored as a header line `<commit_id> <insertions> <removals> <files_changed> <changes_count>`
+/// followed by line `<mode> <insertions> <removals> <path>` for each changed file, file content is not stored
+///
+/// Writers hold the `diffs.lock` file while appending or rewriting the cache file,
+/// so concurrent GitQL processes never interleave their records
+pub(crate) struct DiffsCache {
+    file_path: PathBuf,
+    diffs: HashMap<ObjectId, CommitDiff>,
+}
+
+impl DiffsCache {
+    /// Open the cache of the repository and load the stored diffs if exists,
+    /// if the cache file has invalid records it's rewritten with only the valid ones
+    pub(crate) fn open(repo: &gix::Repository) -> Self {
+        let file_path = repo
+            .path()
+            .join(DIFFS_CACHE_DIRECTORY)
+            .join(DIFFS_CACHE_FILE);
+
+        let diffs = match fs::read_to_string(&file_path) {
+            Ok(content) => {
+                let (diffs, is_corrupted) = parse_diffs_cache(&content);
+                if is_corrupted {
+                    compact_diffs_cache(&file_path, &diffs);
+                }
+                diffs
+            }
+            Err(_) => HashMap::new(),
+        };
+
+        DiffsCache { file_path, diffs }
+    }
+
+    pub(crate) fn get(&self, commit_id: &ObjectId) -> Option<&CommitDiff> {
+        self.diffs.get(commit_id)
+    }
+
+    /// Store the new diffs in memory and append them to the cache file,
+    /// failing to write the cache file is ignored because the diffs can be calculated again
+    pub(crate) fn insert(&mut self, diffs: Vec<(ObjectId, CommitDiff)>) {
+        let mut content = String::new();
+        for (commit_id, diff) in diffs {
+            // Paths with new lines can't be stored in the line based format
+            if diff
+                .changes
+                .iter()
+                .any(|change| change.location.contains('\n'))
+            {
+                continue;
+            }
+
+            format_commit_diff(&mut content, &commit_id, &diff);
+            self.diffs.insert(commit_id, diff);
+        }
+
+        if content.is_empty() {
+            return;
+        }
+
+        if let Some(directory) = self.file_path.parent() {
+            let _ = fs::create_dir_all(directory);
+        }
+
+        let Some(_lock) = DiffsCacheLock::acquire(&self.file_path) else {
+            return;
+        };
+
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)
+        {
+            let _ = file.write_all(content.as_bytes());
+        }
+    }
+}
+
+/// Lock file of the diffs cache, removed when dropped
+struct DiffsCacheLock {
+    path: PathBuf,
+}
+
+impl DiffsCacheLock {
+    /// Create the lock file, return None if another process holds a lock that is not stale
+    fn acquire(cache_file_path: &Path) -> Option<Self> {
+        let path = cache_file_path.with_extension(DIFFS_CACHE_LOCK_EXTENSION);
+        for _ in 0..2 {
+            if OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .is_ok()
+            {
+                return Some(DiffsCacheLock { path });
+            }
+
+            // Lock left by a process that exited while writing
+            let is_stale = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|elapsed| elapsed > DIFFS_CACHE_STALE_LOCK_DURATION);
+
+            if !is_stale {
+                return None;
+            }
+
+            let _ = fs::remove_file(&path);
+        }
+        None
+    }
+}
+
+impl Drop for DiffsCacheLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Rewrite the cache file with the valid diffs, written to a temporary file then renamed
+/// so readers see the old or the new content but never a partial one
+fn compact_diffs_cache(file_path: &Path, diffs: &HashMap<ObjectId, CommitDiff>) {
+    let Some(_lock) = DiffsCacheLock::acquire(file_path) else {
+        return;
+    };
+
+    let mut content = String::new();
+    for (commit_id, diff) in diffs {
+        format_commit_diff(&mut content, commit_id, diff);
+    }
+
+    let temporary_path = file_path.with_extension(DIFFS_CACHE_TEMPORARY_EXTENSION);
+    if fs::write(&temporary_path, content).is_err()
+        || fs::rename(&temporary_path, file_path).is_err()
+    {
+        let _ = fs::remove_file(&temporary_path);
+    }
+}
+
+fn format_commit_diff(content: &mut String, commit_id: &ObjectId, diff: &CommitDiff) {
+    content.push_str(&format!(
+        "{} {} {} {} {}\n",
+        commit_id,
+        diff.insertions,
+        diff.removals,
+        diff.files_changed,
+        diff.changes.len()
+    ));
+
+    for change in diff.changes.iter() {
+        content.push_str(&format!(
+            "{} {} {} {}\n",
+            change.kind.mode(),
+            change.insertions,
+            change.removals,
+            change.location
+        ));
+    }
+}
+
+/// Remove the stored diffs cache of the repository if exists
+pub(crate) fn clear_diffs_cache(repo: &gix::Repository) -> Result<(), String> {
+    let directory = repo.path().join(DIFFS_CACHE_DIRECTORY);
+    if !directory.exists() {
+        return Ok(());
+    }
+
+    fs::remove_dir_all(directory).map_err(|error| error.to_string())
+}
+
+/// Parse the diffs cache content, invalid or partially written records are skipped
+/// and the parsing continue from the next header line.
+///
+/// Return the valid diffs and true if any invalid record was found
+fn parse_diffs_cache(content: &str) -> (HashMap<ObjectId, CommitDiff>, bool) {
+    let mut diffs: HashMap<ObjectId, CommitDiff> = HashMap::new();
+    let mut is_corrupted = false;
+
+    // Line without new line at the end is partially written
+    let mut lines = content
+        .split_inclusive('\n')
+        .map(|line| line.strip_suffix('\n'))
+        .peekable();
+
+    while let Some(header) = lines.next() {
+        let Some((commit_id, insertions, removals, files_changed, changes_count)) =
+            header.and_then(parse_commit_header)
+        else {
+            is_corrupted = true;
+            continue;
+        };
+
+        let mut changes: Vec<DiffChange> = Vec::with_capacity(changes_count);
+        while changes.len() < changes_count {
+            // Keep the invalid line, it may be the header of the next record
+            let Some(change) = lines
+                .peek()
+                .and_then(|line| line.and_then(parse_diff_change))
+            else {
+                break;
+            };
+            lines.next();
+            changes.push(change);
+        }
+
+        if changes.len() != changes_count {
+            is_corrupted = true;
+            continue;
+        }
+
+        let diff = CommitDiff {
+            insertions,
+            removals,
+            files_changed,
+            changes,
+        };
+
+        diffs.insert(commit_id, diff);
+    }
+
+    (diffs, is_corrupted)
+}
+
+fn parse_commit_header(line: &str) -> Option<(ObjectId, u32, u32, usize, usize)> {
+    let parts: Vec<&str> = line.split(' ').collect();
+    if parts.len() != 5 {
+        return None;
+    }
+
+    Some((
+        ObjectId::from_str(parts[0]).ok()?,
+        parts[1].parse::<u32>().ok()?,
+        parts[2].parse::<u32>().ok()?,
+        parts[3].parse::<usize>().ok()?,
+        parts[4].parse::<usize>().ok()?,
+    ))
+}
+
+fn parse_diff_change(line: &str) -> Option<DiffChange> {
+    let mut parts = line.splitn(4, ' ');
+    let kind = DiffChangeKind::from_mode(parts.next()?.chars().next()?)?;
+    let insertions = parts.next()?.parse::<u32>().ok()?;
+    let removals = parts.next()?.parse::<u32>().ok()?;
+    let location = parts.next()?.to_string();
+    Some(DiffChange {
+        location,
+        content: vec![],
+        insertions,
+        removals,
+        kind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gitql::test_utils::TestRepository;
+
+    const FIRST_ID: &str = "1111111111111111111111111111111111111111";
+    const SECOND_ID: &str = "2222222222222222222222222222222222222222";
+    const THIRD_ID: &str = "3333333333333333333333333333333333333333";
+
+    fn object_id(id: &str) -> ObjectId {
+        ObjectId::from_str(id).unwrap()
+    }
+
+    fn commit_diff(location: &str) -> CommitDiff {
+        CommitDiff {
+            insertions: 3,
+            removals: 1,
+            files_changed: 1,
+            changes: vec![DiffChange {
+                location: location.to_string(),
+                content: vec![],
+                insertions: 3,
+                removals: 1,
+                kind: DiffChangeKind::Modification,
+            }],
+        }
+    }
+
+    fn cache_file_path(repository: &TestRepository) -> PathBuf {
+        repository
+            .path
+            .join(".git")
+            .join(DIFFS_CACHE_DIRECTORY)
+            .join(DIFFS_CACHE_FILE)
+    }
+
+    #[test]
+    fn parse_skips_invalid_records() {
+        let content = format!(
+            "{FIRST_ID} 3 1 1 1\nM 3 1 src/main.rs\n\
+             invalid header\n\
+             {SECOND_ID} 3 1 1 2\nM 3 1 src/lib.rs\n\
+             {THIRD_ID} 3 1 1 1\nA 3 1 README.md\n"
+        );
+
+        let (diffs, is_corrupted) = parse_diffs_cache(&content);
+        assert!(is_corrupted);
+        assert_eq!(diffs.len(), 2);
+        assert!(diffs.contains_key(&object_id(FIRST_ID)));
+        assert!(!diffs.contains_key(&object_id(SECOND_ID)));
+        assert_eq!(diffs[&object_id(THIRD_ID)].changes[0].location, "README.md");
+    }
+
+    #[test]
+    fn parse_skips_partially_written_record() {
+        let content =
+            format!("{FIRST_ID} 3 1 1 1\nM 3 1 src/main.rs\n{SECOND_ID} 3 1 1 1\nM 3 1 src/li");
+
+        let (diffs, is_corrupted) = parse_diffs_cache(&content);
+        assert!(is_corrupted);
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs.contains_key(&object_id(FIRST_ID)));
+    }
+
+    #[test]
+    fn open_rewrites_corrupted_cache() {
+        let repository = TestRepository::new("diffs-cache-compact");
+        let file_path = cache_file_path(&repository);
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        fs::write(
+            &file_path,
+            format!("{FIRST_ID} 3 1 1 1\nM 3 1 src/main.rs\n{SECOND_ID} 3 1"),
+        )
+        .unwrap();
+
+        let cache = DiffsCache::open(&repository.open());
+        assert!(cache.get(&object_id(FIRST_ID)).is_some());
+
+        let content = fs::read_to_string(&file_path).unwrap();
+        assert_eq!(content, format!("{FIRST_ID} 3 1 1 1\nM 3 1 src/main.rs\n"));
+        assert!(
+            !file_path
+                .with_extension(DIFFS_CACHE_LOCK_EXTENSION)
+                .exists()
+        );
+    }
+
+    #[test]
+    fn insert_is_skipped_while_cache_is_locked() {
+        let repository = TestRepository::new("diffs-cache-lock");
+        let repo = repository.open();
+        let file_path = cache_file_path(&repository);
+        let lock_path = file_path.with_extension(DIFFS_CACHE_LOCK_EXTENSION);
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        fs::write(&lock_path, "").unwrap();
+
+        let mut cache = DiffsCache::open(&repo);
+        cache.insert(vec![(object_id(FIRST_ID), commit_diff("src/main.rs"))]);
+        assert!(cache.get(&object_id(FIRST_ID)).is_some());
+        assert!(!file_path.exists());
+
+        fs::remove_file(&lock_path).unwrap();
+        cache.insert(vec![(object_id(SECOND_ID), commit_diff("src/lib.rs"))]);
+
+        let cache = DiffsCache::open(&repo);
+        assert!(cache.get(&object_id(FIRST_ID)).is_none());
+        assert_eq!(
+            cache.get(&object_id(SECOND_ID)).unwrap().changes[0].location,
+            "src/lib.rs"
+        );
+        assert!(!lock_path.exists());
+    }
+}
@@ -12,7 +12,7 @@ use lineeditor::keybindings::KeyCombination;
 use lineeditor::style::Style;
 use lineeditor::styled_buffer::StyledBuffer;
 
-const GITQL_RESERVED_KEYWORDS: [&str; 57] = [
+const GITQL_RESERVED_KEYWORDS: [&str; 64] = [
     "do",
     "set",
     "select",
@@ -37,6 +37,13 @@ const GITQL_RESERVED_KEYWORDS: [&str; 57] = [
     "glob",
     "describe",
     "show",
+    "explain",
+    "analyze",
+    "create",
+    "function",
+    "returns",
+    "view",
+    "drop",
     "regexp",
     "into",
     "outfile",
@@ -1,99 +1,126 @@
-use std::collections::HashMap;
-use std::sync::OnceLock;
-
-use gitql_ast::types::DataType;
 use gitql_ast::types::boolean::BoolType;
 use gitql_ast::types::datetime::DateTimeType;
 use gitql_ast::types::integer::IntType;
 use gitql_ast::types::text::TextType;
+use gitql_core::schema::ColumnDefinition;
+use gitql_core::schema::TableDefinition;
 
 use crate::gitql::types::diff_changes::DiffChangesType;
 
-pub fn tables_fields_types() -> HashMap<&'static str, Box<dyn DataType>> {
-    let mut map: HashMap<&'static str, Box<dyn DataType>> = HashMap::new();
-    map.insert("commit_id", Box::new(TextType));
-    map.insert("title", Box::new(TextType));
-    map.insert("message", Box::new(TextType));
-    map.insert("name", Box::new(TextType));
-    map.insert("author_name", Box::new(TextType));
-    map.insert("author_email", Box::new(TextType));
-    map.insert("committer_name", Box::new(TextType));
-    map.insert("committer_email", Box::new(TextType));
-    map.insert("full_name", Box::new(TextType));
-    map.insert("insertions", Box::new(IntType));
-    map.insert("removals", Box::new(IntType));
-    map.insert("diff_changes", Box::new(DiffChangesType));
-    map.insert("files_changed", Box::new(IntType));
-    map.insert("type", Box::new(TextType));
-    map.insert("datetime", Box::new(DateTimeType));
-    map.insert("is_head", Box::new(BoolType));
-    map.insert("is_remote", Box::new(BoolType));
-    map.insert("commit_count", Box::new(IntType));
-    map.insert("parents_count", Box::new(IntType));
-    map.insert("updated", Box::new(DateTimeType));
-    map.insert("path", Box::new(TextType));
-    map.insert("mode", Box::new(TextType));
-    map.insert("repo", Box::new(TextType));
-    map
-}
-
-pub fn tables_fields_names() -> &'static HashMap<&'static str, Vec<&'static str>> {
-    static HASHMAP: OnceLock<HashMap<&'static str, Vec<&'static str>>> = OnceLock::new();
-    HASHMAP.get_or_init(|| {
-        let mut map = HashMap::new();
-        map.insert("refs", vec!["name", "full_name", "type", "repo"]);
-        map.insert(
+pub fn tables_definitions() -> Vec<TableDefinition> {
+    vec![
+        TableDefinition::new(
+            "refs",
+            vec![
+                text_column("name", "Reference name"),
+                text_column("full_name", "Reference full name"),
+                text_column("type", "Reference type"),
+                repo_column(),
+            ],
+        ),
+        TableDefinition::new(
             "commits",
             vec![
-                "commit_id",
-                "title",
-                "message",
-                "author_name",
-                "author_email",
-                "committer_name",
-                "committer_email",
-                "datetime",
-                "parents_count",
-                "repo",
+                text_column("commit_id", "Commit id"),
+                text_column("title", "Commit title"),
+                text_column("message", "Commit full message"),
+                text_column("author_name", "Author name"),
+                text_column("author_email", "Author email"),
+                text_column("committer_name", "Committer name"),
+                text_column("committer_email", "Committer email"),
+                datetime_column("datetime", "Commit date time"),
+                int_column("parents_count", "Number of commit parents"),
+                text_column("parent_id", "Commit id of the first parent or Null").nullable(),
+                repo_column(),
             ],
-        );
-        map.insert(
+        ),
+        TableDefinition::new(
             "branches",
             vec![
-                "name",
-                "commit_count",
-                "is_head",
-                "is_remote",
-                "updated",
-                "repo",
+                text_column("name", "Branch name"),
+                int_column("commit_count", "Number of commits in this branch"),
+                bool_column("is_head", "Is the head branch"),
+                bool_column("is_remote", "Is a remote branch"),
+                datetime_column("updated", "Last update Commit date time").nullable(),
+                repo_column(),
             ],
-        );
-        map.insert(
+        ),
+        TableDefinition::new(
             "diffs",
             vec![
-                "commit_id",
-                "author_name",
-                "author_email",
-                "insertions",
-                "removals",
-                "files_changed",
-                "diff_changes",
-                "datetime",
-                "repo",
+                text_column("commit_id", "Commit id"),
+                text_column("author_name", "Author name"),
+                text_column("author_email", "Author email"),
+                int_column("insertions", "Number of inserted lines"),
+                int_column("removals", "Number of deleted lines"),
+                int_column("files_changed", "Number of file changed"),
+                ColumnDefinition::new("diff_changes", Box::new(DiffChangesType))
+                    .with_description("Diff content and info for changes"),
+                datetime_column("datetime", "Commit date time"),
+                repo_column(),
             ],
-        );
-        map.insert(
+        ),
+        TableDefinition::new(
             "diffs_changes",
             vec![
-                "commit_id",
-                "insertions",
-                "removals",
-                "mode",
-                "path",
-                "repo",
+                text_column("commit_id", "Commit id"),
+                int_column("insertions", "Number of inserted lines in one change"),
+                int_column("removals", "Number of deleted lines in one change"),
+                text_column("mode", "Change mode A, D, M, C or R"),
+                text_column("path", "Location of the change"),
+                repo_column(),
             ],
-        );
-        map.insert("tags", vec!["name", "repo"]);
-        map
-    })
+        ),
+        TableDefinition::new("tags", vec![text_column("name", "Tag name"), repo_column()]),
+        TableDefinition::new(
+            "blame",
+            vec![
+                text_column("path", "File path in the HEAD tree"),
+                int_column("line_number", "Line number starting from 1"),
+                text_column("commit_id", "Id of the commit that introduced the line"),
+                text_column("author_name", "Author name"),
+                text_column("author_email", "Author email"),
+                datetime_column("datetime", "Author date time"),
+                text_column("line_content", "Line content"),
+                repo_column(),
+            ],
+        ),
+        TableDefinition::new(
+            "files",
+            vec![
+                text_column("path", "File path in the HEAD tree"),
+                text_column("name", "File name"),
+                text_column("extension", "File extension"),
+                int_column("size", "File size in bytes"),
+                text_column("mode", "File mode in octal"),
+                text_column("blob_id", "Id of the file blob"),
+                bool_column("is_binary", "Is the file content binary"),
+                int_column(
+                    "line_count",
+                    "Number of lines in the file, zero for binary files",
+                ),
+                repo_column(),
+            ],
+        ),
+    ]
+}
+
+fn text_column(name: &str, description: &str) -> ColumnDefinition {
+    ColumnDefinition::new(name, Box::new(TextType)).with_description(description)
+}
+
+fn int_column(name: &str, description: &str) -> ColumnDefinition {
+    ColumnDefinition::new(name, Box::new(IntType)).with_description(description)
+}
+
+fn bool_column(name: &str, description: &str) -> ColumnDefinition {
+    ColumnDefinition::new(name, Box::new(BoolType)).with_description(description)
+}
+
+fn datetime_column(name: &str, description: &str) -> ColumnDefinition {
+    ColumnDefinition::new(name, Box::new(DateTimeType)).with_description(description)
+}
+
+fn repo_column() -> ColumnDefinition {
+    text_column("repo", "Repository full path")
 }
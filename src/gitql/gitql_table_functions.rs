@@ -0,0 +1,195 @@
+use gitql_ast::types::text::TextType;
+use gitql_core::environment::Environment;
+use gitql_core::object::Row;
+use gitql_core::signature::TableSignature;
+use gitql_engine::data_provider::ProviderHints;
+
+use super::gitql_data_provider::revision_tree;
+use super::gitql_data_provider::select_commits;
+use super::gitql_data_provider::select_diffs;
+use super::gitql_data_provider::select_diffs_changes;
+use super::gitql_data_provider::select_tree_files;
+
+/// Register the git table functions that select rows of a revision from each repository
+///
+/// - `commits('main..feature')` or `log('main..feature')` select the commits of revision or range.
+/// - `diffs('v1.0..v2.0')` and `diffs_changes('v1.0..v2.0')` select the diffs of the commits of revision or range.
+/// - `tree('v1.0')` select the files of revision tree with the same columns of `files` table.
+pub(crate) fn register_git_table_functions(
+    env: &mut Environment,
+    repos: &[gix::Repository],
+    is_diffs_cache_enabled: bool,
+) {
+    for function_name in ["commits", "log"] {
+        register_revision_table_function(
+            env,
+            function_name,
+            "commits",
+            repos,
+            |repo, revision, columns| {
+                let hints = ProviderHints::default();
+                select_commits(repo, Some(revision), columns, &hints)?.collect()
+            },
+        );
+    }
+
+    register_revision_table_function(
+        env,
+        "diffs",
+        "diffs",
+        repos,
+        move |repo, revision, columns| {
+            let hints = ProviderHints::default();
+            select_diffs(
+                repo,
+                Some(revision),
+                columns,
+                &hints,
+                is_diffs_cache_enabled,
+            )?
+            .collect()
+        },
+    );
+
+    register_revision_table_function(
+        env,
+        "diffs_changes",
+        "diffs_changes",
+        repos,
+        move |repo, revision, columns| {
+            let hints = ProviderHints::default();
+            select_diffs_changes(
+                repo,
+                Some(revision),
+                columns,
+                &hints,
+                is_diffs_cache_enabled,
+            )?
+            .collect()
+        },
+    );
+
+    register_revision_table_function(env, "tree", "files", repos, |repo, revision, columns| {
+        let tree = revision_tree(repo, revision)?;
+        select_tree_files(repo, tree, columns)
+    });
+}
+
+/// Register table function that take a revision and return rows with the same columns of the schema table
+fn register_revision_table_function(
+    env: &mut Environment,
+    function_name: &'static str,
+    table_name: &str,
+    repos: &[gix::Repository],
+    select_rows: impl Fn(&gix::Repository, &str, &[String]) -> Result<Vec<Row>, String> + 'static,
+) {
+    let Some(table) = env.schema.table(table_name) else {
+        return;
+    };
+
+    let signature = TableSignature::new(vec![Box::new(TextType)], table.columns.clone());
+    let columns_names: Vec<String> = signature
+        .columns
+        .iter()
+        .map(|column| column.name.to_string())
+        .collect();
+
+    let repos = repos.to_vec();
+    env.with_table_closure(function_name, signature, move |inputs| {
+        let revision = inputs[0].as_text().unwrap();
+        let mut rows: Vec<Row> = vec![];
+        for repo in repos.iter() {
+            rows.append(&mut select_rows(repo, &revision, &columns_names)?);
+        }
+        Ok(rows)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gitql::test_utils::TestRepository;
+    use crate::gitql::test_utils::query_rows;
+
+    const FIRST_COMMIT_TIME: i64 = 1_700_000_000;
+
+    #[test]
+    fn log_selects_commits_of_revision() {
+        let repository = TestRepository::new("table-log");
+        repository.commit("a.txt", b"a\n", "add a", FIRST_COMMIT_TIME);
+        repository.commit("b.txt", b"b\n", "add b", FIRST_COMMIT_TIME + 60);
+        repository.commit("c.txt", b"c\n", "add c", FIRST_COMMIT_TIME + 120);
+
+        let rows = query_rows(repository.open(), "SELECT title FROM log('HEAD~1')").unwrap();
+        assert_eq!(rows, vec![vec!["add b"], vec!["add a"]]);
+    }
+
+    #[test]
+    fn tree_selects_files_of_revision() {
+        let repository = TestRepository::new("table-tree");
+        repository.commit("a.txt", b"a\n", "add a", FIRST_COMMIT_TIME);
+        repository.commit("b.txt", b"b\nb\n", "add b", FIRST_COMMIT_TIME + 60);
+
+        let query = "SELECT path, line_count FROM tree('HEAD~1')";
+        assert_eq!(
+            query_rows(repository.open(), query).unwrap(),
+            vec![vec!["a.txt", "1"]]
+        );
+
+        let query = "SELECT path, line_count FROM tree('HEAD')";
+        assert_eq!(
+            query_rows(repository.open(), query).unwrap(),
+            vec![vec!["a.txt", "1"], vec!["b.txt", "2"]]
+        );
+    }
+
+    #[test]
+    fn unknown_revision_is_error() {
+        let repository = TestRepository::new("table-unknown");
+        repository.commit("a.txt", b"a\n", "add a", FIRST_COMMIT_TIME);
+
+        let result = query_rows(repository.open(), "SELECT title FROM log('missing')");
+        assert!(result.is_err());
+    }
+
+    fn tagged_repository(name: &str) -> TestRepository {
+        let repository = TestRepository::new(name);
+        repository.commit("a.txt", b"a\n", "add a", FIRST_COMMIT_TIME);
+        repository.tag("v1.0");
+        repository.commit("b.txt", b"b\n", "add b", FIRST_COMMIT_TIME + 60);
+        repository.commit("c.txt", b"c\nc\n", "add c", FIRST_COMMIT_TIME + 120);
+        repository
+    }
+
+    #[test]
+    fn commits_selects_commits_of_range() {
+        let repository = tagged_repository("table-commits-range");
+
+        let query = "SELECT title FROM commits('v1.0..HEAD')";
+        assert_eq!(
+            query_rows(repository.open(), query).unwrap(),
+            vec![vec!["add c"], vec!["add b"]]
+        );
+
+        let query = "SELECT title FROM commits('HEAD..v1.0')";
+        assert!(query_rows(repository.open(), query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn commits_table_is_not_scoped_by_range() {
+        let repository = tagged_repository("table-commits-table");
+
+        let query = "SELECT title FROM commits";
+        assert_eq!(query_rows(repository.open(), query).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn diffs_changes_selects_changes_of_range() {
+        let repository = tagged_repository("table-diffs-range");
+
+        let query = "SELECT path FROM diffs_changes('v1.0..HEAD')";
+        assert_eq!(
+            query_rows(repository.open(), query).unwrap(),
+            vec![vec!["c.txt"], vec!["b.txt"]]
+        );
+    }
+}
@@ -1,24 +1,38 @@
+use std::fs;
+
+use gitql_ast::query::Query;
 use gitql_core::environment::Environment;
 use gitql_core::schema::Schema;
-use gitql_schema::tables_fields_names;
-use gitql_schema::tables_fields_types;
+use gitql_parser::diagnostic::Diagnostic;
+use gitql_parser::parser;
+use gitql_parser::tokenizer::Tokenizer;
+use gitql_schema::tables_definitions;
 use gitql_std::aggregation::aggregation_function_signatures;
 use gitql_std::aggregation::aggregation_functions;
+use gitql_std::aggregation::ordered_set_aggregation_function_signatures;
+use gitql_std::aggregation::ordered_set_aggregation_functions;
+use gitql_std::table::table_function_signatures;
+use gitql_std::table::table_functions;
 use gitql_std::window::window_function_signatures;
 use gitql_std::window::window_functions;
 
 pub(crate) mod functions;
 pub(crate) mod gitql_data_provider;
+pub(crate) mod gitql_diffs;
+pub(crate) mod gitql_diffs_cache;
 pub(crate) mod gitql_line_editor;
 pub(crate) mod gitql_schema;
+pub(crate) mod gitql_table_functions;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub(crate) mod types;
 pub(crate) mod values;
 
-pub(crate) fn create_gitql_environment() -> Environment {
-    let schema = Schema {
-        tables_fields_names: tables_fields_names().to_owned(),
-        tables_fields_types: tables_fields_types().to_owned(),
-    };
+pub(crate) fn create_gitql_environment(
+    repos: &[gix::Repository],
+    is_diffs_cache_enabled: bool,
+) -> Environment {
+    let schema = Schema::with_tables(tables_definitions());
 
     let std_signatures = functions::gitql_std_signatures();
     let std_functions = functions::gitql_std_functions();
@@ -26,16 +40,78 @@ pub(crate) fn create_gitql_environment() -> Environment {
     let aggregation_signatures = aggregation_function_signatures();
     let aggregation_functions = aggregation_functions();
 
+    let ordered_set_signatures = ordered_set_aggregation_function_signatures();
+    let ordered_set_functions = ordered_set_aggregation_functions();
+
     let window_signatures = window_function_signatures();
     let window_function = window_functions();
 
+    let table_signatures = table_function_signatures();
+    let table_functions = table_functions();
+
     let mut env = Environment::new(schema);
     env.with_standard_functions(&std_signatures, std_functions);
     env.with_aggregation_functions(&aggregation_signatures, aggregation_functions);
+    env.with_ordered_set_aggregation_functions(&ordered_set_signatures, ordered_set_functions);
     env.with_window_functions(&window_signatures, window_function);
+    env.with_table_functions(&table_signatures, table_functions);
+    gitql_table_functions::register_git_table_functions(&mut env, repos, is_diffs_cache_enabled);
     env
 }
 
+pub(crate) fn clear_diffs_caches(repositories: &[gix::Repository]) -> Result<(), String> {
+    for repository in repositories {
+        gitql_diffs_cache::clear_diffs_cache(repository)?;
+    }
+    Ok(())
+}
+
+/// Path of the project file that define the shared views, relative to the repository work directory
+const PROJECT_VIEWS_FILE: &str = ".gitql/views.sql";
+
+/// Define the views of the project views file of each repository if exists,
+/// on error return the file content and the diagnostic to report
+pub(crate) fn define_project_views(
+    repos: &[gix::Repository],
+    env: &mut Environment,
+) -> Result<(), (String, Box<Diagnostic>)> {
+    let mut views_files = vec![];
+    for repo in repos {
+        if let Some(workdir) = repo.workdir() {
+            let views_file = workdir.join(PROJECT_VIEWS_FILE);
+            if views_file.exists() && !views_files.contains(&views_file) {
+                views_files.push(views_file);
+            }
+        }
+    }
+
+    for views_file in views_files {
+        let Ok(content) = fs::read_to_string(&views_file) else {
+            continue;
+        };
+
+        let parser_result =
+            Tokenizer::tokenize(&content).and_then(|tokens| parser::parse_gql(tokens, env));
+        let queries = match parser_result {
+            Ok(queries) => queries,
+            Err(diagnostic) => return Err((content, diagnostic)),
+        };
+
+        if queries
+            .iter()
+            .any(|query| !matches!(query, Query::CreateView(_)))
+        {
+            let message = format!(
+                "Project views file `{}` can only contain `CREATE VIEW` queries",
+                views_file.display()
+            );
+            return Err((String::new(), Diagnostic::error(&message).as_boxed()));
+        }
+    }
+
+    Ok(())
+}
+
 pub(crate) fn validate_git_repositories(
     repositories: &Vec<String>,
 ) -> Result<Vec<gix::Repository>, String> {
@@ -49,3 +125,75 @@ pub(crate) fn validate_git_repositories(
     }
     Ok(git_repositories)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gitql::test_utils::TestRepository;
+    use crate::gitql::test_utils::query_env_rows;
+
+    fn write_views_file(repository: &TestRepository, content: &str) {
+        let views_file = repository.path.join(PROJECT_VIEWS_FILE);
+        fs::create_dir_all(views_file.parent().unwrap()).unwrap();
+        fs::write(views_file, content).unwrap();
+    }
+
+    #[test]
+    fn project_views_are_defined_and_queried() {
+        let repository = TestRepository::new("project-views");
+        repository.commit("a.txt", b"a\n", "add a", 1_700_000_000);
+        repository.commit("b.txt", b"b\n", "fix b", 1_700_000_060);
+        write_views_file(
+            &repository,
+            "CREATE VIEW fixes AS SELECT title FROM commits WHERE title LIKE 'fix%';",
+        );
+
+        let repos = vec![repository.open()];
+        let mut env = create_gitql_environment(&repos, false);
+        assert!(define_project_views(&repos, &mut env).is_ok());
+        assert!(env.is_view("fixes"));
+
+        let rows = query_env_rows(&mut env, repos, "SELECT title FROM fixes").unwrap();
+        assert_eq!(rows, vec![vec!["fix b"]]);
+    }
+
+    #[test]
+    fn project_views_file_without_views_is_ok() {
+        let repository = TestRepository::new("project-views-missing");
+        repository.commit("a.txt", b"a\n", "add a", 1_700_000_000);
+
+        let repos = vec![repository.open()];
+        let mut env = create_gitql_environment(&repos, false);
+        assert!(define_project_views(&repos, &mut env).is_ok());
+        assert!(env.views.is_empty());
+    }
+
+    #[test]
+    fn project_views_file_with_other_queries_is_error() {
+        let repository = TestRepository::new("project-views-select");
+        repository.commit("a.txt", b"a\n", "add a", 1_700_000_000);
+        write_views_file(&repository, "SELECT title FROM commits;");
+
+        let repos = vec![repository.open()];
+        let mut env = create_gitql_environment(&repos, false);
+        let (_, diagnostic) = define_project_views(&repos, &mut env).unwrap_err();
+        assert!(
+            diagnostic
+                .message()
+                .contains("can only contain `CREATE VIEW` queries")
+        );
+    }
+
+    #[test]
+    fn project_views_file_with_invalid_query_is_error() {
+        let repository = TestRepository::new("project-views-invalid");
+        repository.commit("a.txt", b"a\n", "add a", 1_700_000_000);
+        let content = "CREATE VIEW broken AS SELECT missing_column FROM commits;";
+        write_views_file(&repository, content);
+
+        let repos = vec![repository.open()];
+        let mut env = create_gitql_environment(&repos, false);
+        let (source, _) = define_project_views(&repos, &mut env).unwrap_err();
+        assert_eq!(source, content);
+    }
+}
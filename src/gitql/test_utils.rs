@@ -0,0 +1,111 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use gitql_core::environment::Environment;
+use gitql_engine::data_provider::DataProvider;
+use gitql_engine::engine;
+use gitql_engine::engine::EvaluationResult;
+use gitql_parser::parser;
+use gitql_parser::tokenizer::Tokenizer;
+
+use super::create_gitql_environment;
+use super::gitql_data_provider::GitQLDataProvider;
+
+/// Temporary git repository created with the git command line, removed on drop
+pub(crate) struct TestRepository {
+    pub path: PathBuf,
+}
+
+impl TestRepository {
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("gitql-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        let repository = TestRepository { path };
+        repository.git(&["init", "--quiet", "--initial-branch=main"], 0);
+        repository
+    }
+
+    /// Write the file content and commit it with the same author and committer timestamp
+    pub(crate) fn commit(&self, file: &str, content: &[u8], title: &str, timestamp: i64) {
+        let file_path = self.path.join(file);
+        if let Some(parent) = Path::new(&file_path).parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(file_path, content).unwrap();
+        self.git(&["add", "--all"], timestamp);
+        self.git(&["commit", "--quiet", "-m", title], timestamp);
+    }
+
+    pub(crate) fn tag(&self, name: &str) {
+        self.git(&["tag", name], 0);
+    }
+
+    pub(crate) fn open(&self) -> gix::Repository {
+        gix::open(&self.path).unwrap()
+    }
+
+    fn git(&self, args: &[&str], timestamp: i64) {
+        let date = format!("{timestamp} +0000");
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(&self.path)
+            .env("GIT_AUTHOR_NAME", "GitQL")
+            .env("GIT_AUTHOR_EMAIL", "gitql@example.com")
+            .env("GIT_COMMITTER_NAME", "GitQL")
+            .env("GIT_COMMITTER_EMAIL", "gitql@example.com")
+            .env("GIT_AUTHOR_DATE", &date)
+            .env("GIT_COMMITTER_DATE", &date)
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+}
+
+impl Drop for TestRepository {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Parse and evaluate the query on the repository and return the literals of the last selected rows
+pub(crate) fn query_rows(repo: gix::Repository, query: &str) -> Result<Vec<Vec<String>>, String> {
+    let repos = vec![repo];
+    let mut env = create_gitql_environment(&repos, false);
+    query_env_rows(&mut env, repos, query)
+}
+
+/// Parse and evaluate the query in the environment and return the literals of the last selected rows
+pub(crate) fn query_env_rows(
+    env: &mut Environment,
+    repos: Vec<gix::Repository>,
+    query: &str,
+) -> Result<Vec<Vec<String>>, String> {
+    let tokens =
+        Tokenizer::tokenize(query).map_err(|diagnostic| diagnostic.message().to_owned())?;
+    let queries =
+        parser::parse_gql(tokens, env).map_err(|diagnostic| diagnostic.message().to_owned())?;
+
+    let provider: Box<dyn DataProvider> = Box::new(GitQLDataProvider::new(repos));
+    let mut rows = vec![];
+    for evaluation_result in engine::evaluate(env, &provider, queries)? {
+        if let EvaluationResult::SelectedGroups(object) = evaluation_result {
+            rows = object
+                .groups
+                .first()
+                .map(|group| {
+                    group
+                        .rows
+                        .iter()
+                        .map(|row| row.values.iter().map(|value| value.literal()).collect())
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+    }
+    Ok(rows)
+}
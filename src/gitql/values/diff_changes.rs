@@ -45,6 +45,17 @@ impl DiffChangeKind {
         }
     }
 
+    pub fn from_mode(mode: char) -> Option<Self> {
+        match mode {
+            'A' => Some(DiffChangeKind::Addition),
+            'D' => Some(DiffChangeKind::Deletion),
+            'M' => Some(DiffChangeKind::Modification),
+            'R' => Some(DiffChangeKind::Rewrite),
+            'C' => Some(DiffChangeKind::Copy),
+            _ => None,
+        }
+    }
+
     pub fn mode(&self) -> char {
         match self {
             DiffChangeKind::Addition => 'A',
@@ -2,12 +2,15 @@ use std::fs;
 use std::io;
 use std::io::IsTerminal;
 
+use gitql::clear_diffs_caches;
 use gitql::create_gitql_environment;
+use gitql::define_project_views;
 use gitql::gitql_data_provider::GitQLDataProvider;
 use gitql::validate_git_repositories;
 use gitql_cli::arguments;
 use gitql_cli::arguments::Arguments;
 use gitql_cli::arguments::Command;
+use gitql_cli::arguments::DiffsCacheMode;
 use gitql_cli::diagnostic_reporter;
 use gitql_cli::diagnostic_reporter::DiagnosticReporter;
 use gitql_cli::printer::BaseOutputPrinter;
@@ -54,7 +57,9 @@ fn main() {
             }
 
             let repos = git_repos_result.ok().unwrap();
-            let mut env = create_gitql_environment();
+            apply_diffs_cache_mode(&arguments, &repos, &mut reporter);
+            let mut env = create_gitql_environment(&repos, is_diffs_cache_enabled(&arguments));
+            load_project_views(&repos, &mut env, &mut reporter);
             let query =
                 fs::read_to_string(script_file).expect("Should have been able to read the file");
             execute_gitql_query(&query, &arguments, &repos, &mut env, &mut reporter);
@@ -71,7 +76,9 @@ fn main() {
             }
 
             let repos = git_repos_result.ok().unwrap();
-            let mut env = create_gitql_environment();
+            apply_diffs_cache_mode(&arguments, &repos, &mut reporter);
+            let mut env = create_gitql_environment(&repos, is_diffs_cache_enabled(&arguments));
+            load_project_views(&repos, &mut env, &mut reporter);
             execute_gitql_query(&query, &arguments, &repos, &mut env, &mut reporter);
         }
         Command::Help => {
@@ -99,7 +106,10 @@ fn launch_gitql_repl(arguments: &Arguments) {
     }
 
     let git_repositories = git_repos_result.ok().unwrap();
-    let mut global_env = create_gitql_environment();
+    apply_diffs_cache_mode(arguments, &git_repositories, &mut reporter);
+    let mut global_env =
+        create_gitql_environment(&git_repositories, is_diffs_cache_enabled(arguments));
+    load_project_views(&git_repositories, &mut global_env, &mut reporter);
 
     // Launch the right line editor if the flag is enabled
     // Later this line editor will be the default editor
@@ -175,6 +185,39 @@ fn launch_gitql_repl(arguments: &Arguments) {
     }
 }
 
+/// Remove the stored diffs cache of the repositories if the clear mode is used
+fn apply_diffs_cache_mode(
+    arguments: &Arguments,
+    repos: &[gix::Repository],
+    reporter: &mut DiagnosticReporter,
+) {
+    if arguments.diffs_cache != DiffsCacheMode::Clear {
+        return;
+    }
+
+    if let Err(error) = clear_diffs_caches(repos) {
+        reporter.report_diagnostic("", Diagnostic::error(&error));
+        std::process::exit(1);
+    }
+}
+
+/// Return true if the commits diffs should be loaded from and stored in the persistent cache
+fn is_diffs_cache_enabled(arguments: &Arguments) -> bool {
+    arguments.diffs_cache == DiffsCacheMode::Enabled
+}
+
+/// Load the views defined in the project views file of each repository, exit on invalid file
+fn load_project_views(
+    repos: &[gix::Repository],
+    env: &mut Environment,
+    reporter: &mut DiagnosticReporter,
+) {
+    if let Err((content, diagnostic)) = define_project_views(repos, env) {
+        reporter.report_diagnostic(&content, *diagnostic);
+        std::process::exit(1);
+    }
+}
+
 fn execute_gitql_query(
     query: &str,
     arguments: &Arguments,
@@ -206,7 +249,9 @@ fn execute_gitql_query(
     let front_duration = front_start.elapsed();
 
     let engine_start = std::time::Instant::now();
-    let provider: Box<dyn DataProvider> = Box::new(GitQLDataProvider::new(repos.to_vec()));
+    let provider: Box<dyn DataProvider> = Box::new(
+        GitQLDataProvider::new(repos.to_vec()).with_diffs_cache(is_diffs_cache_enabled(arguments)),
+    );
     let evaluation_result = engine::evaluate(env, &provider, query_node);
     let engine_duration = engine_start.elapsed();
 